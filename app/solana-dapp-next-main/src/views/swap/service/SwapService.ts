@@ -11,6 +11,10 @@ import {NATIVE_MINT} from "@solana/spl-token";
 
 const VERBOSE = true;
 const SIMPLE_LP_PROGRAM_ID = "GMDA6SqHUFzctniBczeBSsoLEfd3HaW161wwyAms2buL";
+// the dapp works with the first lp of each SOL/token pair
+const POOL_INDEX = 0;
+// swap fails if user would receive 1% less than the on-chain quote
+const DEFAULT_SLIPPAGE_BPS = 100;
 
 
 export async function initLp(
@@ -21,6 +25,8 @@ export async function initLp(
     wallet: anchor.Wallet,
     connection: anchor.web3.Connection,
   },
+  // null to use the default fee of program config
+  feeBps: number | null = null,
 ): Promise<{tx: string, lpPubKey: PublicKey}> {
   const {wallet, connection} = data;
   const provider = AnchorBrowserClient.getProvider(connection, wallet);
@@ -31,6 +37,7 @@ export async function initLp(
     program,
     quotePubKey,
     rate,
+    feeBps,
     provider.wallet as any,
     true,
     data,
@@ -72,6 +79,7 @@ export async function swap(
     wallet: anchor.Wallet,
     connection: anchor.web3.Connection,
   },
+  slippageBps = DEFAULT_SLIPPAGE_BPS,
 ): Promise<{
   tx: string,
   lpBalances: {quote: number, base: number}
@@ -85,6 +93,7 @@ export async function swap(
     from,
     to,
     amountFrom ? parseFloat(amountFrom) : 0,
+    slippageBps,
     {wallet, connection, provider},
   )
 }
@@ -94,6 +103,7 @@ async function init_new_lp(
   program: Program<SimpleLiquidityPool>,
   quote: anchor.web3.PublicKey,
   rate: string,
+  feeBps: number | null,
   authority: anchor.web3.Keypair,
   logError = true,
   data: {
@@ -104,33 +114,20 @@ async function init_new_lp(
   const {wallet, connection} = data;
 
   const {
-    LP_SEED_PREFIX,
-    LP_LIQUIDITY_PREFIX,
-    LP_FEE_SEED_PREFIX,
     LP_RATE_DECIMAL,
   } = getThisProgramConstants(program);
 
-  const [lpPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_SEED_PREFIX,
-      quote.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpFeePubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_FEE_SEED_PREFIX,
-      quote.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpLiquidityPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_LIQUIDITY_PREFIX,
-      quote.toBuffer(),
-    ],
-    program.programId
-  ))
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpFeePubKey,
+    lpFeeBaseAta,
+    lpFeeQuoteAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, quote);
+  const {configPubKey} = getConfigAccounts(program);
   VERBOSE && console.log('{init_new_lp} liquidityPoolPubKey, FeePubKey: ', {
     lpPubKey: lpPubKey.toString(),
     lpFeePubKey: lpFeePubKey.toString(),
@@ -138,43 +135,26 @@ async function init_new_lp(
   });
 
 
-  const lpLiquidityQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: quote,
-    owner: lpLiquidityPubKey
-    // owner: lpPubKey,
-  });
-  const lpFeeQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: quote,
-    owner: lpFeePubKey
-  });
-
-  console.log('{init_new_lp} a: ', {
-    lp: lpPubKey,
-    tokenQuote: quote,
-    lpLiquidity: lpLiquidityPubKey,
-    lpLiquidityQuoteAta: lpLiquidityQuoteAta,
-    lpFee: lpFeePubKey,
-    lpFeeQuoteAta: lpFeeQuoteAta,
-    user: authority.publicKey,
-    rent: anchor.web3.SYSVAR_RENT_PUBKEY,
-    systemProgram: anchor.web3.SystemProgram.programId,
-    tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
-    associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
-  });
-
   const fixedRate = new BigNumber(rate).toNumber();
-  const tx = await program.methods.initialize(fixedRate * Math.pow(10, LP_RATE_DECIMAL))
+  const tx = await program.methods.initialize(fixedRate * Math.pow(10, LP_RATE_DECIMAL), feeBps, POOL_INDEX)
     .accounts({
       lp: lpPubKey,
+      tokenBase: NATIVE_MINT,
       tokenQuote: quote,
       lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
       lpLiquidityQuoteAta: lpLiquidityQuoteAta,
       lpFee: lpFeePubKey,
+      lpFeeBaseAta: lpFeeBaseAta,
       lpFeeQuoteAta: lpFeeQuoteAta,
+      lpShareMint: lpShareMintPubKey,
+      config: configPubKey,
       user: authority.publicKey,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     })
     .signers([])
@@ -214,40 +194,29 @@ async function add_liquidity_to_exist_lp(
   const {wallet, connection, provider} = data;
 
   const {
-    LP_SEED_PREFIX,
-    LP_LIQUIDITY_PREFIX,
     TOKEN_DECIMAL,
   } = getThisProgramConstants(program);
 
 
-  // const tokenBasePubKey = NATIVE_MINT;  // Sol
-  // const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
-  // const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
   const tokenQuotePubKey = quote;
-  const [lpPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_SEED_PREFIX,
-      // tokenBasePubKey.toBuffer(),
-      tokenQuotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpLiquidityPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_LIQUIDITY_PREFIX,
-      tokenQuotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, tokenQuotePubKey);
+  const {configPubKey} = getConfigAccounts(program);
 
-  const lpLiquidityQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: tokenQuotePubKey,
-    owner: lpLiquidityPubKey,
-  });
   const userQuoteAta = await anchor.utils.token.associatedAddress({
     mint: tokenQuotePubKey,
     owner: wallet.publicKey
   });
+  const userShareAta = await anchor.utils.token.associatedAddress({
+    mint: lpShareMintPubKey,
+    owner: wallet.publicKey
+  });
 
   const lpBalances = {
     before: {quote: 0, base: 0},
@@ -267,16 +236,27 @@ async function add_liquidity_to_exist_lp(
   const tx = await program.methods.addLiquidity(
     new anchor.BN(baseAmount),  // Solana decimal is 9
     new anchor.BN(quoteAmount), // My token
+    true, // pay native SOL, it is wrapped into lp wSOL account
   )
     .accounts({
       lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
       tokenQuote: tokenQuotePubKey,
       lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
       lpLiquidityQuoteAta: lpLiquidityQuoteAta,
+      userBaseAta: program.programId, // base is SOL and wrapped: no user wSOL ATA
       userQuoteAta: userQuoteAta,
+      lpShareMint: lpShareMintPubKey,
+      userShareAta: userShareAta,
+      userWsolTemp: getWsolTempAccount(program, wallet.publicKey),
+      liquidityProvider: getLiquidityProvider(program, lpPubKey, wallet.publicKey),
+      config: configPubKey,
       user: wallet.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     })
     .signers([])
@@ -287,7 +267,7 @@ async function add_liquidity_to_exist_lp(
     });
   console.log('{test___add_liquidity_to_exist_lp} tx: ', tx);
 
-  lpBalances.after.base = await provider.connection.getBalance(lpLiquidityPubKey);
+  lpBalances.after.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
   lpBalances.after.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
 
   return {
@@ -308,6 +288,7 @@ async function swap_token(
   fromPubKey: PublicKey,
   toPubKey: PublicKey,
   fromAmount: number,
+  slippageBps: number,
   data: {
     wallet: anchor.Wallet,
     connection: anchor.web3.Connection,
@@ -318,6 +299,7 @@ async function swap_token(
     fromPubKey,
     toPubKey,
     fromAmount,
+    slippageBps,
   });
   const {wallet, connection, provider} = data;
   const showException = true;
@@ -328,11 +310,7 @@ async function swap_token(
     : [toPubKey, fromPubKey];
 
   const {
-    LP_SEED_PREFIX,
-    LP_LIQUIDITY_PREFIX,
-    LP_FEE_SEED_PREFIX,
-    LP_RATE_DECIMAL,
-    LP_SWAP_FEE_PERMIL,
+    LP_FEE_BPS_DENOMINATOR,
     TOKEN_DECIMAL,
   } = getThisProgramConstants(program);
 
@@ -340,41 +318,36 @@ async function swap_token(
   const fromDecimals = swappingBaseToQuote ? NATIVE_SOL_DECIMAL : TOKEN_DECIMAL;
   // const toDecimals = fromPubKey.equals(NATIVE_MINT) ? TOKEN_DECIMAL: NATIVE_SOL_DECIMAL;
 
-  const [lpPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_SEED_PREFIX,
-      quotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpFeePubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_FEE_SEED_PREFIX,
-      quotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpLiquidityPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_LIQUIDITY_PREFIX,
-      quotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-
-  const lpLiquidityQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: quotePubKey,
-    owner: lpLiquidityPubKey,
-  });
-  const feeAta = await anchor.utils.token.associatedAddress({
-    mint: quotePubKey,
-    owner: lpFeePubKey
-  });
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpFeePubKey,
+    lpFeeBaseAta,
+    lpFeeQuoteAta: feeAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, quotePubKey);
+  const {
+    configPubKey,
+    treasuryPubKey,
+    treasuryBaseAta,
+    treasuryQuoteAta,
+  } = await getTreasuryAccounts(program, quotePubKey);
   const userQuoteAta = await anchor.utils.token.associatedAddress({
     mint: quotePubKey,
     owner: wallet.publicKey
   });
 
+  // min_amount_out: the exact output quoted on-chain minus the slippage tolerance
+  const fromAmountBN = new anchor.BN(fromAmount * Math.pow(10, fromDecimals));
+  const quote = await program.methods.quoteSwap(fromPubKey, toPubKey, fromAmountBN)
+    .accounts({lp: lpPubKey, config: configPubKey})
+    .view();
+  const minAmountOut = quote.toAmountWithoutFee.sub(quote.fee)
+    .muln(LP_FEE_BPS_DENOMINATOR - slippageBps)
+    .divn(LP_FEE_BPS_DENOMINATOR);
+
   const lpBalances = {
     before: {quote: 0, base: 0},
     after: {quote: 0, base: 0},
@@ -392,25 +365,40 @@ async function swap_token(
     lpLiquidityQuoteAta: lpLiquidityQuoteAta.toString(),
     lpBalances,
     lpFeeBalances,
-    fromAmountBN: fromAmount * Math.pow(10, fromDecimals),
+    fromAmountBN: fromAmountBN.toString(),
+    minAmountOut: minAmountOut.toString(),
   });
 
   const tx = await program.methods.swap(
     fromPubKey,
     toPubKey,
-    new anchor.BN(fromAmount * Math.pow(10, fromDecimals)),
+    fromAmountBN,
+    minAmountOut,
+    true, // pay and receive native SOL, lp holds wSOL
   )
     .accounts({
       lp: lpPubKey,
+      tokenBase: basePubKey,
       tokenQuote: quotePubKey,
       lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
       lpLiquidityQuoteAta: lpLiquidityQuoteAta,
       lpFee: lpFeePubKey,
+      lpFeeBaseAta: lpFeeBaseAta,
       lpFeeQuoteAta: feeAta,
+      lpShareMint: lpShareMintPubKey,
+      config: configPubKey,
+      treasury: treasuryPubKey,
+      treasuryBaseAta: treasuryBaseAta,
+      treasuryQuoteAta: treasuryQuoteAta,
+      userBaseAta: program.programId, // base is SOL and wrapped: no user wSOL ATA
       userQuoteAta: userQuoteAta,
+      userWsolTemp: getWsolTempAccount(program, wallet.publicKey),
       user: wallet.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     })
     .signers([])
@@ -421,7 +409,7 @@ async function swap_token(
     });
   VERBOSE && console.log('{test__swap_token} tx: ', tx);
 
-  lpBalances.after.base = await provider.connection.getBalance(lpLiquidityPubKey);
+  lpBalances.after.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
   lpBalances.after.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
 
   console.log('{swap_token} lpBalances: ', lpBalances);
@@ -448,26 +436,16 @@ export async function getLpBalances(
   const program = new anchor.Program(LpIdl, SIMPLE_LP_PROGRAM_ID, provider)
 
   const {
-    LP_LIQUIDITY_PREFIX,
     TOKEN_DECIMAL,
   } = getThisProgramConstants(program);
 
-  const [lpLiquidityPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_LIQUIDITY_PREFIX,
-      quote.toBuffer(),
-    ],
-    program.programId
-  ))
-  const lpLiquidityQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: quote,
-    owner: lpLiquidityPubKey,
-  });
+  const {lpLiquidityBaseAta, lpLiquidityQuoteAta} = await getLpAccounts(program, quote);
 
   let lpBalances_after_base = 0;
   let lpBalances_after_quote = 0;
   try {
-    lpBalances_after_base = await provider.connection.getBalance(lpLiquidityPubKey)
+    // lp holds SOL as wSOL, its lamports also contain rent
+    lpBalances_after_base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
   } catch (e) {
     console.log('{getLpBalances} base e: ', e);
   }
@@ -535,14 +513,7 @@ export async function fetchExistLp(quote: PublicKey, data: {
   injectToWindow(program, anchor, wallet, connection);
 
 
-  const {
-    LP_SEED_PREFIX,
-  } = getThisProgramConstants(program);
-
-  const [lpPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [LP_SEED_PREFIX, quote.toBuffer()],
-    program.programId
-  ))
+  const {lpPubKey} = await getLpAccounts(program, quote);
   try {
     /**
      * NOT WORKING in browser - still investigating
@@ -570,20 +541,111 @@ export async function fetchExistLp(quote: PublicKey, data: {
 }
 
 
+/**
+ * FixedRateLP PDAs and token accounts of the SOL/quote pair
+ * Copied from instructions/utils.test.ts: seeds contain both mints sorted by key and the pool_index
+ */
+async function getLpAccounts(
+  program: Program<SimpleLiquidityPool>,
+  quote: PublicKey,
+  poolIndex = POOL_INDEX,
+) {
+  const {
+    LP_SEED_PREFIX,
+    LP_LIQUIDITY_PREFIX,
+    LP_FEE_SEED_PREFIX,
+    LP_SHARE_MINT_PREFIX,
+  } = getThisProgramConstants(program);
+
+  const base = NATIVE_MINT;
+  const [mintA, mintB] = Buffer.compare(base.toBuffer(), quote.toBuffer()) <= 0 ? [base, quote] : [quote, base];
+  const poolIndexSeed = new anchor.BN(poolIndex).toArrayLike(Buffer, "le", 2);
+  const findPda = (prefix: Buffer) => PublicKey.findProgramAddressSync(
+    [prefix, mintA.toBuffer(), mintB.toBuffer(), poolIndexSeed],
+    program.programId
+  )[0];
+
+  const lpPubKey = findPda(LP_SEED_PREFIX);
+  const lpLiquidityPubKey = findPda(LP_LIQUIDITY_PREFIX);
+  const lpFeePubKey = findPda(LP_FEE_SEED_PREFIX);
+  const lpShareMintPubKey = findPda(LP_SHARE_MINT_PREFIX);
+
+  return {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta: await anchor.utils.token.associatedAddress({mint: base, owner: lpLiquidityPubKey}),
+    lpLiquidityQuoteAta: await anchor.utils.token.associatedAddress({mint: quote, owner: lpLiquidityPubKey}),
+    lpFeePubKey,
+    lpFeeBaseAta: await anchor.utils.token.associatedAddress({mint: base, owner: lpFeePubKey}),
+    lpFeeQuoteAta: await anchor.utils.token.associatedAddress({mint: quote, owner: lpFeePubKey}),
+    lpShareMintPubKey,
+  };
+}
+
+/**
+ * Global program config, it decides the default fee and who can create a lp
+ */
+function getConfigAccounts(program: Program<SimpleLiquidityPool>) {
+  const {CONFIG_SEED_PREFIX} = getThisProgramConstants(program);
+  const [configPubKey] = PublicKey.findProgramAddressSync([CONFIG_SEED_PREFIX], program.programId);
+
+  return {
+    configPubKey,
+  };
+}
+
+/**
+ * Protocol fee of the swap goes to the treasury ATAs of both tokens
+ */
+async function getTreasuryAccounts(program: Program<SimpleLiquidityPool>, quote: PublicKey) {
+  const {TREASURY_SEED_PREFIX} = getThisProgramConstants(program);
+  const [treasuryPubKey] = PublicKey.findProgramAddressSync([TREASURY_SEED_PREFIX], program.programId);
+
+  return {
+    ...getConfigAccounts(program),
+    treasuryPubKey,
+    treasuryBaseAta: await anchor.utils.token.associatedAddress({mint: NATIVE_MINT, owner: treasuryPubKey}),
+    treasuryQuoteAta: await anchor.utils.token.associatedAddress({mint: quote, owner: treasuryPubKey}),
+  };
+}
+
+/**
+ * Temporary wSOL account of user, created and closed by the program when wrapSol is set
+ */
+function getWsolTempAccount(program: Program<SimpleLiquidityPool>, user: PublicKey) {
+  const {LP_WSOL_TEMP_SEED_PREFIX} = getThisProgramConstants(program);
+  return PublicKey.findProgramAddressSync([LP_WSOL_TEMP_SEED_PREFIX, user.toBuffer()], program.programId)[0];
+}
+
+function getLiquidityProvider(program: Program<SimpleLiquidityPool>, lp: PublicKey, user: PublicKey) {
+  const {LP_PROVIDER_SEED_PREFIX} = getThisProgramConstants(program);
+  return PublicKey.findProgramAddressSync([LP_PROVIDER_SEED_PREFIX, lp.toBuffer(), user.toBuffer()], program.programId)[0];
+}
+
 export function getThisProgramConstants(program: Program<SimpleLiquidityPool>) {
   const LP_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_SEED_PREFIX", program)), "utf8");
   const LP_LIQUIDITY_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_LIQUIDITY_PREFIX", program)), "utf8");
   const LP_FEE_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_FEE_SEED_PREFIX", program)), "utf8");
+  const LP_SHARE_MINT_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_SHARE_MINT_PREFIX", program)), "utf8");
+  const LP_PROVIDER_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_PROVIDER_SEED_PREFIX", program)), "utf8");
+  const LP_WSOL_TEMP_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_WSOL_TEMP_SEED_PREFIX", program)), "utf8");
+  const CONFIG_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("CONFIG_SEED_PREFIX", program)), "utf8");
+  const TREASURY_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("TREASURY_SEED_PREFIX", program)), "utf8");
   const LP_RATE_DECIMAL = parseInt(getProgramConstant("LP_RATE_DECIMAL", program));
-  const LP_SWAP_FEE_PERMIL = parseInt(getProgramConstant("LP_SWAP_FEE_PERMIL", program));
+  const LP_FEE_BPS_DENOMINATOR = parseInt(getProgramConstant("LP_FEE_BPS_DENOMINATOR", program));
   const TOKEN_DECIMAL = parseInt(getProgramIdlConstant("TOKEN_DECIMAL", MoveTokenIdl));
 
   return {
     LP_SEED_PREFIX,
     LP_LIQUIDITY_PREFIX,
     LP_FEE_SEED_PREFIX,
+    LP_SHARE_MINT_PREFIX,
+    LP_PROVIDER_SEED_PREFIX,
+    LP_WSOL_TEMP_SEED_PREFIX,
+    CONFIG_SEED_PREFIX,
+    TREASURY_SEED_PREFIX,
     LP_RATE_DECIMAL,
-    LP_SWAP_FEE_PERMIL,
+    LP_FEE_BPS_DENOMINATOR,
     TOKEN_DECIMAL,
   };
 }
@@ -0,0 +1,30 @@
+///
+/// Errors of the pool math, the program maps each of them to its LpBaseError of the same name
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MathError {
+  InvalidRate,
+  InvalidFee,
+  InvalidAmount,
+  InvalidSwapAmount,
+  InvalidSwapToken,
+  InsufficientBaseAmount,
+  InsufficientQuoteAmount,
+  LargeSwapAmount,
+}
+
+impl core::fmt::Display for MathError {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let msg = match self {
+      MathError::InvalidRate => "Fixed rate must be > 0 and fit in RATE_DECIMAL",
+      MathError::InvalidFee => "Swap fee must be <= MAX_FEE_BPS",
+      MathError::InvalidAmount => "Invalid amount",
+      MathError::InvalidSwapAmount => "Swap amount must be > 0 and give a non-zero output",
+      MathError::InvalidSwapToken => "Tokens are not the pair of this pool",
+      MathError::InsufficientBaseAmount => "Not enough base token in the pool",
+      MathError::InsufficientQuoteAmount => "Not enough quote token in the pool",
+      MathError::LargeSwapAmount => "Swap amount is too large",
+    };
+    f.write_str(msg)
+  }
+}
@@ -0,0 +1,367 @@
+use crate::{ceil_div, mul_div_floor, MathError, SwapDir, FEE_BPS_DENOMINATOR, RATE_DECIMAL};
+
+///
+/// What FixedRateLP needs for the math: rate, fee and the usable reserves
+///
+/// Integer math only, rate has RATE_DECIMAL decimals:
+///   BaseToQuote: to_amount = from_amount * rate / 10^RATE_DECIMAL
+///   QuoteToBase: to_amount = from_amount * 10^RATE_DECIMAL / rate
+/// Rounding always favours the pool
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct FixedRatePool {
+  /// 1 base = rate / 10^RATE_DECIMAL quote
+  pub rate: u32,
+  /// Swap fee in basis point, deducted on to_amount
+  pub fee_bps: u16,
+  pub amount_base: u64,
+  pub amount_quote: u64,
+}
+
+///
+/// A swap before it is applied to the reserves
+///   from_amount: from token entering the liquidity
+///   to_amount_without_fee: to token leaving the liquidity
+///   fee: deducted on to_amount_without_fee, user receives to_amount_without_fee - fee
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SwapPreview {
+  pub from_amount: u64,
+  pub to_amount_without_fee: u64,
+  pub fee: u64,
+}
+
+impl SwapPreview {
+  /// What the user receives
+  pub fn to_amount(&self) -> u64 {
+    self.to_amount_without_fee - self.fee
+  }
+}
+
+impl FixedRatePool {
+  pub fn validate_rate(rate: u32) -> Result<(), MathError> {
+    if rate == 0 || rate > 2_u32.pow(32 - RATE_DECIMAL as u32) {
+      return Err(MathError::InvalidRate);
+    }
+
+    Ok(())
+  }
+
+  pub fn validate_fee(fee_bps: u16) -> Result<(), MathError> {
+    crate::validate_fee(fee_bps)
+  }
+
+  /// base_amount + quote_amount converted to base token at the pool rate, rounded down
+  pub fn value_in_base(&self, base_amount: u64, quote_amount: u64) -> u128 {
+    let rate_decimal = 10_u128.pow(RATE_DECIMAL as u32);
+    base_amount as u128 + quote_amount as u128 * rate_decimal / self.rate as u128
+  }
+
+  /// base_amount + quote_amount valued in base and scaled by rate, exact: base * rate + quote * 10^RATE_DECIMAL
+  pub fn scaled_value(&self, base_amount: u64, quote_amount: u64) -> u128 {
+    base_amount as u128 * self.rate as u128 + quote_amount as u128 * 10_u128.pow(RATE_DECIMAL as u32)
+  }
+
+  ///
+  /// Share to mint for a deposit, proportional to the value added:
+  ///   first deposit: share = value
+  ///   next deposits: share = value * share_supply / pool_value
+  /// Rounded down, so the pool never gives more share than deserved
+  ///
+  /// Next deposits use the exact scaled_value, a rounded down pool_value
+  /// would let a deposit dilute the current share holders
+  ///
+  pub fn deposit_share(&self, base_amount: u64, quote_amount: u64, share_supply: u64) -> Result<u64, MathError> {
+    if base_amount == 0 && quote_amount == 0 {
+      return Err(MathError::InvalidAmount);
+    }
+
+    let share = if share_supply == 0 {
+      self.value_in_base(base_amount, quote_amount)
+    } else {
+      let pool_value = self.scaled_value(self.amount_base, self.amount_quote);
+      if pool_value == 0 {
+        return Err(MathError::InvalidAmount);
+      }
+      self.scaled_value(base_amount, quote_amount)
+        .checked_mul(share_supply as u128)
+        .ok_or(MathError::InvalidAmount)?
+        / pool_value
+    };
+
+    match u64::try_from(share) {
+      Ok(share) if share > 0 => Ok(share),
+      _ => Err(MathError::InvalidAmount),
+    }
+  }
+
+  /// Rounded up, fee is never more than to_amount so it always fit in u64
+  pub fn swap_fee(&self, to_amount: u64) -> u64 {
+    ceil_div(to_amount as u128 * self.fee_bps as u128, FEE_BPS_DENOMINATOR as u128) as u64
+  }
+
+  ///
+  /// Swap exactly `from_amount`, to_amount is rounded down and fee is rounded up
+  /// A from_amount too small to buy anything is rejected
+  ///
+  pub fn preview_swap(&self, swap_dir: SwapDir, from_amount: u64) -> Result<SwapPreview, MathError> {
+    if from_amount == 0 {
+      return Err(MathError::InvalidSwapAmount);
+    }
+
+    let rate = self.rate as u128;
+    let rate_decimal = 10_u128.pow(RATE_DECIMAL as u32);
+
+    let to_amount = match swap_dir {
+      SwapDir::BaseToQuote => {
+        let to_amount = mul_div_floor(from_amount, rate, rate_decimal)?;
+        if to_amount > self.amount_quote {
+          return Err(MathError::InsufficientQuoteAmount);
+        }
+        to_amount
+      },
+      SwapDir::QuoteToBase => {
+        let to_amount = mul_div_floor(from_amount, rate_decimal, rate)?;
+        if to_amount > self.amount_base {
+          return Err(MathError::InsufficientBaseAmount);
+        }
+        to_amount
+      }
+    };
+    // dust: user would pay from_amount for nothing
+    if to_amount == 0 {
+      return Err(MathError::InvalidSwapAmount);
+    }
+
+    Ok(SwapPreview {
+      from_amount,
+      to_amount_without_fee: to_amount,
+      fee: self.swap_fee(to_amount),
+    })
+  }
+
+  ///
+  /// Exact output version of preview_swap: user receive exactly `to_amount` after fee
+  ///
+  /// Both the gross output and the input are rounded up, in favour of the pool
+  ///
+  pub fn preview_swap_exact_out(&self, swap_dir: SwapDir, to_amount: u64) -> Result<SwapPreview, MathError> {
+    if to_amount == 0 {
+      return Err(MathError::InvalidSwapAmount);
+    }
+
+    // to_amount = to_amount_without_fee - fee
+    let fee_denominator = FEE_BPS_DENOMINATOR as u128 - self.fee_bps as u128;
+    let to_amount_without_fee = ceil_div(to_amount as u128 * FEE_BPS_DENOMINATOR as u128, fee_denominator);
+
+    let rate = self.rate as u128;
+    let rate_decimal = 10_u128.pow(RATE_DECIMAL as u32);
+    let from_amount = match swap_dir {
+      SwapDir::BaseToQuote => {
+        if to_amount_without_fee > self.amount_quote as u128 {
+          return Err(MathError::InsufficientQuoteAmount);
+        }
+        ceil_div(to_amount_without_fee * rate_decimal, rate)
+      },
+      SwapDir::QuoteToBase => {
+        if to_amount_without_fee > self.amount_base as u128 {
+          return Err(MathError::InsufficientBaseAmount);
+        }
+        ceil_div(to_amount_without_fee * rate, rate_decimal)
+      }
+    };
+
+    let from_amount = u64::try_from(from_amount).map_err(|_| MathError::LargeSwapAmount)?;
+    // to_amount_without_fee <= current reserve so it always fit in u64
+    let to_amount_without_fee = to_amount_without_fee as u64;
+
+    Ok(SwapPreview {
+      from_amount,
+      to_amount_without_fee,
+      fee: to_amount_without_fee - to_amount,
+    })
+  }
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::MAX_FEE_BPS;
+
+  /// 1 base = 10 quote, 5% fee
+  fn pool() -> FixedRatePool {
+    FixedRatePool {
+      rate: 10_000,
+      fee_bps: 500,
+      amount_base: 1_000_000,
+      amount_quote: 10_000_000,
+    }
+  }
+
+  #[test]
+  fn validate_rate_bounds() {
+    assert_eq!(FixedRatePool::validate_rate(0), Err(MathError::InvalidRate));
+    assert_eq!(FixedRatePool::validate_rate(1), Ok(()));
+    assert_eq!(FixedRatePool::validate_rate(2_u32.pow(29)), Ok(()));
+    assert_eq!(FixedRatePool::validate_rate(2_u32.pow(29) + 1), Err(MathError::InvalidRate));
+    assert_eq!(FixedRatePool::validate_rate(u32::MAX), Err(MathError::InvalidRate));
+  }
+
+  #[test]
+  fn validate_fee_bounds() {
+    assert_eq!(FixedRatePool::validate_fee(0), Ok(()));
+    assert_eq!(FixedRatePool::validate_fee(MAX_FEE_BPS), Ok(()));
+    assert_eq!(FixedRatePool::validate_fee(MAX_FEE_BPS + 1), Err(MathError::InvalidFee));
+  }
+
+  #[test]
+  fn swap_fee_rounds_up() {
+    let pool = pool();
+    assert_eq!(pool.swap_fee(0), 0);
+    assert_eq!(pool.swap_fee(1), 1);
+    assert_eq!(pool.swap_fee(20), 1);
+    assert_eq!(pool.swap_fee(21), 2);
+    assert_eq!(pool.swap_fee(10_000), 500);
+    assert_eq!(FixedRatePool { fee_bps: 0, ..pool }.swap_fee(10_000), 0);
+  }
+
+  #[test]
+  fn swap_fee_never_exceeds_amount() {
+    for fee_bps in [0, 1, 30, 500, MAX_FEE_BPS] {
+      let pool = FixedRatePool { fee_bps, ..pool() };
+      for amount in [0, 1, 2, 9, 10, 11, 99, 12_345, u64::MAX] {
+        assert!(pool.swap_fee(amount) <= amount, "fee_bps {} amount {}", fee_bps, amount);
+      }
+    }
+  }
+
+  #[test]
+  fn preview_base_to_quote() {
+    let preview = pool().preview_swap(SwapDir::BaseToQuote, 1_000).unwrap();
+    assert_eq!(preview, SwapPreview { from_amount: 1_000, to_amount_without_fee: 10_000, fee: 500 });
+    assert_eq!(preview.to_amount(), 9_500);
+  }
+
+  #[test]
+  fn preview_quote_to_base() {
+    let preview = pool().preview_swap(SwapDir::QuoteToBase, 10_000).unwrap();
+    assert_eq!(preview, SwapPreview { from_amount: 10_000, to_amount_without_fee: 1_000, fee: 50 });
+  }
+
+  #[test]
+  fn preview_rounds_output_down() {
+    // 1 base = 1.5 quote
+    let pool = FixedRatePool { rate: 1_500, fee_bps: 0, ..pool() };
+    assert_eq!(pool.preview_swap(SwapDir::BaseToQuote, 1).unwrap().to_amount_without_fee, 1);
+    assert_eq!(pool.preview_swap(SwapDir::QuoteToBase, 1), Err(MathError::InvalidSwapAmount));
+    assert_eq!(pool.preview_swap(SwapDir::QuoteToBase, 2).unwrap().to_amount_without_fee, 1);
+    assert_eq!(pool.preview_swap(SwapDir::QuoteToBase, 3).unwrap().to_amount_without_fee, 2);
+  }
+
+  #[test]
+  fn preview_zero_amount() {
+    assert_eq!(pool().preview_swap(SwapDir::BaseToQuote, 0), Err(MathError::InvalidSwapAmount));
+    assert_eq!(pool().preview_swap_exact_out(SwapDir::BaseToQuote, 0), Err(MathError::InvalidSwapAmount));
+  }
+
+  #[test]
+  fn preview_over_liquidity() {
+    let pool = pool();
+    assert_eq!(pool.preview_swap(SwapDir::BaseToQuote, 1_000_000).unwrap().to_amount_without_fee, 10_000_000);
+    assert_eq!(pool.preview_swap(SwapDir::BaseToQuote, 1_000_001), Err(MathError::InsufficientQuoteAmount));
+    assert_eq!(pool.preview_swap(SwapDir::QuoteToBase, 10_000_010), Err(MathError::InsufficientBaseAmount));
+  }
+
+  #[test]
+  fn preview_overflow() {
+    let pool = FixedRatePool { rate: 2_u32.pow(29), amount_quote: u64::MAX, ..pool() };
+    assert_eq!(pool.preview_swap(SwapDir::BaseToQuote, u64::MAX), Err(MathError::LargeSwapAmount));
+  }
+
+  #[test]
+  fn preview_exact_out_base_to_quote() {
+    let preview = pool().preview_swap_exact_out(SwapDir::BaseToQuote, 9_500).unwrap();
+    assert_eq!(preview, SwapPreview { from_amount: 1_000, to_amount_without_fee: 10_000, fee: 500 });
+    assert_eq!(preview.to_amount(), 9_500);
+  }
+
+  #[test]
+  fn preview_exact_out_rounds_in_favour_of_pool() {
+    let pool = pool();
+    for to_amount in 1..2_000 {
+      for swap_dir in [SwapDir::BaseToQuote, SwapDir::QuoteToBase] {
+        let exact_out = pool.preview_swap_exact_out(swap_dir, to_amount).unwrap();
+        assert_eq!(exact_out.to_amount(), to_amount);
+
+        // paying the quoted input with preview_swap gives at least what was asked
+        let exact_in = pool.preview_swap(swap_dir, exact_out.from_amount).unwrap();
+        assert!(exact_in.to_amount() >= to_amount, "{:?} to_amount {}", swap_dir, to_amount);
+      }
+    }
+  }
+
+  #[test]
+  fn preview_exact_out_over_liquidity() {
+    let pool = pool();
+    assert_eq!(pool.preview_swap_exact_out(SwapDir::BaseToQuote, 10_000_000), Err(MathError::InsufficientQuoteAmount));
+    assert_eq!(pool.preview_swap_exact_out(SwapDir::QuoteToBase, 1_000_000), Err(MathError::InsufficientBaseAmount));
+  }
+
+  #[test]
+  fn round_trip_never_profits() {
+    let pool = pool();
+    for from_amount in 1..3_000 {
+      let to_quote = pool.preview_swap(SwapDir::BaseToQuote, from_amount).unwrap().to_amount();
+      if to_quote == 0 {
+        continue;
+      }
+      // too little quote to buy any base back is rejected
+      let back = pool.preview_swap(SwapDir::QuoteToBase, to_quote).map_or(0, |preview| preview.to_amount());
+      assert!(back <= from_amount, "from {} back {}", from_amount, back);
+    }
+  }
+
+  #[test]
+  fn value_in_base_at_rate() {
+    let pool = pool();
+    assert_eq!(pool.value_in_base(1, 0), 1);
+    assert_eq!(pool.value_in_base(0, 10), 1);
+    assert_eq!(pool.value_in_base(0, 9), 0);
+    assert_eq!(pool.value_in_base(5, 50), 10);
+  }
+
+  #[test]
+  fn deposit_share_first_and_next() {
+    let empty = FixedRatePool { amount_base: 0, amount_quote: 0, ..pool() };
+    assert_eq!(empty.deposit_share(1_000, 10_000, 0), Ok(2_000));
+
+    // pool value = 1_000_000 + 10_000_000 / 10 = 2_000_000, supply 2_000_000
+    assert_eq!(pool().deposit_share(1_000, 0, 2_000_000), Ok(1_000));
+    assert_eq!(pool().deposit_share(0, 10_000, 2_000_000), Ok(1_000));
+  }
+
+  #[test]
+  fn deposit_share_never_dilutes() {
+    // 19 quote is worth 1.9 base, a rounded down pool value of 1 would give a whole share for 1 base
+    let pool = FixedRatePool { amount_base: 0, amount_quote: 19, ..pool() };
+    assert_eq!(pool.deposit_share(1, 0, 1), Err(MathError::InvalidAmount));
+    assert_eq!(pool.deposit_share(2, 0, 1), Ok(1));
+  }
+
+  #[test]
+  fn deposit_share_overflow() {
+    let pool = FixedRatePool { rate: 2_u32.pow(29), amount_base: 1, amount_quote: 0, ..pool() };
+    assert_eq!(pool.deposit_share(u64::MAX, 0, u64::MAX), Err(MathError::InvalidAmount));
+  }
+
+  #[test]
+  fn deposit_share_invalid() {
+    let pool = pool();
+    assert_eq!(pool.deposit_share(0, 0, 0), Err(MathError::InvalidAmount));
+    // worth less than one share
+    assert_eq!(pool.deposit_share(0, 9, 0), Err(MathError::InvalidAmount));
+    let drained = FixedRatePool { amount_base: 0, amount_quote: 0, ..pool };
+    assert_eq!(drained.deposit_share(1, 0, 100), Err(MathError::InvalidAmount));
+  }
+}
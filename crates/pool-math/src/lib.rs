@@ -0,0 +1,115 @@
+//!
+//! Rate, fee and swap direction math of FixedRateLP, fee bounds shared with ConstantProductLP
+//!
+//! Plain integers only, no Anchor and no Pubkey, so the program and off-chain quoting
+//! (bots, backends) share the exact same rounding
+//!
+#![no_std]
+
+mod error;
+mod fixed_rate;
+
+pub use error::*;
+pub use fixed_rate::*;
+
+/// Rate has RATE_DECIMAL decimals: 1 base = rate / 10^RATE_DECIMAL quote
+pub const RATE_DECIMAL: u8 = 3;
+/// fee = to_amount * fee_bps / FEE_BPS_DENOMINATOR
+pub const FEE_BPS_DENOMINATOR: u16 = 10_000;
+pub const MAX_FEE_BPS: u16 = 1_000; // 1000/10000 = 10%
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SwapDir {
+  BaseToQuote,
+  QuoteToBase,
+}
+
+///
+/// Direction of a swap from `from` to `to` in a <base, quote> pair, None if they are not the pair
+///
+/// Generic so it works with Pubkey on-chain and any key type off-chain
+///
+pub fn get_swap_dir<K: PartialEq>(base: &K, quote: &K, from: &K, to: &K) -> Option<SwapDir> {
+  if from == base && to == quote {
+    Some(SwapDir::BaseToQuote)
+  } else if from == quote && to == base {
+    Some(SwapDir::QuoteToBase)
+  } else {
+    None
+  }
+}
+
+/// Fee of any lp type is bounded by MAX_FEE_BPS
+pub fn validate_fee(fee_bps: u16) -> Result<(), MathError> {
+  if fee_bps > MAX_FEE_BPS {
+    return Err(MathError::InvalidFee);
+  }
+
+  Ok(())
+}
+
+/// a / b rounded up, b must be > 0
+// u128::div_ceil is too recent for the Solana toolchain
+#[allow(clippy::manual_div_ceil)]
+pub fn ceil_div(a: u128, b: u128) -> u128 {
+  (a + b - 1) / b
+}
+
+/// amount * numerator / denominator, rounded down, LargeSwapAmount if it does not fit in u64
+pub fn mul_div_floor(amount: u64, numerator: u128, denominator: u128) -> Result<u64, MathError> {
+  let result = (amount as u128)
+    .checked_mul(numerator)
+    .ok_or(MathError::LargeSwapAmount)?
+    .checked_div(denominator)
+    .ok_or(MathError::LargeSwapAmount)?;
+
+  u64::try_from(result).map_err(|_| MathError::LargeSwapAmount)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn swap_dir_of_pair() {
+    let (base, quote, other) = (1u8, 2u8, 3u8);
+    assert_eq!(get_swap_dir(&base, &quote, &base, &quote), Some(SwapDir::BaseToQuote));
+    assert_eq!(get_swap_dir(&base, &quote, &quote, &base), Some(SwapDir::QuoteToBase));
+    assert_eq!(get_swap_dir(&base, &quote, &base, &base), None);
+    assert_eq!(get_swap_dir(&base, &quote, &quote, &quote), None);
+    assert_eq!(get_swap_dir(&base, &quote, &other, &quote), None);
+    assert_eq!(get_swap_dir(&base, &quote, &base, &other), None);
+  }
+
+  #[test]
+  fn validate_fee_bounds() {
+    assert_eq!(validate_fee(0), Ok(()));
+    assert_eq!(validate_fee(MAX_FEE_BPS), Ok(()));
+    assert_eq!(validate_fee(MAX_FEE_BPS + 1), Err(MathError::InvalidFee));
+  }
+
+  #[test]
+  fn ceil_div_rounds_up() {
+    assert_eq!(ceil_div(0, 3), 0);
+    assert_eq!(ceil_div(1, 3), 1);
+    assert_eq!(ceil_div(3, 3), 1);
+    assert_eq!(ceil_div(4, 3), 2);
+    assert_eq!(ceil_div(u64::MAX as u128, 1), u64::MAX as u128);
+  }
+
+  #[test]
+  fn mul_div_floor_rounds_down() {
+    assert_eq!(mul_div_floor(10, 1, 3), Ok(3));
+    assert_eq!(mul_div_floor(10, 2, 3), Ok(6));
+    assert_eq!(mul_div_floor(0, 7, 3), Ok(0));
+    assert_eq!(mul_div_floor(u64::MAX, 1, 1), Ok(u64::MAX));
+  }
+
+  #[test]
+  fn mul_div_floor_overflow() {
+    assert_eq!(mul_div_floor(u64::MAX, 2, 1), Err(MathError::LargeSwapAmount));
+    assert_eq!(mul_div_floor(1, 1, 0), Err(MathError::LargeSwapAmount));
+    assert_eq!(mul_div_floor(u64::MAX, u128::MAX, 1), Err(MathError::LargeSwapAmount));
+  }
+}
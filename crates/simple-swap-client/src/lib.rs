@@ -0,0 +1,21 @@
+//!
+//! Rust client of simple-liquidity-pool and move-token
+//!
+//! Instructions are built from the programs' own `accounts` / `instruction` structs (cpi feature),
+//! so a renamed account or argument breaks the build here instead of on-chain
+//!
+//! ```ignore
+//! let keys = FixedRateLpKeys::new(base, quote, token::ID, token::ID, 0);
+//! let ix = liquidity_pool::swap(&keys, &user, base, quote, 1_000, 9_000, false);
+//! ```
+//!
+// Deserializers return anchor_lang::Result like the programs do
+#![allow(clippy::result_large_err)]
+
+pub mod pda;
+pub mod state;
+pub mod liquidity_pool;
+pub mod move_token;
+
+pub use pda::FixedRateLpKeys;
+pub use pool_math;
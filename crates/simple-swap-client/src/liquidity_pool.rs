@@ -0,0 +1,140 @@
+use anchor_lang::{
+  prelude::Pubkey,
+  solana_program::{instruction::Instruction, system_program, sysvar},
+  InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token, token};
+use simple_liquidity_pool::{accounts, instruction};
+use crate::pda::{find_config, find_treasury, find_wsol_temp, FixedRateLpKeys};
+
+//
+// simple-liquidity-pool instructions, accounts are derived from FixedRateLpKeys
+// so the caller only passes what the program cannot derive
+//
+
+///
+/// Create a new FixedRateLP of keys.token_base/keys.token_quote at keys.pool_index
+///
+/// user: becomes the lp authority, must be allowed by ProgramConfig::require_can_create_pool
+/// fee_bps: None to use the default fee of program config
+///
+pub fn initialize(keys: &FixedRateLpKeys, user: &Pubkey, fixed_rate: u32, fee_bps: Option<u16>) -> Instruction {
+  let accounts = accounts::LpInit {
+    lp: keys.lp,
+    token_base: keys.token_base,
+    token_quote: keys.token_quote,
+    lp_liquidity: keys.lp_liquidity,
+    lp_liquidity_base_ata: keys.lp_liquidity_base_ata,
+    lp_liquidity_quote_ata: keys.lp_liquidity_quote_ata,
+    lp_fee: keys.lp_fee,
+    lp_fee_base_ata: keys.lp_fee_base_ata,
+    lp_fee_quote_ata: keys.lp_fee_quote_ata,
+    lp_share_mint: keys.lp_share_mint,
+    config: find_config().0,
+    user: *user,
+    rent: sysvar::rent::ID,
+    system_program: system_program::ID,
+    token_program: token::ID,
+    token_base_program: keys.token_base_program,
+    token_quote_program: keys.token_quote_program,
+    associated_token_program: associated_token::ID,
+  };
+
+  Instruction {
+    program_id: simple_liquidity_pool::ID,
+    accounts: accounts.to_account_metas(None),
+    data: instruction::Initialize { fixed_rate, fee_bps, pool_index: keys.pool_index }.data(),
+  }
+}
+
+///
+/// Deposit base and/or quote, user receives lp share
+///
+/// wrap_sol: pay native SOL instead of wSOL, the lp must have native SOL as base or quote
+///
+pub fn add_liquidity(
+  keys: &FixedRateLpKeys,
+  user: &Pubkey,
+  base_amount: u64,
+  quote_amount: u64,
+  wrap_sol: bool,
+) -> Instruction {
+  let (user_base_ata, user_quote_ata) = keys.user_atas_unless_wrapped(user, wrap_sol);
+  let accounts = accounts::LpAddLiquidity {
+    lp: keys.lp,
+    token_base: keys.token_base,
+    token_quote: keys.token_quote,
+    lp_liquidity: keys.lp_liquidity,
+    lp_liquidity_base_ata: keys.lp_liquidity_base_ata,
+    lp_liquidity_quote_ata: keys.lp_liquidity_quote_ata,
+    user_base_ata,
+    user_quote_ata,
+    lp_share_mint: keys.lp_share_mint,
+    user_share_ata: keys.user_share_ata(user),
+    user_wsol_temp: find_wsol_temp(user).0,
+    liquidity_provider: keys.liquidity_provider(user),
+    config: find_config().0,
+    user: *user,
+    system_program: system_program::ID,
+    token_program: token::ID,
+    token_base_program: keys.token_base_program,
+    token_quote_program: keys.token_quote_program,
+    associated_token_program: associated_token::ID,
+  };
+
+  Instruction {
+    program_id: simple_liquidity_pool::ID,
+    accounts: accounts.to_account_metas(None),
+    data: instruction::AddLiquidity { base_amount, quote_amount, wrap_sol }.data(),
+  }
+}
+
+///
+/// Swap exactly from_amount of `from` into `to`, fail if user would receive less than min_amount_out
+///
+/// from, to: token_base and token_quote of the lp, in either order
+/// wrap_sol: pay and receive native SOL instead of wSOL
+///
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+  keys: &FixedRateLpKeys,
+  user: &Pubkey,
+  from: Pubkey,
+  to: Pubkey,
+  from_amount: u64,
+  min_amount_out: u64,
+  wrap_sol: bool,
+) -> Instruction {
+  let (user_base_ata, user_quote_ata) = keys.user_atas_unless_wrapped(user, wrap_sol);
+  let accounts = accounts::LpSwap {
+    lp: keys.lp,
+    token_base: keys.token_base,
+    token_quote: keys.token_quote,
+    lp_liquidity: keys.lp_liquidity,
+    lp_liquidity_base_ata: keys.lp_liquidity_base_ata,
+    lp_liquidity_quote_ata: keys.lp_liquidity_quote_ata,
+    lp_fee: keys.lp_fee,
+    lp_fee_base_ata: keys.lp_fee_base_ata,
+    lp_fee_quote_ata: keys.lp_fee_quote_ata,
+    lp_share_mint: keys.lp_share_mint,
+    config: find_config().0,
+    treasury: find_treasury().0,
+    treasury_base_ata: keys.treasury_base_ata(),
+    treasury_quote_ata: keys.treasury_quote_ata(),
+    user_base_ata,
+    user_quote_ata,
+    user_wsol_temp: find_wsol_temp(user).0,
+    user: *user,
+    system_program: system_program::ID,
+    token_program: token::ID,
+    token_base_program: keys.token_base_program,
+    token_quote_program: keys.token_quote_program,
+    associated_token_program: associated_token::ID,
+  };
+
+  Instruction {
+    program_id: simple_liquidity_pool::ID,
+    accounts: accounts.to_account_metas(None),
+    data: instruction::Swap { from, to, from_amount, min_amount_out, wrap_sol }.data(),
+  }
+}
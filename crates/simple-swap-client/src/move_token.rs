@@ -0,0 +1,74 @@
+use anchor_lang::{
+  prelude::Pubkey,
+  solana_program::{instruction::Instruction, system_program, sysvar},
+};
+use anchor_lang_move_token::{InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token, token};
+use move_token::{accounts, instruction};
+use crate::pda::{ata, find_metadata_account, find_mint_authority};
+
+//
+// move-token instructions, the mint authority bump is derived here
+//
+
+///
+/// Create `mint` with its Metaplex metadata and mint initial_supply to the payer ATA
+///
+/// mint: a new keypair, it must sign the transaction too
+///
+pub fn create_token(
+  payer: &Pubkey,
+  mint: &Pubkey,
+  metadata_title: String,
+  metadata_symbol: String,
+  metadata_uri: String,
+  initial_supply: u64,
+) -> Instruction {
+  let (mint_authority, mint_authority_pda_bump) = find_mint_authority(mint);
+  let accounts = accounts::CreateTokenMint {
+    mint_account: *mint,
+    mint_authority,
+    payer_ata: ata(payer, mint, &token::ID),
+    payer: *payer,
+    rent: sysvar::rent::ID,
+    metadata_account: find_metadata_account(mint).0,
+    system_program: system_program::ID,
+    token_program: token::ID,
+    token_metadata_program: mpl_token_metadata::ID,
+    associated_token_program: associated_token::ID,
+  };
+
+  Instruction {
+    program_id: move_token::ID,
+    accounts: accounts.to_account_metas(None),
+    data: instruction::CreateToken {
+      metadata_title,
+      metadata_symbol,
+      metadata_uri,
+      initial_supply,
+      mint_authority_pda_bump,
+    }.data(),
+  }
+}
+
+/// Airdrop `amount` of `mint` to recipient, its ATA is created if needed
+pub fn mint_to_another_wallet(payer: &Pubkey, mint: &Pubkey, recipient: &Pubkey, amount: u64) -> Instruction {
+  let (mint_authority, mint_authority_pda_bump) = find_mint_authority(mint);
+  let accounts = accounts::MintToAnotherWallet {
+    mint_account: *mint,
+    mint_authority,
+    recipient: *recipient,
+    recipient_ata: ata(recipient, mint, &token::ID),
+    payer: *payer,
+    rent: sysvar::rent::ID,
+    system_program: system_program::ID,
+    token_program: token::ID,
+    associated_token_program: associated_token::ID,
+  };
+
+  Instruction {
+    program_id: move_token::ID,
+    accounts: accounts.to_account_metas(None),
+    data: instruction::MintToAnotherWallet { amount, mint_authority_pda_bump }.data(),
+  }
+}
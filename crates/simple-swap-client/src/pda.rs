@@ -0,0 +1,265 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use simple_liquidity_pool::state::{
+  FixedRateLP, CONFIG_SEED_PREFIX, LP_FEE_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_PROVIDER_SEED_PREFIX,
+  LP_SEED_PREFIX, LP_SHARE_MINT_PREFIX, LP_WSOL_TEMP_SEED_PREFIX, TREASURY_SEED_PREFIX,
+};
+use move_token::instructions::MINT_AUTH_SEED_PREFIX;
+
+//
+// Same seeds as the #[account(seeds = ...)] constraints of the programs
+// Every lp PDA uses the mints in canonical order + pool_index, see FixedRateLP::sorted_mints
+//
+
+fn find_lp_pda(prefix: &[u8], token_base: &Pubkey, token_quote: &Pubkey, pool_index: u16) -> (Pubkey, u8) {
+  let (mint_a, mint_b) = FixedRateLP::sorted_mints(*token_base, *token_quote);
+  Pubkey::find_program_address(
+    &[prefix, mint_a.as_ref(), mint_b.as_ref(), pool_index.to_le_bytes().as_ref()],
+    &simple_liquidity_pool::ID,
+  )
+}
+
+/// FixedRateLP state account
+pub fn find_lp(token_base: &Pubkey, token_quote: &Pubkey, pool_index: u16) -> (Pubkey, u8) {
+  find_lp_pda(LP_SEED_PREFIX, token_base, token_quote, pool_index)
+}
+
+/// Owner of the lp liquidity token accounts
+pub fn find_lp_liquidity(token_base: &Pubkey, token_quote: &Pubkey, pool_index: u16) -> (Pubkey, u8) {
+  find_lp_pda(LP_LIQUIDITY_PREFIX, token_base, token_quote, pool_index)
+}
+
+/// Owner of the lp fee token accounts
+pub fn find_lp_fee(token_base: &Pubkey, token_quote: &Pubkey, pool_index: u16) -> (Pubkey, u8) {
+  find_lp_pda(LP_FEE_SEED_PREFIX, token_base, token_quote, pool_index)
+}
+
+pub fn find_lp_share_mint(token_base: &Pubkey, token_quote: &Pubkey, pool_index: u16) -> (Pubkey, u8) {
+  find_lp_pda(LP_SHARE_MINT_PREFIX, token_base, token_quote, pool_index)
+}
+
+/// LiquidityProvider of `user` in `lp`
+pub fn find_liquidity_provider(lp: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(
+    &[LP_PROVIDER_SEED_PREFIX, lp.as_ref(), user.as_ref()],
+    &simple_liquidity_pool::ID,
+  )
+}
+
+/// Temporary wSOL account used by wrap_sol
+pub fn find_wsol_temp(user: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[LP_WSOL_TEMP_SEED_PREFIX, user.as_ref()], &simple_liquidity_pool::ID)
+}
+
+pub fn find_config() -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[CONFIG_SEED_PREFIX], &simple_liquidity_pool::ID)
+}
+
+pub fn find_treasury() -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[TREASURY_SEED_PREFIX], &simple_liquidity_pool::ID)
+}
+
+/// move-token mint authority of `mint`
+pub fn find_mint_authority(mint: &Pubkey) -> (Pubkey, u8) {
+  Pubkey::find_program_address(&[MINT_AUTH_SEED_PREFIX, mint.as_ref()], &move_token::ID)
+}
+
+/// Metaplex metadata account of `mint`
+pub fn find_metadata_account(mint: &Pubkey) -> (Pubkey, u8) {
+  mpl_token_metadata::pda::find_metadata_account(mint)
+}
+
+/// ATA of `owner`, token_program is the one owning `mint`: SPL token or Token-2022
+pub fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+  get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+
+///
+/// Every account of one FixedRateLP, same as getLpAccounts in the TS tests
+///
+/// token_*_program: the program owning each mint, anchor_spl::token::ID or anchor_spl::token_2022::ID
+/// The share mint is always a legacy SPL token
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedRateLpKeys {
+  pub token_base: Pubkey,
+  pub token_quote: Pubkey,
+  pub token_base_program: Pubkey,
+  pub token_quote_program: Pubkey,
+  pub pool_index: u16,
+
+  pub lp: Pubkey,
+  pub lp_liquidity: Pubkey,
+  pub lp_liquidity_base_ata: Pubkey,
+  pub lp_liquidity_quote_ata: Pubkey,
+  pub lp_fee: Pubkey,
+  pub lp_fee_base_ata: Pubkey,
+  pub lp_fee_quote_ata: Pubkey,
+  pub lp_share_mint: Pubkey,
+}
+
+impl FixedRateLpKeys {
+  pub fn new(
+    token_base: Pubkey,
+    token_quote: Pubkey,
+    token_base_program: Pubkey,
+    token_quote_program: Pubkey,
+    pool_index: u16,
+  ) -> Self {
+    let (lp, _) = find_lp(&token_base, &token_quote, pool_index);
+    let (lp_liquidity, _) = find_lp_liquidity(&token_base, &token_quote, pool_index);
+    let (lp_fee, _) = find_lp_fee(&token_base, &token_quote, pool_index);
+    let (lp_share_mint, _) = find_lp_share_mint(&token_base, &token_quote, pool_index);
+
+    Self {
+      token_base,
+      token_quote,
+      token_base_program,
+      token_quote_program,
+      pool_index,
+      lp,
+      lp_liquidity,
+      lp_liquidity_base_ata: ata(&lp_liquidity, &token_base, &token_base_program),
+      lp_liquidity_quote_ata: ata(&lp_liquidity, &token_quote, &token_quote_program),
+      lp_fee,
+      lp_fee_base_ata: ata(&lp_fee, &token_base, &token_base_program),
+      lp_fee_quote_ata: ata(&lp_fee, &token_quote, &token_quote_program),
+      lp_share_mint,
+    }
+  }
+
+  /// Keys of an existing lp, read from its FixedRateLP account
+  pub fn from_lp(lp: &FixedRateLP, token_base_program: Pubkey, token_quote_program: Pubkey) -> Self {
+    Self::new(lp.token_base, lp.token_quote, token_base_program, token_quote_program, lp.pool_index)
+  }
+
+  pub fn user_base_ata(&self, user: &Pubkey) -> Pubkey {
+    ata(user, &self.token_base, &self.token_base_program)
+  }
+
+  pub fn user_quote_ata(&self, user: &Pubkey) -> Pubkey {
+    ata(user, &self.token_quote, &self.token_quote_program)
+  }
+
+  /// User base/quote ATAs for add_liquidity and swap: the native SOL side is left out when wrap_sol is set
+  pub fn user_atas_unless_wrapped(&self, user: &Pubkey, wrap_sol: bool) -> (Option<Pubkey>, Option<Pubkey>) {
+    let unless_wrapped = |mint: &Pubkey, ata: Pubkey| (!wrap_sol || *mint != anchor_spl::token::spl_token::native_mint::ID).then_some(ata);
+    (
+      unless_wrapped(&self.token_base, self.user_base_ata(user)),
+      unless_wrapped(&self.token_quote, self.user_quote_ata(user)),
+    )
+  }
+
+  pub fn user_share_ata(&self, user: &Pubkey) -> Pubkey {
+    ata(user, &self.lp_share_mint, &anchor_spl::token::ID)
+  }
+
+  pub fn liquidity_provider(&self, user: &Pubkey) -> Pubkey {
+    find_liquidity_provider(&self.lp, user).0
+  }
+
+  pub fn treasury_base_ata(&self) -> Pubkey {
+    ata(&find_treasury().0, &self.token_base, &self.token_base_program)
+  }
+
+  pub fn treasury_quote_ata(&self) -> Pubkey {
+    ata(&find_treasury().0, &self.token_quote, &self.token_quote_program)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use anchor_spl::{associated_token, token, token::spl_token::native_mint, token_2022};
+
+  /// Seeds written out as in the program constraints, without going through FixedRateLP::sorted_mints
+  fn expected_lp_pda(prefix: &[u8], mint_x: &Pubkey, mint_y: &Pubkey, pool_index: u16) -> Pubkey {
+    let (mint_a, mint_b) = if mint_x.to_bytes() <= mint_y.to_bytes() { (mint_x, mint_y) } else { (mint_y, mint_x) };
+    Pubkey::find_program_address(
+      &[prefix, &mint_a.to_bytes(), &mint_b.to_bytes(), &pool_index.to_le_bytes()],
+      &simple_liquidity_pool::ID,
+    ).0
+  }
+
+  /// ATA derivation of the associated token program: [owner, token_program, mint]
+  fn expected_ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+      &[&owner.to_bytes(), &token_program.to_bytes(), &mint.to_bytes()],
+      &associated_token::ID,
+    ).0
+  }
+
+  #[test]
+  fn lp_pdas_match_program_seeds() {
+    let (token_base, token_quote) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let keys = FixedRateLpKeys::new(token_base, token_quote, token::ID, token::ID, 3);
+
+    assert_eq!(keys.lp, expected_lp_pda(LP_SEED_PREFIX, &token_base, &token_quote, 3));
+    assert_eq!(keys.lp_liquidity, expected_lp_pda(LP_LIQUIDITY_PREFIX, &token_base, &token_quote, 3));
+    assert_eq!(keys.lp_fee, expected_lp_pda(LP_FEE_SEED_PREFIX, &token_base, &token_quote, 3));
+    assert_eq!(keys.lp_share_mint, expected_lp_pda(LP_SHARE_MINT_PREFIX, &token_base, &token_quote, 3));
+  }
+
+  #[test]
+  fn lp_pdas_do_not_depend_on_mint_order() {
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let keys = FixedRateLpKeys::new(mint_x, mint_y, token::ID, token::ID, 0);
+    let swapped = FixedRateLpKeys::new(mint_y, mint_x, token::ID, token::ID, 0);
+
+    assert_eq!(keys.lp, swapped.lp);
+    assert_eq!(keys.lp_liquidity, swapped.lp_liquidity);
+    assert_eq!(keys.lp_fee, swapped.lp_fee);
+    assert_eq!(keys.lp_share_mint, swapped.lp_share_mint);
+    // base/quote ATAs follow the given order
+    assert_eq!(keys.lp_liquidity_base_ata, swapped.lp_liquidity_quote_ata);
+    assert_eq!(keys.lp_fee_quote_ata, swapped.lp_fee_base_ata);
+  }
+
+  #[test]
+  fn pool_index_gives_another_lp() {
+    let (token_base, token_quote) = (native_mint::ID, Pubkey::new_unique());
+    let first = FixedRateLpKeys::new(token_base, token_quote, token::ID, token::ID, 0);
+    let second = FixedRateLpKeys::new(token_base, token_quote, token::ID, token::ID, 1);
+    // 2 bytes little endian: 256 is not 1
+    let third = FixedRateLpKeys::new(token_base, token_quote, token::ID, token::ID, 256);
+
+    assert_ne!(first.lp, second.lp);
+    assert_ne!(second.lp, third.lp);
+    assert_ne!(first.lp_share_mint, second.lp_share_mint);
+    assert_eq!(third.lp, expected_lp_pda(LP_SEED_PREFIX, &token_base, &token_quote, 256));
+  }
+
+  #[test]
+  fn atas_use_the_token_program_of_each_mint() {
+    let (token_base, token_quote) = (native_mint::ID, Pubkey::new_unique());
+    let keys = FixedRateLpKeys::new(token_base, token_quote, token::ID, token_2022::ID, 0);
+    let user = Pubkey::new_unique();
+
+    assert_eq!(keys.lp_liquidity_base_ata, expected_ata(&keys.lp_liquidity, &token_base, &token::ID));
+    assert_eq!(keys.lp_liquidity_quote_ata, expected_ata(&keys.lp_liquidity, &token_quote, &token_2022::ID));
+    assert_eq!(keys.lp_fee_base_ata, expected_ata(&keys.lp_fee, &token_base, &token::ID));
+    assert_eq!(keys.lp_fee_quote_ata, expected_ata(&keys.lp_fee, &token_quote, &token_2022::ID));
+    assert_eq!(keys.user_base_ata(&user), expected_ata(&user, &token_base, &token::ID));
+    assert_eq!(keys.user_quote_ata(&user), expected_ata(&user, &token_quote, &token_2022::ID));
+    // share mint is always a legacy SPL token
+    assert_eq!(keys.user_share_ata(&user), expected_ata(&user, &keys.lp_share_mint, &token::ID));
+
+    // same quote mint under the legacy program is another account
+    let legacy = FixedRateLpKeys::new(token_base, token_quote, token::ID, token::ID, 0);
+    assert_eq!(legacy.lp, keys.lp);
+    assert_ne!(legacy.lp_liquidity_quote_ata, keys.lp_liquidity_quote_ata);
+  }
+
+  #[test]
+  fn wrapped_sol_side_has_no_user_ata() {
+    let keys = FixedRateLpKeys::new(native_mint::ID, Pubkey::new_unique(), token::ID, token::ID, 0);
+    let user = Pubkey::new_unique();
+
+    assert_eq!(keys.user_atas_unless_wrapped(&user, true), (None, Some(keys.user_quote_ata(&user))));
+    assert_eq!(
+      keys.user_atas_unless_wrapped(&user, false),
+      (Some(keys.user_base_ata(&user)), Some(keys.user_quote_ata(&user)))
+    );
+  }
+}
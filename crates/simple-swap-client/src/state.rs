@@ -0,0 +1,19 @@
+use anchor_lang::{AccountDeserialize, Result};
+use simple_liquidity_pool::state::{FixedRateLP, LiquidityProvider, ProgramConfig};
+
+//
+// Account data as returned by get_account, discriminator included
+// Fails if the data is not the expected account type
+//
+
+pub fn fixed_rate_lp(data: &[u8]) -> Result<FixedRateLP> {
+  FixedRateLP::try_deserialize(&mut &data[..])
+}
+
+pub fn liquidity_provider(data: &[u8]) -> Result<LiquidityProvider> {
+  LiquidityProvider::try_deserialize(&mut &data[..])
+}
+
+pub fn program_config(data: &[u8]) -> Result<ProgramConfig> {
+  ProgramConfig::try_deserialize(&mut &data[..])
+}
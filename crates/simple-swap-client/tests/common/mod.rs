@@ -0,0 +1,227 @@
+#![allow(dead_code)]
+
+use anchor_lang::{
+  solana_program::{program_pack::Pack, system_instruction},
+  InstructionData, ToAccountMetas,
+};
+use anchor_spl::token::spl_token;
+use simple_liquidity_pool::state::{errors::LpBaseError, FixedRateLP};
+use simple_swap_client::{
+  liquidity_pool,
+  pda::{ata, find_config, find_treasury},
+  state, FixedRateLpKeys,
+};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+  account::Account,
+  bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+  instruction::{Instruction, InstructionError},
+  pubkey::Pubkey,
+  rent::Rent,
+  signature::{Keypair, Signer},
+  transaction::{Transaction, TransactionError},
+};
+
+/// 1 base = 10 quote, see LP_RATE_DECIMAL
+pub const RATE: u32 = 10_000;
+/// 0.3%
+pub const FEE_BPS: u16 = 30;
+/// 1/6 of the swap fee goes to treasury
+pub const PROTOCOL_FEE_SHARE_BPS: u16 = 1667;
+pub const DECIMALS: u8 = 9;
+/// Minted to the payer on both base and quote
+pub const USER_BALANCE: u64 = 1_000_000_000_000;
+
+///
+/// Both programs loaded from target/deploy, built by cargo test-sbf
+/// SPL token and associated token programs are built into the bank
+///
+/// simple-liquidity-pool is deployed upgradeable like on a real cluster,
+/// init_config checks its upgrade authority
+///
+pub fn program_test() -> ProgramTest {
+  let mut program_test = ProgramTest::default();
+  program_test.prefer_bpf(true);
+  add_upgradeable_program(&mut program_test, "simple_liquidity_pool", simple_liquidity_pool::ID);
+  program_test.add_program("move_token", move_token::ID, None);
+
+  program_test
+}
+
+/// Start the bank, ctx.payer is the simple-liquidity-pool upgrade authority
+pub async fn start() -> ProgramTestContext {
+  let mut ctx = program_test().start_with_context().await;
+
+  // the payer is only known once the bank is started
+  let payer = ctx.payer.pubkey();
+  set_upgrade_authority(&mut ctx, &simple_liquidity_pool::ID, &payer).await;
+
+  ctx
+}
+
+/// ProgramTest::add_program uses the non-upgradeable loader, which has no program data account
+fn add_upgradeable_program(program_test: &mut ProgramTest, program_name: &str, program_id: Pubkey) {
+  let out_dir = std::env::var("SBF_OUT_DIR")
+    .unwrap_or_else(|_| concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy").to_string());
+  let elf = std::fs::read(format!("{out_dir}/{program_name}.so")).unwrap();
+
+  let rent = Rent::default();
+  let program_data = get_program_data_address(&program_id);
+
+  let mut program = Account::new_data(
+    rent.minimum_balance(UpgradeableLoaderState::size_of_program()),
+    &UpgradeableLoaderState::Program { programdata_address: program_data },
+    &bpf_loader_upgradeable::id(),
+  ).unwrap();
+  program.executable = true;
+
+  let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+  let mut data = Account::new_data_with_space(
+    rent.minimum_balance(metadata_len + elf.len()),
+    &UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: None },
+    metadata_len + elf.len(),
+    &bpf_loader_upgradeable::id(),
+  ).unwrap();
+  data.data[metadata_len..].copy_from_slice(&elf);
+
+  program_test.add_account(program_id, program);
+  program_test.add_account(program_data, data);
+}
+
+/// ProgramData account of an upgradeable program, solana-sdk 1.16 has no helper for it
+pub fn get_program_data_address(program_id: &Pubkey) -> Pubkey {
+  Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// Rewrite the program data header of an add_upgradeable_program program, the ELF is kept
+pub async fn set_upgrade_authority(ctx: &mut ProgramTestContext, program_id: &Pubkey, authority: &Pubkey) {
+  let program_data = get_program_data_address(program_id);
+  let mut account = ctx.banks_client.get_account(program_data).await.unwrap().unwrap();
+  account.serialize_data(&UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: Some(*authority) }).unwrap();
+
+  ctx.set_account(&program_data, &account.into());
+}
+
+/// Send `instructions` paid by ctx.payer, signers are added to the payer
+pub async fn process(
+  ctx: &mut ProgramTestContext,
+  instructions: &[Instruction],
+  signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+  // a fresh blockhash, so the same instructions can be sent twice
+  let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+
+  let mut all_signers = vec![&ctx.payer];
+  all_signers.extend_from_slice(signers);
+  let tx = Transaction::new_signed_with_payer(instructions, Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+
+  ctx.banks_client.process_transaction(tx).await
+}
+
+/// The first instruction failed with this LpBaseError
+pub fn assert_lp_error(result: Result<(), BanksClientError>, expected: LpBaseError) {
+  assert_custom_error(result, expected.into());
+}
+
+pub fn assert_custom_error(result: Result<(), BanksClientError>, expected: u32) {
+  match result.expect_err("tx should fail").unwrap() {
+    TransactionError::InstructionError(_, InstructionError::Custom(code)) => assert_eq!(code, expected),
+    e => panic!("expected custom error {}, got {:?}", expected, e),
+  }
+}
+
+pub async fn create_mint(ctx: &mut ProgramTestContext, authority: &Pubkey, decimals: u8) -> Pubkey {
+  let mint = Keypair::new();
+  create_mint_with_keypair(ctx, &mint, authority, decimals).await;
+
+  mint.pubkey()
+}
+
+/// For a mint whose address must be known before the bank starts
+pub async fn create_mint_with_keypair(ctx: &mut ProgramTestContext, mint: &Keypair, authority: &Pubkey, decimals: u8) {
+  let rent = ctx.banks_client.get_rent().await.unwrap();
+  let payer = ctx.payer.pubkey();
+
+  process(ctx, &[
+    system_instruction::create_account(
+      &payer,
+      &mint.pubkey(),
+      rent.minimum_balance(spl_token::state::Mint::LEN),
+      spl_token::state::Mint::LEN as u64,
+      &spl_token::ID,
+    ),
+    spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), authority, None, decimals).unwrap(),
+  ], &[mint]).await.unwrap();
+}
+
+/// ATA of owner, funded with `amount` minted by ctx.payer
+pub async fn create_funded_ata(ctx: &mut ProgramTestContext, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+  let payer = ctx.payer.pubkey();
+  let owner_ata = ata(owner, mint, &spl_token::ID);
+
+  process(ctx, &[
+    spl_associated_token_account::instruction::create_associated_token_account(&payer, owner, mint, &spl_token::ID),
+    spl_token::instruction::mint_to(&spl_token::ID, mint, &owner_ata, &payer, &[], amount).unwrap(),
+  ], &[]).await.unwrap();
+
+  owner_ata
+}
+
+pub async fn token_balance(ctx: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+  let account = ctx.banks_client.get_account(*token_account).await.unwrap().expect("token account should exist");
+  spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+pub async fn fetch_lp(ctx: &mut ProgramTestContext, keys: &FixedRateLpKeys) -> FixedRateLP {
+  let account = ctx.banks_client.get_account(keys.lp).await.unwrap().expect("lp should exist");
+  state::fixed_rate_lp(&account.data).unwrap()
+}
+
+/// init_config is an admin setup step, the client does not ship a builder for it
+/// admin must be the program upgrade authority, see start()
+pub fn init_config(admin: &Pubkey, protocol_fee_share_bps: u16, default_fee_bps: u16) -> Instruction {
+  let accounts = simple_liquidity_pool::accounts::ConfigInit {
+    config: find_config().0,
+    treasury: find_treasury().0,
+    admin: *admin,
+    program: simple_liquidity_pool::ID,
+    program_data: get_program_data_address(&simple_liquidity_pool::ID),
+    system_program: anchor_lang::system_program::ID,
+  };
+
+  Instruction {
+    program_id: simple_liquidity_pool::ID,
+    accounts: accounts.to_account_metas(None),
+    data: simple_liquidity_pool::instruction::InitConfig { protocol_fee_share_bps, default_fee_bps }.data(),
+  }
+}
+
+///
+/// Program config with ctx.payer as admin, two fresh SPL mints with ctx.payer as mint authority,
+/// USER_BALANCE of both minted to ctx.payer
+/// The lp itself is not initialized
+///
+pub async fn setup_pair(ctx: &mut ProgramTestContext) -> FixedRateLpKeys {
+  let payer = ctx.payer.pubkey();
+  process(ctx, &[init_config(&payer, PROTOCOL_FEE_SHARE_BPS, FEE_BPS)], &[]).await.unwrap();
+
+  let token_base = create_mint(ctx, &payer, DECIMALS).await;
+  let token_quote = create_mint(ctx, &payer, DECIMALS).await;
+  create_funded_ata(ctx, &payer, &token_base, USER_BALANCE).await;
+  create_funded_ata(ctx, &payer, &token_quote, USER_BALANCE).await;
+
+  FixedRateLpKeys::new(token_base, token_quote, spl_token::ID, spl_token::ID, 0)
+}
+
+/// setup_pair + initialize at RATE and FEE_BPS + add base_amount, quote_amount of liquidity
+pub async fn setup_pool(ctx: &mut ProgramTestContext, base_amount: u64, quote_amount: u64) -> FixedRateLpKeys {
+  let keys = setup_pair(ctx).await;
+  let payer = ctx.payer.pubkey();
+
+  process(ctx, &[
+    liquidity_pool::initialize(&keys, &payer, RATE, Some(FEE_BPS)),
+    liquidity_pool::add_liquidity(&keys, &payer, base_amount, quote_amount, false),
+  ], &[]).await.unwrap();
+
+  keys
+}
@@ -0,0 +1,68 @@
+//!
+//! move-token in a local bank, run with `cargo test-sbf`
+//!
+//! create_token needs the Metaplex program, which is not in the bank,
+//! so the mint and its mint authority PDA are set up here as create_token would leave them
+//!
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang_move_token::Discriminator;
+use move_token::instructions::{MintAuthorityPda, TOKEN_DECIMAL};
+use simple_swap_client::{move_token as move_token_ix, pda::{ata, find_mint_authority}};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{account::Account, signature::{Keypair, Signer}};
+use common::*;
+
+/// MoveTokenError::InvalidAirDropAmount, first error of the program
+const INVALID_AIR_DROP_AMOUNT: u32 = 6000;
+
+/// A move-token mint: TOKEN_DECIMAL, mint authority is the program PDA
+async fn start_with_mint() -> (ProgramTestContext, Pubkey) {
+  let mut program_test = program_test();
+  let mint = Keypair::new();
+
+  // what create_token inits: MintAuthorityPda, 8 + 32 bytes owned by move-token
+  let (mint_authority, _) = find_mint_authority(&mint.pubkey());
+  let mut data = vec![0; 8 + 32];
+  data[..8].copy_from_slice(&MintAuthorityPda::discriminator());
+  program_test.add_account(mint_authority, Account {
+    lamports: 1_000_000_000,
+    data,
+    owner: move_token::ID,
+    ..Account::default()
+  });
+
+  let mut ctx = program_test.start_with_context().await;
+  create_mint_with_keypair(&mut ctx, &mint, &mint_authority, TOKEN_DECIMAL).await;
+
+  (ctx, mint.pubkey())
+}
+
+#[tokio::test]
+async fn mint_to_another_wallet_airdrops() {
+  let (mut ctx, mint) = start_with_mint().await;
+  let payer = ctx.payer.pubkey();
+  let recipient = Keypair::new().pubkey();
+  let amount = 1000 * 10_u64.pow(TOKEN_DECIMAL as u32);
+
+  // recipient ATA is created on the first airdrop
+  process(&mut ctx, &[move_token_ix::mint_to_another_wallet(&payer, &mint, &recipient, amount)], &[]).await.unwrap();
+  process(&mut ctx, &[move_token_ix::mint_to_another_wallet(&payer, &mint, &recipient, 1)], &[]).await.unwrap();
+
+  let recipient_ata = ata(&recipient, &mint, &anchor_spl::token::ID);
+  assert_eq!(token_balance(&mut ctx, &recipient_ata).await, amount + 1);
+}
+
+#[tokio::test]
+async fn mint_to_another_wallet_rejects_large_airdrop() {
+  let (mut ctx, mint) = start_with_mint().await;
+  let payer = ctx.payer.pubkey();
+  let recipient = Keypair::new().pubkey();
+  let amount = 1000 * 10_u64.pow(TOKEN_DECIMAL as u32) + 1;
+
+  let result = process(&mut ctx, &[move_token_ix::mint_to_another_wallet(&payer, &mint, &recipient, amount)], &[]).await;
+  assert_custom_error(result, INVALID_AIR_DROP_AMOUNT);
+}
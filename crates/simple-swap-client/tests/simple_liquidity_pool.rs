@@ -0,0 +1,319 @@
+//!
+//! simple-liquidity-pool in a local bank, run with `cargo test-sbf`
+//!
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::token::spl_token::{self, error::TokenError};
+use pool_math::{FixedRatePool, SwapDir};
+use simple_liquidity_pool::state::{errors::LpBaseError, LpStatus};
+use simple_swap_client::liquidity_pool;
+use solana_sdk::signature::{Keypair, Signer};
+use common::*;
+
+const BASE_LIQUIDITY: u64 = 100_000_000_000;
+const QUOTE_LIQUIDITY: u64 = 500_000_000_000;
+
+/// What the program should do for this swap, from the lp state before the swap
+fn expected_swap(lp: &simple_liquidity_pool::state::FixedRateLP, swap_dir: SwapDir, from_amount: u64) -> (u64, u64, u64) {
+  let preview = lp.math().preview_swap(swap_dir, from_amount).unwrap();
+  let protocol_fee = (preview.fee as u128 * PROTOCOL_FEE_SHARE_BPS as u128 / 10_000) as u64;
+
+  (preview.to_amount(), preview.fee - protocol_fee, protocol_fee)
+}
+
+#[tokio::test]
+async fn init_config_requires_upgrade_authority() {
+  let mut ctx = start().await;
+  let payer = ctx.payer.pubkey();
+
+  // anyone watching the deployment, cannot become admin by calling init_config first
+  let stranger = Keypair::new();
+  process(&mut ctx, &[system_instruction::transfer(&payer, &stranger.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+  let result = process(&mut ctx, &[init_config(&stranger.pubkey(), PROTOCOL_FEE_SHARE_BPS, FEE_BPS)], &[&stranger]).await;
+  assert_lp_error(result, LpBaseError::InvalidUpgradeAuthority);
+
+  process(&mut ctx, &[init_config(&payer, PROTOCOL_FEE_SHARE_BPS, FEE_BPS)], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn initialize_creates_lp() {
+  let mut ctx = start().await;
+  let keys = setup_pair(&mut ctx).await;
+  let payer = ctx.payer.pubkey();
+
+  process(&mut ctx, &[liquidity_pool::initialize(&keys, &payer, RATE, Some(FEE_BPS))], &[]).await.unwrap();
+
+  let lp = fetch_lp(&mut ctx, &keys).await;
+  assert_eq!(lp.rate, RATE);
+  assert_eq!(lp.fee_bps, FEE_BPS);
+  assert_eq!(lp.authority, payer);
+  assert_eq!(lp.status, LpStatus::Active);
+  assert_eq!(lp.token_base, keys.token_base);
+  assert_eq!(lp.token_quote, keys.token_quote);
+  assert_eq!(lp.pool_index, 0);
+  assert_eq!((lp.amount_base, lp.amount_quote), (0, 0));
+
+  assert_eq!(token_balance(&mut ctx, &keys.lp_liquidity_base_ata).await, 0);
+  assert_eq!(token_balance(&mut ctx, &keys.lp_fee_quote_ata).await, 0);
+}
+
+#[tokio::test]
+async fn initialize_uses_default_fee() {
+  let mut ctx = start().await;
+  let keys = setup_pair(&mut ctx).await;
+  let payer = ctx.payer.pubkey();
+
+  process(&mut ctx, &[liquidity_pool::initialize(&keys, &payer, RATE, None)], &[]).await.unwrap();
+
+  // setup_pair sets FEE_BPS as the config default fee
+  assert_eq!(fetch_lp(&mut ctx, &keys).await.fee_bps, FEE_BPS);
+}
+
+#[tokio::test]
+async fn initialize_rejects_invalid_rate() {
+  let mut ctx = start().await;
+  let keys = setup_pair(&mut ctx).await;
+  let payer = ctx.payer.pubkey();
+
+  let result = process(&mut ctx, &[liquidity_pool::initialize(&keys, &payer, 0, Some(FEE_BPS))], &[]).await;
+  assert_lp_error(result, LpBaseError::InvalidRate);
+
+  let result = process(&mut ctx, &[liquidity_pool::initialize(&keys, &payer, RATE, Some(1_001))], &[]).await;
+  assert_lp_error(result, LpBaseError::InvalidFee);
+}
+
+#[tokio::test]
+async fn initialize_rejects_creator_not_allowed() {
+  let mut ctx = start().await;
+  let keys = setup_pair(&mut ctx).await;
+  let payer = ctx.payer.pubkey();
+
+  // neither admin, approved pool creator nor quote mint authority
+  let stranger = Keypair::new();
+  process(&mut ctx, &[system_instruction::transfer(&payer, &stranger.pubkey(), 1_000_000_000)], &[]).await.unwrap();
+
+  let result = process(
+    &mut ctx,
+    &[liquidity_pool::initialize(&keys, &stranger.pubkey(), RATE, Some(FEE_BPS))],
+    &[&stranger],
+  ).await;
+  assert_lp_error(result, LpBaseError::PoolCreatorNotAllowed);
+}
+
+#[tokio::test]
+async fn add_liquidity_mints_share_by_value() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+
+  let lp = fetch_lp(&mut ctx, &keys).await;
+  assert_eq!((lp.amount_base, lp.amount_quote), (BASE_LIQUIDITY, QUOTE_LIQUIDITY));
+  assert_eq!(token_balance(&mut ctx, &keys.lp_liquidity_base_ata).await, BASE_LIQUIDITY);
+  assert_eq!(token_balance(&mut ctx, &keys.lp_liquidity_quote_ata).await, QUOTE_LIQUIDITY);
+
+  // first deposit: share = value in base = base + quote / 10
+  let first_share = BASE_LIQUIDITY + QUOTE_LIQUIDITY / 10;
+  assert_eq!(token_balance(&mut ctx, &keys.user_share_ata(&payer)).await, first_share);
+  assert_eq!(token_balance(&mut ctx, &keys.user_base_ata(&payer)).await, USER_BALANCE - BASE_LIQUIDITY);
+
+  // next deposit: proportional to the pool value
+  process(&mut ctx, &[liquidity_pool::add_liquidity(&keys, &payer, 0, 10_000_000_000, false)], &[]).await.unwrap();
+  assert_eq!(token_balance(&mut ctx, &keys.user_share_ata(&payer)).await, first_share + 1_000_000_000);
+}
+
+#[tokio::test]
+async fn share_cannot_leave_the_provider() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+  let user_share_ata = keys.user_share_ata(&payer);
+
+  // share accounts stay frozen by the lp: no transfer, no burn outside withdraw_liquidity
+  let other = Keypair::new().pubkey();
+  process(&mut ctx, &[
+    spl_associated_token_account::instruction::create_associated_token_account(&payer, &other, &keys.lp_share_mint, &spl_token::ID),
+  ], &[]).await.unwrap();
+  let other_share_ata = keys.user_share_ata(&other);
+
+  let transfer = spl_token::instruction::transfer(&spl_token::ID, &user_share_ata, &other_share_ata, &payer, &[], 1).unwrap();
+  let result = process(&mut ctx, &[transfer], &[]).await;
+  assert_custom_error(result, TokenError::AccountFrozen as u32);
+
+  let burn = spl_token::instruction::burn(&spl_token::ID, &user_share_ata, &keys.lp_share_mint, &payer, &[], 1).unwrap();
+  let result = process(&mut ctx, &[burn], &[]).await;
+  assert_custom_error(result, TokenError::AccountFrozen as u32);
+}
+
+#[tokio::test]
+async fn add_liquidity_rejects_zero_amount() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+
+  let result = process(&mut ctx, &[liquidity_pool::add_liquidity(&keys, &payer, 0, 0, false)], &[]).await;
+  assert_lp_error(result, LpBaseError::InvalidAmount);
+}
+
+#[tokio::test]
+async fn swap_base_to_quote() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+  let from_amount = 1_000_000_000;
+
+  let lp_before = fetch_lp(&mut ctx, &keys).await;
+  let (to_amount, lp_fee, protocol_fee) = expected_swap(&lp_before, SwapDir::BaseToQuote, from_amount);
+  // 1 base = 10 quote, 0.3% fee, 1/6 of it to treasury
+  assert_eq!((to_amount, lp_fee, protocol_fee), (9_970_000_000, 24_999_000, 5_001_000));
+  let user_base_before = token_balance(&mut ctx, &keys.user_base_ata(&payer)).await;
+  let user_quote_before = token_balance(&mut ctx, &keys.user_quote_ata(&payer)).await;
+
+  process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_base, keys.token_quote, from_amount, to_amount, false)],
+    &[],
+  ).await.unwrap();
+
+  assert_eq!(token_balance(&mut ctx, &keys.user_base_ata(&payer)).await, user_base_before - from_amount);
+  assert_eq!(token_balance(&mut ctx, &keys.user_quote_ata(&payer)).await, user_quote_before + to_amount);
+
+  // fee routing: lp share holders and treasury, on the to token
+  assert_eq!(token_balance(&mut ctx, &keys.lp_fee_quote_ata).await, lp_fee);
+  assert_eq!(token_balance(&mut ctx, &keys.treasury_quote_ata()).await, protocol_fee);
+  assert_eq!(token_balance(&mut ctx, &keys.lp_fee_base_ata).await, 0);
+
+  let lp = fetch_lp(&mut ctx, &keys).await;
+  assert_eq!(lp.amount_base, BASE_LIQUIDITY + from_amount);
+  assert_eq!(lp.amount_quote, QUOTE_LIQUIDITY - to_amount - lp_fee - protocol_fee);
+  assert_eq!(token_balance(&mut ctx, &keys.lp_liquidity_quote_ata).await, lp.amount_quote);
+  assert!(lp.fee_quote_per_share > 0);
+  assert_eq!(lp.fee_base_per_share, 0);
+}
+
+#[tokio::test]
+async fn swap_quote_to_base() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+  let from_amount = 10_000_000_000;
+
+  let lp_before = fetch_lp(&mut ctx, &keys).await;
+  let (to_amount, lp_fee, protocol_fee) = expected_swap(&lp_before, SwapDir::QuoteToBase, from_amount);
+  let user_base_before = token_balance(&mut ctx, &keys.user_base_ata(&payer)).await;
+
+  process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_quote, keys.token_base, from_amount, to_amount, false)],
+    &[],
+  ).await.unwrap();
+
+  assert_eq!(token_balance(&mut ctx, &keys.user_base_ata(&payer)).await, user_base_before + to_amount);
+  assert_eq!(token_balance(&mut ctx, &keys.lp_fee_base_ata).await, lp_fee);
+  assert_eq!(token_balance(&mut ctx, &keys.treasury_base_ata()).await, protocol_fee);
+  assert_eq!(token_balance(&mut ctx, &keys.lp_fee_quote_ata).await, 0);
+
+  let lp = fetch_lp(&mut ctx, &keys).await;
+  assert_eq!(lp.amount_quote, QUOTE_LIQUIDITY + from_amount);
+  assert_eq!(lp.amount_base, BASE_LIQUIDITY - to_amount - lp_fee - protocol_fee);
+  assert!(lp.fee_base_per_share > 0);
+}
+
+#[tokio::test]
+async fn swap_round_trip_does_not_profit() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+  let user_base_before = token_balance(&mut ctx, &keys.user_base_ata(&payer)).await;
+  let user_quote_before = token_balance(&mut ctx, &keys.user_quote_ata(&payer)).await;
+
+  let from_amount = 123_456_789;
+  let lp = fetch_lp(&mut ctx, &keys).await;
+  let (quote_out, _, _) = expected_swap(&lp, SwapDir::BaseToQuote, from_amount);
+  process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_base, keys.token_quote, from_amount, 0, false)],
+    &[],
+  ).await.unwrap();
+  process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_quote, keys.token_base, quote_out, 0, false)],
+    &[],
+  ).await.unwrap();
+
+  assert!(token_balance(&mut ctx, &keys.user_base_ata(&payer)).await < user_base_before);
+  assert_eq!(token_balance(&mut ctx, &keys.user_quote_ata(&payer)).await, user_quote_before);
+}
+
+#[tokio::test]
+async fn swap_rejects_slippage() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+  let from_amount = 1_000_000_000;
+
+  let lp = fetch_lp(&mut ctx, &keys).await;
+  let (to_amount, _, _) = expected_swap(&lp, SwapDir::BaseToQuote, from_amount);
+
+  let result = process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_base, keys.token_quote, from_amount, to_amount + 1, false)],
+    &[],
+  ).await;
+  assert_lp_error(result, LpBaseError::SlippageExceeded);
+}
+
+#[tokio::test]
+async fn swap_rejects_over_liquidity() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+
+  // 50.001 base is worth 500.01 quote, more than the lp has
+  let from_amount = QUOTE_LIQUIDITY / 10 + 1_000_000;
+  let result = process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_base, keys.token_quote, from_amount, 0, false)],
+    &[],
+  ).await;
+  assert_lp_error(result, LpBaseError::InsufficientQuoteAmount);
+
+  // state is untouched
+  let lp = fetch_lp(&mut ctx, &keys).await;
+  assert_eq!((lp.amount_base, lp.amount_quote), (BASE_LIQUIDITY, QUOTE_LIQUIDITY));
+}
+
+#[tokio::test]
+async fn swap_rejects_invalid_input() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let payer = ctx.payer.pubkey();
+
+  let result = process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_base, keys.token_base, 1_000, 0, false)],
+    &[],
+  ).await;
+  assert_lp_error(result, LpBaseError::InvalidSwapToken);
+
+  let result = process(
+    &mut ctx,
+    &[liquidity_pool::swap(&keys, &payer, keys.token_base, keys.token_quote, 0, 0, false)],
+    &[],
+  ).await;
+  assert_lp_error(result, LpBaseError::InvalidSwapAmount);
+}
+
+#[tokio::test]
+async fn swap_math_matches_pool_math() {
+  let mut ctx = start().await;
+  let keys = setup_pool(&mut ctx, BASE_LIQUIDITY, QUOTE_LIQUIDITY).await;
+  let lp = fetch_lp(&mut ctx, &keys).await;
+
+  // what off-chain quoting sees is what the lp state gives
+  let pool = FixedRatePool { rate: RATE, fee_bps: FEE_BPS, amount_base: BASE_LIQUIDITY, amount_quote: QUOTE_LIQUIDITY };
+  assert_eq!(lp.math(), pool);
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-mod instructions;
+pub mod instructions;
 mod errors;
 
 use instructions::*; // Must import as * to avoid error
@@ -1,9 +1,51 @@
+// Token accounts in the Accounts structs are boxed to keep try_accounts under the BPF stack limit
+pub mod init_config;
+pub mod update_config;
+pub mod set_paused;
+pub mod set_pool_creation;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod withdraw_treasury;
+pub mod withdraw_cp_treasury_sol;
 pub mod init;
 pub mod add_lp;
 pub mod swap;
-// pub mod destruct;
+pub mod swap_exact_out;
+pub mod quote_swap;
+pub mod withdraw_lp;
+pub mod claim_fee;
+pub mod update_rate;
+pub mod update_fee;
+pub mod set_status;
+pub mod cp_init;
+pub mod cp_add_lp;
+pub mod cp_swap;
+pub mod cp_withdraw_lp;
+pub mod cp_claim_fee;
+pub mod close_pool;
+pub(crate) mod wrap_sol;
+pub(crate) mod share_token;
 
+pub use init_config::*;
+pub use update_config::*;
+pub use set_paused::*;
+pub use set_pool_creation::*;
+pub use propose_admin::*;
+pub use accept_admin::*;
+pub use withdraw_treasury::*;
+pub use withdraw_cp_treasury_sol::*;
 pub use init::*;
 pub use add_lp::*;
 pub use swap::*;
-// pub use destruct::*;
+pub use quote_swap::*;
+pub use withdraw_lp::*;
+pub use claim_fee::*;
+pub use update_rate::*;
+pub use update_fee::*;
+pub use set_status::*;
+pub use cp_init::*;
+pub use cp_add_lp::*;
+pub use cp_swap::*;
+pub use cp_withdraw_lp::*;
+pub use cp_claim_fee::*;
+pub use close_pool::*;
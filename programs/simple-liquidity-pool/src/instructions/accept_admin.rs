@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+  ProgramConfig,
+  CONFIG_SEED_PREFIX,
+};
+
+///
+/// Step 2 of admin handover, signed by the admin proposed in propose_admin
+///
+pub fn accept_admin(ctx: Context<ConfigAcceptAdmin>) -> Result<()> {
+  let config = &mut ctx.accounts.config;
+  msg!("[accept_admin] admin: {} => {}", config.admin, ctx.accounts.pending_admin.key());
+
+  config.accept_admin(ctx.accounts.pending_admin.key())
+}
+
+
+#[derive(Accounts)]
+pub struct ConfigAcceptAdmin<'info> {
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  pub pending_admin: Signer<'info>,
+}
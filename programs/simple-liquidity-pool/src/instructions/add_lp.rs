@@ -1,27 +1,84 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
 use anchor_spl::{
   token,
+  token_interface,
   token::spl_token,
   associated_token,
 };
-use crate::state::{FixedRateLP, LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX};
+use crate::instructions::wrap_sol::{open_wsol_temp_account, close_wsol_temp_account};
+use crate::instructions::share_token::mint_share;
+use crate::state::{
+  errors::LpBaseError,
+  events::LiquidityAdded,
+  FixedRateLP, LiquidityProvider, ProgramConfig,
+  CONFIG_SEED_PREFIX,
+  LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_PROVIDER_SEED_PREFIX, LP_SHARE_MINT_PREFIX, LP_WSOL_TEMP_SEED_PREFIX,
+};
 
 pub fn add_liquidity(
   ctx: Context<LpAddLiquidity>,
   base_amount: u64,
   quote_amount: u64,
+  wrap_sol: bool,
 ) -> Result<()> {
-  let lp = &mut ctx.accounts.lp;
-  lp.add_liquidity(base_amount, quote_amount)?;
-
-  if base_amount > 0 {
-    transfer_token_into_pool(&ctx, spl_token::native_mint::id(), base_amount)?;
+  if wrap_sol {
+    wrap_user_sol(&ctx, base_amount, quote_amount)?;
   }
-  if quote_amount > 0 {
-    transfer_token_into_pool(&ctx, ctx.accounts.token_quote.key(), quote_amount)?;
+
+  // Token-2022 transfer fee is withheld in the lp account, share is minted for what was received
+  let base_received = if base_amount > 0 {
+    transfer_token_into_pool(&ctx, ctx.accounts.token_base.key(), base_amount, wrap_sol)?
+  } else {
+    0
+  };
+  let quote_received = if quote_amount > 0 {
+    transfer_token_into_pool(&ctx, ctx.accounts.token_quote.key(), quote_amount, wrap_sol)?
+  } else {
+    0
+  };
+
+  if wrap_sol {
+    close_wsol_temp_account(
+      &ctx.accounts.user_wsol_temp.to_account_info(),
+      &ctx.accounts.user.to_account_info(),
+      &ctx.accounts.token_program.to_account_info(),
+    )?;
   }
 
+  let share_supply: u64 = ctx.accounts.lp_share_mint.supply;
+
+  let lp = &mut ctx.accounts.lp;
+  let share = lp.add_liquidity(
+    base_received,
+    quote_received,
+    share_supply,
+  )?;
+
+  let lp_key = lp.key();
+  let provider_bump = *ctx.bumps.get("liquidity_provider").unwrap();
+  ctx.accounts.liquidity_provider.deposit(
+    &mut *ctx.accounts.lp,
+    lp_key,
+    ctx.accounts.user.key(),
+    provider_bump,
+    share,
+  )?;
+
+  mint_share_to_user(&ctx, share)?;
+
+  let lp = &ctx.accounts.lp;
+  emit!(LiquidityAdded {
+    lp: lp.key(),
+    user: ctx.accounts.user.key(),
+    token_base: lp.token_base,
+    token_quote: lp.token_quote,
+    base_amount: base_received,
+    quote_amount: quote_received,
+    share,
+    amount_base: lp.amount_base,
+    amount_quote: lp.amount_quote,
+  });
+
   Ok(())
 }
 
@@ -33,45 +90,125 @@ pub struct LpAddLiquidity<'info> {
     mut,
     seeds = [
       LP_SEED_PREFIX,
-      token_quote.key().as_ref()
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
     ],
     bump = lp.bump,
+    has_one = token_base @ LpBaseError::InvalidTokenPair,
+    has_one = token_quote @ LpBaseError::InvalidTokenPair,
   )]
   pub lp: Account<'info, FixedRateLP>,
 
-  #[account(mut)]
-  pub token_quote: Account<'info, token::Mint>,
+  #[account(mint::token_program = token_base_program)]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mint::token_program = token_quote_program)]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
 
-  // lp liquidity: store SOL liquidity
-  // `lp` account is state, contain data so cannot be used as SOL sender
-  /// CHECK: will handle validation in code if needed
+  // lp liquidity: owner of the liquidity token accounts
+  /// CHECK: data-less PDA, only signs for its token accounts
   #[account(
     mut,
     seeds = [
       LP_LIQUIDITY_PREFIX,
-      token_quote.key().as_ref()
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
     ],
     bump = lp.liquidity_bump,
   )]
   pub lp_liquidity: UncheckedAccount<'info>,
 
-  // lp liquidity: store SPL liquidity
+  // lp liquidity: store token liquidity, wrapped SOL included
+  #[account(
+    mut,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_liquidity_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
   #[account(
     mut,
     associated_token::mint = token_quote,
     associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_quote_program,
   )]
-  pub lp_liquidity_quote_ata: Account<'info, token::TokenAccount>,
+  pub lp_liquidity_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // user token accounts: the SOL side can be left out when wrap_sol is set,
+  // user_wsol_temp replaces it so no wSOL ATA is created for the user
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_base,
+    associated_token::authority = user,
+    associated_token::token_program = token_base_program,
+  )]
+  pub user_base_ata: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 
   #[account(
     init_if_needed,
     payer = user,
     associated_token::mint = token_quote,
     associated_token::authority = user,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub user_quote_ata: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+  // lp share: minted to user, proportional to the value he added
+  #[account(
+    mut,
+    seeds = [
+      LP_SHARE_MINT_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.share_mint_bump,
+  )]
+  pub lp_share_mint: Box<Account<'info, token::Mint>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = lp_share_mint,
+    associated_token::authority = user,
   )]
-  pub user_quote_ata: Account<'info, token::TokenAccount>,
+  pub user_share_ata: Box<Account<'info, token::TokenAccount>>,
 
+  // user pays native SOL through it when wrap_sol is set, see wrap_sol.rs
+  /// CHECK: created and closed within the instruction
+  #[account(
+    mut,
+    seeds = [LP_WSOL_TEMP_SEED_PREFIX, user.key().as_ref()],
+    bump,
+  )]
+  pub user_wsol_temp: UncheckedAccount<'info>,
+
+  // how much share this user got from this lp
+  #[account(
+    init_if_needed,
+    payer = user,
+    space = 8 + LiquidityProvider::MAXIMUM_SIZE,
+    seeds = [
+      LP_PROVIDER_SEED_PREFIX,
+      lp.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump,
+  )]
+  pub liquidity_provider: Account<'info, LiquidityProvider>,
+
+
+  // global config: nothing can be done while the program is paused
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    constraint = !config.paused @ LpBaseError::ProgramPaused,
+  )]
+  pub config: Account<'info, ProgramConfig>,
 
   #[account(mut)]
   pub user: Signer<'info>,
@@ -79,46 +216,117 @@ pub struct LpAddLiquidity<'info> {
   // pub rent: Sysvar<'info, Rent>,
   pub system_program: Program<'info, System>,
   pub token_program: Program<'info, token::Token>,
+  pub token_base_program: Interface<'info, token_interface::TokenInterface>,
+  pub token_quote_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
 }
 
 
 ///
-/// Transfer token from user wallet into pool
+/// Wrap the SOL side of the deposit into user temporary wSOL account
+///
+fn wrap_user_sol<'info>(
+  ctx: &Context<LpAddLiquidity<'info>>,
+  base_amount: u64,
+  quote_amount: u64,
+) -> Result<()> {
+  let (native_mint, amount) = if ctx.accounts.token_base.key() == spl_token::native_mint::id() {
+    (ctx.accounts.token_base.to_account_info(), base_amount)
+  } else {
+    (ctx.accounts.token_quote.to_account_info(), quote_amount)
+  };
+
+  open_wsol_temp_account(
+    &ctx.accounts.user_wsol_temp.to_account_info(),
+    *ctx.bumps.get("user_wsol_temp").unwrap(),
+    &native_mint,
+    &ctx.accounts.user.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.token_program.to_account_info(),
+    amount,
+  )
+}
+
+///
+/// Transfer token from user wallet into pool, return the amount the pool actually received
 ///
 fn transfer_token_into_pool<'info>(
   ctx: &Context<LpAddLiquidity<'info>>,
   for_token: Pubkey,
   amount: u64,
-) -> Result<()> {
+  wrap_sol: bool,
+) -> Result<u64> {
   msg!("[transfer_token_into_pool] Transferring {} {} tokens ...", amount, for_token.key().to_string());
 
-  let is_native_and_base_token = for_token == spl_token::native_mint::id();
-
-  if is_native_and_base_token {
-    // case native SOL
-    system_program::transfer(
-      CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        system_program::Transfer {
-          from: ctx.accounts.user.to_account_info(),
-          to: ctx.accounts.lp_liquidity.to_account_info(),
-        },
-      ),
-      amount,
+  let is_base = for_token == ctx.accounts.lp.token_base;
+  let (mint, user_ata, to, token_program) = if is_base {
+    (
+      &ctx.accounts.token_base,
+      &ctx.accounts.user_base_ata,
+      &ctx.accounts.lp_liquidity_base_ata,
+      ctx.accounts.token_base_program.to_account_info(),
     )
   } else {
-    // case SPL token
-    token::transfer(
-      CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.user_quote_ata.to_account_info(),
-          to: ctx.accounts.lp_liquidity_quote_ata.to_account_info(),
-          authority: ctx.accounts.user.to_account_info(),
-        },
-      ),
-      amount,
+    (
+      &ctx.accounts.token_quote,
+      &ctx.accounts.user_quote_ata,
+      &ctx.accounts.lp_liquidity_quote_ata,
+      ctx.accounts.token_quote_program.to_account_info(),
     )
-  }
+  };
+  let from = if wrap_sol && for_token == spl_token::native_mint::id() {
+    ctx.accounts.user_wsol_temp.to_account_info()
+  } else {
+    user_ata
+      .as_ref()
+      .map(|ata| ata.to_account_info())
+      .ok_or_else(|| error!(LpBaseError::MissingUserTokenAccount))?
+  };
+  let balance_before = token::accessor::amount(&to.to_account_info())?;
+
+  token_interface::transfer_checked(
+    CpiContext::new(
+      token_program,
+      token_interface::TransferChecked {
+        from,
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+      },
+    ),
+    amount,
+    mint.decimals,
+  )?;
+
+  let balance_after = token::accessor::amount(&to.to_account_info())?;
+  Ok(balance_after - balance_before)
+}
+
+///
+/// Mint lp share to user wallet, signed by lp, see share_token.rs
+///
+fn mint_share_to_user<'info>(
+  ctx: &Context<LpAddLiquidity<'info>>,
+  share: u64,
+) -> Result<()> {
+  msg!("[mint_share_to_user] Minting {} share ...", share);
+
+  let (mint_a, mint_b) = ctx.accounts.lp.seed_mints();
+  let pool_index = ctx.accounts.lp.pool_index.to_le_bytes();
+  let lp_seeds: &[&[u8]] = &[
+    LP_SEED_PREFIX,
+    mint_a.as_ref(),
+    mint_b.as_ref(),
+    pool_index.as_ref(),
+    &[ctx.accounts.lp.bump],
+  ];
+
+  mint_share(
+    &ctx.accounts.lp_share_mint.to_account_info(),
+    &ctx.accounts.user_share_ata,
+    &ctx.accounts.lp.to_account_info(),
+    lp_seeds,
+    &ctx.accounts.token_program.to_account_info(),
+    share,
+  )
 }
@@ -4,9 +4,9 @@ import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_poo
 import {sleep} from "../../../../tests/helpers/time";
 import {getCurrentProvider, getProgramConstant, getProgramIdlConstant, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
 import {assert, expect} from "chai";
-import {NATIVE_MINT, NATIVE_MINT_2022} from "@solana/spl-token"
+import {getAccount, NATIVE_MINT, NATIVE_MINT_2022} from "@solana/spl-token"
 import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
-import {getThisProgramConstants} from "./utils.test";
+import {getConfigAccounts, getLpAccounts, getThisProgramConstants, getWsolTempAccount} from "./utils.test";
 
 
 export default function test__add_liquidity(program: Program<SimpleLiquidityPool>) {
@@ -34,51 +34,49 @@ export async function add_liquidity_to_exist_lp(program: Program<SimpleLiquidity
   const wallet = getProviderWallet();
 
   const {
-    LP_SEED_PREFIX,
-    LP_LIQUIDITY_PREFIX,
+    LP_PROVIDER_SEED_PREFIX,
     TOKEN_DECIMAL,
   } = getThisProgramConstants(program);
 
 
-  // const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
   const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
   const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
-  const [lpPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_SEED_PREFIX,
-      // tokenBasePubKey.toBuffer(),
-      tokenQuotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpLiquidityPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+  const [liquidityProviderPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
     [
-      LP_LIQUIDITY_PREFIX,
-      tokenQuotePubKey.toBuffer(),
+      LP_PROVIDER_SEED_PREFIX,
+      lpPubKey.toBuffer(),
+      wallet.payer.publicKey.toBuffer(),
     ],
     program.programId
   ))
 
-  // const baseAta = await anchor.utils.token.associatedAddress({
-  //   mint: tokenBasePubKey,
-  //   owner: lpPubKey
-  // });
-  const lpLiquidityQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: tokenQuotePubKey,
-    owner: lpLiquidityPubKey,
-    // owner: lpPubKey,
-  });
   const userQuoteAta = await anchor.utils.token.associatedAddress({
     mint: tokenQuotePubKey,
     owner: wallet.payer.publicKey
   });
+  const userShareAta = await anchor.utils.token.associatedAddress({
+    mint: lpShareMintPubKey,
+    owner: wallet.payer.publicKey
+  });
+
+  const {configPubKey} = await getConfigAccounts(program, tokenQuotePubKey);
 
   const lpBalances = {
     before: {quote: 0, base: 0},
     after: {quote: 0, base: 0},
   }
-  lpBalances.before.base = await provider.connection.getBalance(lpLiquidityPubKey);
+  lpBalances.before.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
   lpBalances.before.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
+  const shareBefore = await getShareBalance(userShareAta);
+  const lpBefore = await program.account.fixedRateLp.fetch(lpPubKey);
   // console.log('{test___add_liquidity_to_exist_lp} lpBalances before: ', lpBalances);
 
   const baseAmount = baseDepositAmount * 1e9;
@@ -87,16 +85,27 @@ export async function add_liquidity_to_exist_lp(program: Program<SimpleLiquidity
   const tx = await program.methods.addLiquidity(
     new anchor.BN(baseAmount),  // Solana decimal is 9
     new anchor.BN(quoteAmount), // My token
+    true, // pay native SOL, it is wrapped into lp wSOL account
   )
     .accounts({
       lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
       tokenQuote: tokenQuotePubKey,
       lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
       lpLiquidityQuoteAta: lpLiquidityQuoteAta,
+      userBaseAta: program.programId, // base is SOL and wrapped: no user wSOL ATA
       userQuoteAta: userQuoteAta,
+      lpShareMint: lpShareMintPubKey,
+      userShareAta: userShareAta,
+      userWsolTemp: getWsolTempAccount(program, wallet.payer.publicKey),
+      liquidityProvider: liquidityProviderPubKey,
+      config: configPubKey,
       user: wallet.payer.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     })
     .signers([wallet.payer])
@@ -107,11 +116,36 @@ export async function add_liquidity_to_exist_lp(program: Program<SimpleLiquidity
     });
   console.log('{test___add_liquidity_to_exist_lp} tx: ', tx);
 
-  lpBalances.after.base = await provider.connection.getBalance(lpLiquidityPubKey);
+  lpBalances.after.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
   lpBalances.after.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
 
   // lpBalances must increase
   VERBOSE && console.log('{test___add_liquidity_to_exist_lp} lpBalances after: ', lpBalances);
   expect(lpBalances.after.base).to.be.eq(lpBalances.before.base + baseAmount);
   expect(lpBalances.after.quote).to.be.eq(lpBalances.before.quote + quoteAmount);
+
+  // cached reserves must increase the same
+  const lpAfter = await program.account.fixedRateLp.fetch(lpPubKey);
+  expect(lpAfter.amountBase.toNumber()).to.be.eq(lpBefore.amountBase.toNumber() + baseAmount);
+  expect(lpAfter.amountQuote.toNumber()).to.be.eq(lpBefore.amountQuote.toNumber() + quoteAmount);
+
+  // user must receive share
+  const shareAfter = await getShareBalance(userShareAta);
+  assert(shareAfter > shareBefore, "User must receive lp share");
+
+  // share account stays frozen by the lp, so the share cannot be transferred away from the provider record
+  const shareAccount = await getAccount(provider.connection, userShareAta);
+  assert(shareAccount.isFrozen, "User share account must stay frozen");
+
+  return tx;
+}
+
+async function getShareBalance(shareAta: anchor.web3.PublicKey) {
+  const provider = getCurrentProvider();
+  try {
+    return new anchor.BN((await provider.connection.getTokenAccountBalance(shareAta)).value.amount).toNumber();
+  } catch (e) {
+    // share ata is not created yet
+    return 0;
+  }
 }
@@ -0,0 +1,184 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  token_interface,
+  associated_token,
+};
+use crate::state::{
+  errors::LpBaseError,
+  events::FeeClaimed,
+  FixedRateLP, LiquidityProvider,
+  LP_SEED_PREFIX, LP_FEE_SEED_PREFIX, LP_PROVIDER_SEED_PREFIX,
+};
+
+pub fn claim_fee(ctx: Context<LpClaimFee>) -> Result<()> {
+  let (base_fee, quote_fee) = ctx.accounts.liquidity_provider.claim_fee(&mut *ctx.accounts.lp)?;
+  msg!("[claim_fee] base fee: {}, quote fee: {}", base_fee, quote_fee);
+
+  if base_fee > 0 {
+    transfer_fee_to_user(&ctx, ctx.accounts.token_base.key(), base_fee)?;
+  }
+  if quote_fee > 0 {
+    transfer_fee_to_user(&ctx, ctx.accounts.token_quote.key(), quote_fee)?;
+  }
+
+  emit!(FeeClaimed {
+    lp: ctx.accounts.lp.key(),
+    user: ctx.accounts.user.key(),
+    base_fee,
+    quote_fee,
+  });
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct LpClaimFee<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      LP_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.bump,
+    has_one = token_base @ LpBaseError::InvalidTokenPair,
+    has_one = token_quote @ LpBaseError::InvalidTokenPair,
+  )]
+  pub lp: Account<'info, FixedRateLP>,
+
+  #[account(mint::token_program = token_base_program)]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mint::token_program = token_quote_program)]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+
+  // lp fee: owner of the fee token accounts
+  /// CHECK: data-less PDA, only signs for its token accounts
+  #[account(
+    mut,
+    seeds = [
+      LP_FEE_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.fee_bump,
+  )]
+  pub lp_fee: UncheckedAccount<'info>,
+
+  // lp fee: store token fee collected
+  #[account(
+    mut,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_fee,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_fee_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_fee,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub lp_fee_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_base,
+    associated_token::authority = user,
+    associated_token::token_program = token_base_program,
+  )]
+  pub user_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = user,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub user_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // seeds contain user key, so a user can only claim his own fee
+  #[account(
+    mut,
+    seeds = [
+      LP_PROVIDER_SEED_PREFIX,
+      lp.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump = liquidity_provider.bump,
+  )]
+  pub liquidity_provider: Account<'info, LiquidityProvider>,
+
+
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_base_program: Interface<'info, token_interface::TokenInterface>,
+  pub token_quote_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+
+/// Transfer fee from lp_fee to user
+fn transfer_fee_to_user<'info>(
+  ctx: &Context<LpClaimFee<'info>>,
+  for_token: Pubkey,
+  amount: u64,
+) -> Result<()> {
+  msg!("[transfer_fee_to_user] Transferring {} {} tokens ...", amount, for_token.key().to_string());
+
+  // wrapped SOL is a token like any other, user can close his wSOL ata to unwrap it
+  let is_base = for_token == ctx.accounts.lp.token_base;
+  let (mint, from, to, token_program) = if is_base {
+    (
+      &ctx.accounts.token_base,
+      &ctx.accounts.lp_fee_base_ata,
+      &ctx.accounts.user_base_ata,
+      ctx.accounts.token_base_program.to_account_info(),
+    )
+  } else {
+    (
+      &ctx.accounts.token_quote,
+      &ctx.accounts.lp_fee_quote_ata,
+      &ctx.accounts.user_quote_ata,
+      ctx.accounts.token_quote_program.to_account_info(),
+    )
+  };
+  let insufficient = if is_base { LpBaseError::InsufficientBaseAmount } else { LpBaseError::InsufficientQuoteAmount };
+  if amount > from.amount {
+    return Err(error!(insufficient));
+  }
+
+  let (mint_a, mint_b) = ctx.accounts.lp.seed_mints();
+  let pool_index = ctx.accounts.lp.pool_index.to_le_bytes();
+  let signer_seeds: &[&[&[u8]]] = &[&[
+    LP_FEE_SEED_PREFIX,
+    mint_a.as_ref(),
+    mint_b.as_ref(),
+    pool_index.as_ref(),
+    &[ctx.accounts.lp.fee_bump],
+  ]];
+
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      token_program,
+      token_interface::TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: ctx.accounts.lp_fee.to_account_info(),
+      },
+      signer_seeds,
+    ),
+    amount,
+    mint.decimals,
+  )
+}
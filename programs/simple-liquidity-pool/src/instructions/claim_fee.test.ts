@@ -0,0 +1,160 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getCurrentProvider, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair} from "@solana/web3.js";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getLpAccounts, getThisProgramConstants} from "./utils.test";
+
+
+export default function test__claim_fee(program: Program<SimpleLiquidityPool>) {
+  it("Liquidity provider can claim fee collected from swaps", async () => test__claim_fee_by_provider(program));
+  it("Claim twice will get nothing more", async () => test__claim_fee_twice(program));
+  it("Wallet without share cannot claim fee", async () => test__claim_fee_by_other_wallet(program));
+}
+
+async function test__claim_fee_by_provider(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const provider = getCurrentProvider();
+  const {lpFeeBaseAta, lpFeeQuoteAta} = await getClaimFeeAccounts(program, wallet.payer);
+
+  const lpFeeBalances = {
+    before: {quote: 0, base: 0},
+    after: {quote: 0, base: 0},
+  }
+  lpFeeBalances.before.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeBaseAta)).value.amount).toNumber();
+  lpFeeBalances.before.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeQuoteAta)).value.amount).toNumber();
+
+  // swap tests ran before, so there must be some fee to claim
+  const tx = await claim_fee(program, {payer: wallet.payer, showException: true});
+  assert(!!tx, "Tx should not be empty");
+
+  lpFeeBalances.after.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeBaseAta)).value.amount).toNumber();
+  lpFeeBalances.after.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeQuoteAta)).value.amount).toNumber();
+
+  VERBOSE && console.log('{test__claim_fee_by_provider} lpFeeBalances: ', lpFeeBalances);
+  assert(lpFeeBalances.after.base <= lpFeeBalances.before.base, "LP fee base balance must decrease");
+  assert(lpFeeBalances.after.quote <= lpFeeBalances.before.quote, "LP fee quote balance must decrease");
+}
+
+async function test__claim_fee_twice(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const provider = getCurrentProvider();
+  const {lpFeeBaseAta, lpFeeQuoteAta, liquidityProviderPubKey} = await getClaimFeeAccounts(program, wallet.payer);
+
+  await claim_fee(program, {payer: wallet.payer, showException: true});
+  const baseBefore = new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeBaseAta)).value.amount).toNumber();
+  const quoteBefore = new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeQuoteAta)).value.amount).toNumber();
+
+  // No swap in between, nothing to claim
+  await claim_fee(program, {payer: wallet.payer, showException: true});
+  expect(new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeBaseAta)).value.amount).toNumber()).to.be.eq(baseBefore);
+  expect(new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeQuoteAta)).value.amount).toNumber()).to.be.eq(quoteBefore);
+
+  const providerAccount = await program.account.liquidityProvider.fetch(liquidityProviderPubKey);
+  expect(providerAccount.feeBasePending.toNumber()).to.be.eq(0);
+  expect(providerAccount.feeQuotePending.toNumber()).to.be.eq(0);
+}
+
+async function test__claim_fee_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await claim_fee(program, {payer: walletKeyPair, showException: false});
+  } catch (e) {
+    // liquidity_provider account of this wallet does not exist
+    assert(!!e.message, "Should throw error when claim by other wallet");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function getClaimFeeAccounts(program: Program<SimpleLiquidityPool>, payer: Keypair) {
+  const {LP_PROVIDER_SEED_PREFIX} = getThisProgramConstants(program);
+
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  const {lpPubKey, lpFeePubKey, lpFeeBaseAta, lpFeeQuoteAta} = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+  const [liquidityProviderPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      LP_PROVIDER_SEED_PREFIX,
+      lpPubKey.toBuffer(),
+      payer.publicKey.toBuffer(),
+    ],
+    program.programId
+  ))
+
+  const userBaseAta = await anchor.utils.token.associatedAddress({
+    mint: tokenBasePubKey,
+    owner: payer.publicKey
+  });
+  const userQuoteAta = await anchor.utils.token.associatedAddress({
+    mint: tokenQuotePubKey,
+    owner: payer.publicKey
+  });
+
+  return {
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    lpPubKey,
+    lpFeePubKey,
+    lpFeeBaseAta,
+    lpFeeQuoteAta,
+    liquidityProviderPubKey,
+    userBaseAta,
+    userQuoteAta,
+  }
+}
+
+export async function claim_fee(program: Program<SimpleLiquidityPool>, option: {
+  payer: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{claim_fee} : ', Date.now());
+  const {payer, showException} = option;
+
+  const {
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    lpPubKey,
+    lpFeePubKey,
+    lpFeeBaseAta,
+    lpFeeQuoteAta,
+    liquidityProviderPubKey,
+    userBaseAta,
+    userQuoteAta,
+  } = await getClaimFeeAccounts(program, payer);
+
+  const tx = await program.methods.claimFee()
+    .accounts({
+      lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      lpFee: lpFeePubKey,
+      lpFeeBaseAta: lpFeeBaseAta,
+      lpFeeQuoteAta: lpFeeQuoteAta,
+      userBaseAta: userBaseAta,
+      userQuoteAta: userQuoteAta,
+      liquidityProvider: liquidityProviderPubKey,
+      user: payer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{claim_fee} tx: ', tx);
+
+  return tx;
+}
@@ -0,0 +1,271 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  token,
+  token_interface,
+  associated_token,
+};
+use crate::state::{
+  errors::LpBaseError,
+  events::PoolClosed,
+  FixedRateLP, FeeSharingLP,
+  LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_FEE_SEED_PREFIX, LP_SHARE_MINT_PREFIX,
+};
+
+///
+/// Close an empty lp and give all the rent back to the lp authority (the creator)
+///
+/// Lp must have no share, no reserve and no unclaimed fee left
+/// Rounding dust left in the token accounts is swept to the authority
+///
+/// NOTE: lp_share_mint cannot be closed with the SPL token program, it stays with 0 supply,
+/// init on the same pool_index reuses it
+///
+pub fn close_pool(ctx: Context<LpClosePool>) -> Result<()> {
+  let lp = &ctx.accounts.lp;
+  let (fee_base_unclaimed, fee_quote_unclaimed) = lp.fee_unclaimed();
+  require!(
+    ctx.accounts.lp_share_mint.supply == 0
+      && lp.amount_base == 0
+      && lp.amount_quote == 0
+      && fee_base_unclaimed == 0
+      && fee_quote_unclaimed == 0,
+    LpBaseError::PoolNotEmpty
+  );
+
+  let (mint_a, mint_b) = lp.seed_mints();
+  let pool_index = lp.pool_index.to_le_bytes();
+  let liquidity_seeds: &[&[u8]] = &[
+    LP_LIQUIDITY_PREFIX,
+    mint_a.as_ref(),
+    mint_b.as_ref(),
+    pool_index.as_ref(),
+    &[lp.liquidity_bump],
+  ];
+  let fee_seeds: &[&[u8]] = &[
+    LP_FEE_SEED_PREFIX,
+    mint_a.as_ref(),
+    mint_b.as_ref(),
+    pool_index.as_ref(),
+    &[lp.fee_bump],
+  ];
+
+  let accounts = &ctx.accounts;
+  let base = (&accounts.token_base, accounts.token_base_program.to_account_info());
+  let quote = (&accounts.token_quote, accounts.token_quote_program.to_account_info());
+  for (token_account, (mint, token_program), dust_receiver, owner, owner_seeds) in [
+    (&accounts.lp_liquidity_base_ata, &base, &accounts.authority_base_ata, &accounts.lp_liquidity, liquidity_seeds),
+    (&accounts.lp_liquidity_quote_ata, &quote, &accounts.authority_quote_ata, &accounts.lp_liquidity, liquidity_seeds),
+    (&accounts.lp_fee_base_ata, &base, &accounts.authority_base_ata, &accounts.lp_fee, fee_seeds),
+    (&accounts.lp_fee_quote_ata, &quote, &accounts.authority_quote_ata, &accounts.lp_fee, fee_seeds),
+  ] {
+    close_token_account(
+      &ctx,
+      token_account.to_account_info(),
+      token_account.amount,
+      mint,
+      token_program.clone(),
+      dust_receiver.to_account_info(),
+      owner.to_account_info(),
+      owner_seeds,
+    )?;
+  }
+
+  // SOL holders: runtime removes them once they have no lamports
+  close_sol_holder(&ctx, ctx.accounts.lp_liquidity.to_account_info())?;
+  close_sol_holder(&ctx, ctx.accounts.lp_fee.to_account_info())?;
+
+  emit!(PoolClosed {
+    lp: ctx.accounts.lp.key(),
+    authority: ctx.accounts.authority.key(),
+  });
+
+  // lp itself is closed by the `close` constraint
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct LpClosePool<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      LP_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.bump,
+    has_one = token_base @ LpBaseError::InvalidTokenPair,
+    has_one = token_quote @ LpBaseError::InvalidTokenPair,
+    has_one = authority @ LpBaseError::InvalidAuthority,
+    close = authority,
+  )]
+  pub lp: Account<'info, FixedRateLP>,
+
+  #[account(mint::token_program = token_base_program)]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mint::token_program = token_quote_program)]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+
+  /// CHECK: data-less PDA, only signs for its token accounts
+  #[account(
+    mut,
+    seeds = [
+      LP_LIQUIDITY_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.liquidity_bump,
+  )]
+  pub lp_liquidity: UncheckedAccount<'info>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_liquidity_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub lp_liquidity_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  /// CHECK: data-less PDA, only signs for its token accounts
+  #[account(
+    mut,
+    seeds = [
+      LP_FEE_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.fee_bump,
+  )]
+  pub lp_fee: UncheckedAccount<'info>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_fee,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_fee_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_fee,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub lp_fee_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // lp share: nobody can hold any share when closing
+  #[account(
+    seeds = [
+      LP_SHARE_MINT_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.share_mint_bump,
+  )]
+  pub lp_share_mint: Box<Account<'info, token::Mint>>,
+
+  // receive the token dust
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = token_base,
+    associated_token::authority = authority,
+    associated_token::token_program = token_base_program,
+  )]
+  pub authority_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = authority,
+    associated_token::mint = token_quote,
+    associated_token::authority = authority,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub authority_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+
+  #[account(mut)]
+  pub authority: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_base_program: Interface<'info, token_interface::TokenInterface>,
+  pub token_quote_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+
+/// Sweep the dust to authority then close the token account, rent goes to authority
+#[allow(clippy::too_many_arguments)]
+fn close_token_account<'info>(
+  ctx: &Context<LpClosePool<'info>>,
+  token_account: AccountInfo<'info>,
+  dust_amount: u64,
+  mint: &InterfaceAccount<'info, token_interface::Mint>,
+  token_program: AccountInfo<'info>,
+  dust_receiver: AccountInfo<'info>,
+  owner: AccountInfo<'info>,
+  owner_seeds: &[&[u8]],
+) -> Result<()> {
+  msg!("[close_token_account] Closing {}, dust: {} ...", token_account.key(), dust_amount);
+  let signer_seeds: &[&[&[u8]]] = &[owner_seeds];
+
+  if dust_amount > 0 {
+    token_interface::transfer_checked(
+      CpiContext::new_with_signer(
+        token_program.clone(),
+        token_interface::TransferChecked {
+          from: token_account.clone(),
+          mint: mint.to_account_info(),
+          to: dust_receiver,
+          authority: owner.clone(),
+        },
+        signer_seeds,
+      ),
+      dust_amount,
+      mint.decimals,
+    )?;
+  }
+
+  token_interface::close_account(
+    CpiContext::new_with_signer(
+      token_program,
+      token_interface::CloseAccount {
+        account: token_account,
+        destination: ctx.accounts.authority.to_account_info(),
+        authority: owner,
+      },
+      signer_seeds,
+    ),
+  )
+}
+
+/// Move all lamports of a SOL holder PDA to authority
+fn close_sol_holder<'info>(
+  ctx: &Context<LpClosePool<'info>>,
+  sol_holder: AccountInfo<'info>,
+) -> Result<()> {
+  let lamports = sol_holder.lamports();
+  msg!("[close_sol_holder] Closing {}, lamports: {} ...", sol_holder.key(), lamports);
+
+  **sol_holder.try_borrow_mut_lamports()? = 0;
+  **ctx.accounts.authority
+    .to_account_info()
+    .try_borrow_mut_lamports()? += lamports;
+
+  Ok(())
+}
@@ -0,0 +1,84 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair} from "@solana/web3.js";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getLpAccounts} from "./utils.test";
+
+
+export default function test__close_pool(program: Program<SimpleLiquidityPool>) {
+  // The test lp still has liquidity, closing an empty lp needs a fresh pair
+  it("Cannot close lp while it still has liquidity", async () => test__close_not_empty_pool(program));
+  it("Other wallet cannot close lp", async () => test__close_pool_by_other_wallet(program));
+}
+
+async function test__close_not_empty_pool(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+
+  let tx = "";
+  try {
+    tx = await close_pool(program, {authority: wallet.payer, showException: false});
+  } catch (e) {
+    assert(e.message.indexOf("PoolNotEmpty") > -1, "Should throw PoolNotEmpty");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__close_pool_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await close_pool(program, {authority: walletKeyPair, showException: false});
+  } catch (e) {
+    assert(e.message.indexOf("InvalidAuthority") > -1, "Should throw InvalidAuthority");
+  }
+  expect(tx).to.be.empty;
+}
+
+export async function close_pool(program: Program<SimpleLiquidityPool>, option: {
+  authority: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{close_pool} : ', Date.now());
+  const {authority, showException} = option;
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const accounts = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+
+  const tx = await program.methods.closePool()
+    .accounts({
+      lp: accounts.lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      lpLiquidity: accounts.lpLiquidityPubKey,
+      lpLiquidityBaseAta: accounts.lpLiquidityBaseAta,
+      lpLiquidityQuoteAta: accounts.lpLiquidityQuoteAta,
+      lpFee: accounts.lpFeePubKey,
+      lpFeeBaseAta: accounts.lpFeeBaseAta,
+      lpFeeQuoteAta: accounts.lpFeeQuoteAta,
+      lpShareMint: accounts.lpShareMintPubKey,
+      authorityBaseAta: await anchor.utils.token.associatedAddress({mint: tokenBasePubKey, owner: authority.publicKey}),
+      authorityQuoteAta: await anchor.utils.token.associatedAddress({mint: tokenQuotePubKey, owner: authority.publicKey}),
+      authority: authority.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([authority])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{close_pool} tx: ', tx);
+
+  return tx;
+}
@@ -0,0 +1,226 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::{
+  token,
+  token::spl_token,
+  associated_token,
+};
+use crate::instructions::share_token::mint_share;
+use crate::state::{
+  errors::LpBaseError,
+  events::LiquidityAdded,
+  ConstantProductLP, LiquidityProvider, ProgramConfig,
+  CONFIG_SEED_PREFIX,
+  CP_LP_SEED_PREFIX, CP_LP_LIQUIDITY_PREFIX, CP_LP_PROVIDER_SEED_PREFIX, CP_LP_SHARE_MINT_PREFIX,
+};
+
+///
+/// max_base_amount, max_quote_amount: the most user want to add
+/// Only the amounts matching the current reserve ratio will be taken
+///
+pub fn cp_add_liquidity(
+  ctx: Context<CpLpAddLiquidity>,
+  max_base_amount: u64,
+  max_quote_amount: u64,
+) -> Result<()> {
+  let share_supply: u64 = ctx.accounts.lp_share_mint.supply;
+
+  let lp = &mut ctx.accounts.lp;
+  let (base_amount, quote_amount, share) = lp.add_liquidity(max_base_amount, max_quote_amount, share_supply)?;
+
+  let lp_key = lp.key();
+  let provider_bump = *ctx.bumps.get("liquidity_provider").unwrap();
+  ctx.accounts.liquidity_provider.deposit(
+    &mut *ctx.accounts.lp,
+    lp_key,
+    ctx.accounts.user.key(),
+    provider_bump,
+    share,
+  )?;
+
+  if base_amount > 0 {
+    transfer_token_into_pool(&ctx, spl_token::native_mint::id(), base_amount)?;
+  }
+  if quote_amount > 0 {
+    transfer_token_into_pool(&ctx, ctx.accounts.token_quote.key(), quote_amount)?;
+  }
+
+  mint_share_to_user(&ctx, share)?;
+
+  let lp = &ctx.accounts.lp;
+  emit!(LiquidityAdded {
+    lp: lp.key(),
+    user: ctx.accounts.user.key(),
+    token_base: lp.token_base,
+    token_quote: lp.token_quote,
+    base_amount,
+    quote_amount,
+    share,
+    amount_base: lp.reserve_base,
+    amount_quote: lp.reserve_quote,
+  });
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct CpLpAddLiquidity<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_SEED_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.bump,
+  )]
+  pub lp: Account<'info, ConstantProductLP>,
+
+  #[account()]
+  pub token_quote: Account<'info, token::Mint>,
+
+
+  // lp liquidity: store SOL liquidity
+  /// CHECK: will handle validation in code if needed
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_LIQUIDITY_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.liquidity_bump,
+  )]
+  pub lp_liquidity: UncheckedAccount<'info>,
+
+  // lp liquidity: store SPL liquidity
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_liquidity,
+  )]
+  pub lp_liquidity_quote_ata: Account<'info, token::TokenAccount>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = user,
+  )]
+  pub user_quote_ata: Account<'info, token::TokenAccount>,
+
+  // lp share: minted to user, proportional to the liquidity he added
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_SHARE_MINT_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.share_mint_bump,
+  )]
+  pub lp_share_mint: Account<'info, token::Mint>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = lp_share_mint,
+    associated_token::authority = user,
+  )]
+  pub user_share_ata: Account<'info, token::TokenAccount>,
+
+  // how much share this user got from this lp
+  #[account(
+    init_if_needed,
+    payer = user,
+    space = 8 + LiquidityProvider::MAXIMUM_SIZE,
+    seeds = [
+      CP_LP_PROVIDER_SEED_PREFIX,
+      lp.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump,
+  )]
+  pub liquidity_provider: Account<'info, LiquidityProvider>,
+
+
+  // global config: nothing can be done while the program is paused
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    constraint = !config.paused @ LpBaseError::ProgramPaused,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, token::Token>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+
+///
+/// Transfer token from user wallet into pool
+///
+fn transfer_token_into_pool<'info>(
+  ctx: &Context<CpLpAddLiquidity<'info>>,
+  for_token: Pubkey,
+  amount: u64,
+) -> Result<()> {
+  msg!("[transfer_token_into_pool] Transferring {} {} tokens ...", amount, for_token.key().to_string());
+
+  let is_native_and_base_token = for_token == spl_token::native_mint::id();
+
+  if is_native_and_base_token {
+    // case native SOL
+    system_program::transfer(
+      CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+          from: ctx.accounts.user.to_account_info(),
+          to: ctx.accounts.lp_liquidity.to_account_info(),
+        },
+      ),
+      amount,
+    )
+  } else {
+    // case SPL token
+    token::transfer(
+      CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        token::Transfer {
+          from: ctx.accounts.user_quote_ata.to_account_info(),
+          to: ctx.accounts.lp_liquidity_quote_ata.to_account_info(),
+          authority: ctx.accounts.user.to_account_info(),
+        },
+      ),
+      amount,
+    )
+  }
+}
+
+///
+/// Mint lp share to user wallet, signed by lp, see share_token.rs
+///
+fn mint_share_to_user<'info>(
+  ctx: &Context<CpLpAddLiquidity<'info>>,
+  share: u64,
+) -> Result<()> {
+  msg!("[mint_share_to_user] Minting {} share ...", share);
+
+  let token_quote_pubkey = ctx.accounts.token_quote.key();
+  let lp_seeds: &[&[u8]] = &[
+    CP_LP_SEED_PREFIX,
+    token_quote_pubkey.as_ref(),
+    &[ctx.accounts.lp.bump],
+  ];
+
+  mint_share(
+    &ctx.accounts.lp_share_mint.to_account_info(),
+    &ctx.accounts.user_share_ata,
+    &ctx.accounts.lp.to_account_info(),
+    lp_seeds,
+    &ctx.accounts.token_program.to_account_info(),
+    share,
+  )
+}
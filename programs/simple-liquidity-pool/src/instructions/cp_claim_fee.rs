@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  token,
+  token::spl_token,
+  associated_token,
+};
+use crate::state::{
+  errors::LpBaseError,
+  events::FeeClaimed,
+  ConstantProductLP, LiquidityProvider,
+  CP_LP_SEED_PREFIX, CP_LP_FEE_SEED_PREFIX, CP_LP_PROVIDER_SEED_PREFIX,
+};
+
+pub fn cp_claim_fee(ctx: Context<CpLpClaimFee>) -> Result<()> {
+  let (base_fee, quote_fee) = ctx.accounts.liquidity_provider.claim_fee(&mut *ctx.accounts.lp)?;
+  msg!("[claim_fee] base fee: {}, quote fee: {}", base_fee, quote_fee);
+
+  if base_fee > 0 {
+    transfer_fee_to_user(&ctx, spl_token::native_mint::id(), base_fee)?;
+  }
+  if quote_fee > 0 {
+    transfer_fee_to_user(&ctx, ctx.accounts.token_quote.key(), quote_fee)?;
+  }
+
+  emit!(FeeClaimed {
+    lp: ctx.accounts.lp.key(),
+    user: ctx.accounts.user.key(),
+    base_fee,
+    quote_fee,
+  });
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct CpLpClaimFee<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_SEED_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.bump,
+  )]
+  pub lp: Account<'info, ConstantProductLP>,
+
+  #[account()]
+  pub token_quote: Account<'info, token::Mint>,
+
+
+  // lp fee: store SOL fee collected
+  /// CHECK: Just to store SOL
+  #[account(
+    mut,
+    seeds = [CP_LP_FEE_SEED_PREFIX, token_quote.key().as_ref()],
+    bump = lp.fee_bump,
+  )]
+  pub lp_fee: UncheckedAccount<'info>,
+
+  // lp fee: store SPL fee collected
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_fee,
+  )]
+  pub lp_fee_quote_ata: Account<'info, token::TokenAccount>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = user,
+  )]
+  pub user_quote_ata: Account<'info, token::TokenAccount>,
+
+  // seeds contain user key, so a user can only claim his own fee
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_PROVIDER_SEED_PREFIX,
+      lp.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump = liquidity_provider.bump,
+  )]
+  pub liquidity_provider: Account<'info, LiquidityProvider>,
+
+
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, token::Token>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+
+/// Transfer fee from lp_fee to user
+fn transfer_fee_to_user<'info>(
+  ctx: &Context<CpLpClaimFee<'info>>,
+  for_token: Pubkey,
+  amount: u64,
+) -> Result<()> {
+  msg!("[transfer_fee_to_user] Transferring {} {} tokens ...", amount, for_token.key().to_string());
+
+  let is_native_and_base_token = for_token == spl_token::native_mint::id();
+
+  if is_native_and_base_token {
+    // case native SOL
+    // lp_fee must stay rent-exempt after claiming
+    let lp_fee = ctx.accounts.lp_fee.to_account_info();
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(ConstantProductLP::SOL_HOLDER_SIZE);
+    let claimable = lp_fee.lamports().saturating_sub(rent_exempt_lamports);
+    require!(amount <= claimable, LpBaseError::InsufficientBaseAmount);
+
+    **lp_fee.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.user
+      .to_account_info()
+      .try_borrow_mut_lamports()? += amount;
+    Ok(())
+  } else {
+    // case SPL token
+    require!(amount <= ctx.accounts.lp_fee_quote_ata.amount, LpBaseError::InsufficientQuoteAmount);
+
+    let token_quote_pubkey = ctx.accounts.token_quote.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+      CP_LP_FEE_SEED_PREFIX,
+      token_quote_pubkey.as_ref(),
+      &[ctx.accounts.lp.fee_bump],
+    ]];
+
+    token::transfer(
+      CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token::Transfer {
+          from: ctx.accounts.lp_fee_quote_ata.to_account_info(),
+          to: ctx.accounts.user_quote_ata.to_account_info(),
+          authority: ctx.accounts.lp_fee.to_account_info(),
+        },
+        signer_seeds,
+      ),
+      amount,
+    )
+  }
+}
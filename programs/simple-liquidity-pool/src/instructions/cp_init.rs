@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  token,
+  token::spl_token,
+  associated_token,
+};
+use crate::state::{
+  errors::LpBaseError,
+  ConstantProductLP, CpLpInitParams, ProgramConfig,
+  CONFIG_SEED_PREFIX,
+  CP_LP_SEED_PREFIX, CP_LP_LIQUIDITY_PREFIX, CP_LP_FEE_SEED_PREFIX, CP_LP_SHARE_MINT_PREFIX, LP_SHARE_DECIMAL,
+};
+
+
+pub fn cp_init(ctx: Context<CpLpInit>, fee_bps: Option<u16>) -> Result<()> {
+  // one lp per quote mint: same creator check as FixedRateLP, so nobody can squat a token launch
+  let quote_mint_authority: Option<Pubkey> = ctx.accounts.token_quote.mint_authority.into();
+  ctx.accounts.config.require_can_create_pool(ctx.accounts.user.key(), quote_mint_authority)?;
+
+  let fee_bps = fee_bps.unwrap_or(ctx.accounts.config.default_fee_bps);
+
+  let lp = &mut ctx.accounts.lp;
+
+  lp.init(CpLpInitParams {
+    authority: ctx.accounts.user.key(),
+    token_base: spl_token::native_mint::id(),
+    token_quote: ctx.accounts.token_quote.key(),
+    fee_bps,
+    bump: *ctx.bumps.get("lp").unwrap(),
+    liquidity_bump: *ctx.bumps.get("lp_liquidity").unwrap(),
+    fee_bump: *ctx.bumps.get("lp_fee").unwrap(),
+    share_mint_bump: *ctx.bumps.get("lp_share_mint").unwrap(),
+  })?;
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct CpLpInit<'info> {
+  // lp state data
+  // No price here, the first add_liquidity will decide it
+  #[account(
+    init,
+    payer = user,
+    space = 8 + ConstantProductLP::MAXIMUM_SIZE,
+    seeds = [
+      CP_LP_SEED_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump,
+  )]
+  pub lp: Account<'info, ConstantProductLP>,
+
+  // base is native SOL, so quote cannot be wrapped SOL
+  #[account(
+    constraint = token_quote.key() != spl_token::native_mint::id() @ LpBaseError::InvalidTokenPair,
+  )]
+  pub token_quote: Account<'info, token::Mint>,
+
+
+  // lp liquidity: store SOL liquidity
+  /// CHECK: Just to store SOL
+  #[account(
+    init,
+    payer = user,
+    space = ConstantProductLP::SOL_HOLDER_SIZE,
+    seeds = [
+      CP_LP_LIQUIDITY_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump,
+  )]
+  pub lp_liquidity: UncheckedAccount<'info>,
+
+  // lp liquidity: store SPL liquidity
+  #[account(
+    init,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_liquidity,
+  )]
+  pub lp_liquidity_quote_ata: Account<'info, token::TokenAccount>,
+
+  // lp fee: store SOL fee collected, share holders can claim it
+  /// CHECK: Just to store SOL
+  #[account(
+    init,
+    payer = user,
+    space = ConstantProductLP::SOL_HOLDER_SIZE,
+    seeds = [CP_LP_FEE_SEED_PREFIX, token_quote.key().as_ref()],
+    bump,
+  )]
+  pub lp_fee: UncheckedAccount<'info>,
+
+  // lp fee: store SPL fee collected, share holders can claim it
+  #[account(
+    init,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_fee,
+  )]
+  pub lp_fee_quote_ata: Account<'info, token::TokenAccount>,
+
+  // lp share: minted to liquidity providers, lp is the mint authority
+  // lp is also the freeze authority, share accounts stay frozen: see share_token.rs
+  #[account(
+    init,
+    payer = user,
+    mint::decimals = LP_SHARE_DECIMAL,
+    mint::authority = lp,
+    mint::freeze_authority = lp,
+    seeds = [CP_LP_SHARE_MINT_PREFIX, token_quote.key().as_ref()],
+    bump,
+  )]
+  pub lp_share_mint: Account<'info, token::Mint>,
+
+
+  // global config: nothing can be done while the program is paused
+  // it also decides who can create a lp, see ProgramConfig::require_can_create_pool
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    constraint = !config.paused @ LpBaseError::ProgramPaused,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub rent: Sysvar<'info, Rent>,
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, token::Token>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
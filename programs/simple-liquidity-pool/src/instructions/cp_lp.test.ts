@@ -0,0 +1,350 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getCurrentProvider, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {createMint, NATIVE_MINT} from "@solana/spl-token"
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {getConfigAccounts, getThisProgramConstants, getTxEvents} from "./utils.test";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+
+
+/**
+ * Constant product AMM lp: same pair as FixedRateLP (SOL - Your Token) but in its own accounts
+ */
+export default function test__cp_lp(program: Program<SimpleLiquidityPool>) {
+  it("can not init constant product lp with wrapped SOL as quote", async () => test__cp_init_native_quote(program));
+  it("can not init constant product lp while the program is paused", async () => test__cp_init_paused(program));
+  it("can not init constant product lp when not allowed to create a lp", async () => test__cp_init_by_not_allowed_creator(program));
+  it("can init constant product lp", async () => test__cp_init(program));
+  it("can add liquidity to constant product lp", async () => test__cp_add_liquidity(program));
+  it("can swap with constant product lp, k never decreases", async () => test__cp_swap(program));
+  it("Admin can withdraw SOL protocol fee of constant product lp", async () => test__cp_withdraw_treasury_sol(program));
+  it("can claim fee from constant product lp", async () => test__cp_claim_fee(program));
+  it("can withdraw liquidity from constant product lp", async () => test__cp_withdraw_liquidity(program));
+}
+
+const CP_FEE_BPS = 30; // 0.3%
+
+// feeBps: null to use the default fee of program config
+async function cp_init_lp(
+  program: Program<SimpleLiquidityPool>,
+  feeBps: number | null,
+  tokenQuote?: anchor.web3.PublicKey,
+  creator: anchor.web3.Keypair = getProviderWallet().payer,
+) {
+  const accounts = await getCpLpAccounts(program, tokenQuote);
+  const {configPubKey} = await getConfigAccounts(program, accounts.tokenQuotePubKey);
+
+  return program.methods.cpInitialize(feeBps)
+    .accounts({
+      lp: accounts.lpPubKey,
+      tokenQuote: accounts.tokenQuotePubKey,
+      lpLiquidity: accounts.lpLiquidityPubKey,
+      lpLiquidityQuoteAta: accounts.lpLiquidityQuoteAta,
+      lpFee: accounts.lpFeePubKey,
+      lpFeeQuoteAta: accounts.lpFeeQuoteAta,
+      lpShareMint: accounts.lpShareMintPubKey,
+      config: configPubKey,
+      user: creator.publicKey,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([creator])
+    .rpc();
+}
+
+async function test__cp_init_native_quote(program: Program<SimpleLiquidityPool>) {
+  let tx = "";
+  try {
+    tx = await cp_init_lp(program, CP_FEE_BPS, NATIVE_MINT);
+  } catch (e) {
+    assert(e.message.indexOf("InvalidTokenPair") > -1, "Should throw InvalidTokenPair");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__cp_init_paused(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {configPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+  const setPaused = (paused: boolean) => program.methods.setPaused(paused)
+    .accounts({config: configPubKey, admin: wallet.payer.publicKey})
+    .signers([wallet.payer])
+    .rpc();
+
+  await setPaused(true);
+  let tx = "";
+  try {
+    tx = await cp_init_lp(program, CP_FEE_BPS);
+  } catch (e) {
+    assert(e.message.indexOf("ProgramPaused") > -1, "Should throw ProgramPaused");
+  } finally {
+    // Unpause, next tests need to init the lp
+    await setPaused(false);
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__cp_init_by_not_allowed_creator(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const creator = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(1, creator.publicKey);
+
+  // quote mint authority is the provider wallet, not the creator
+  const tokenQuotePubKey = await createMint(getCurrentProvider().connection, wallet.payer, wallet.publicKey, null, 6);
+
+  let tx = "";
+  try {
+    tx = await cp_init_lp(program, CP_FEE_BPS, tokenQuotePubKey, creator);
+  } catch (e) {
+    assert(e.message.indexOf("PoolCreatorNotAllowed") > -1, "Should throw PoolCreatorNotAllowed");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__cp_init(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const accounts = await getCpLpAccounts(program);
+
+  const tx = await cp_init_lp(program, CP_FEE_BPS)
+    .catch(e => {
+      VERBOSE && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  assert(!!tx, "Tx should not be empty");
+
+  const lpAccount = await program.account.constantProductLp.fetch(accounts.lpPubKey);
+  assert(lpAccount.authority.equals(wallet.payer.publicKey), "Init wallet must be the lp authority");
+  expect(lpAccount.reserveBase.toNumber()).to.be.eq(0);
+  expect(lpAccount.reserveQuote.toNumber()).to.be.eq(0);
+  expect(lpAccount.feeBps).to.be.eq(CP_FEE_BPS);
+}
+
+async function test__cp_add_liquidity(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {TOKEN_DECIMAL} = getThisProgramConstants(program);
+  const accounts = await getCpLpAccounts(program);
+
+  // first deposit set the price: 1 SOL = 10 token
+  const baseAmount = 0.3 * 1e9;
+  const quoteAmount = 3 * Math.pow(10, TOKEN_DECIMAL);
+
+  const tx = await program.methods.cpAddLiquidity(new anchor.BN(baseAmount), new anchor.BN(quoteAmount))
+    .accounts({
+      lp: accounts.lpPubKey,
+      tokenQuote: accounts.tokenQuotePubKey,
+      lpLiquidity: accounts.lpLiquidityPubKey,
+      lpLiquidityQuoteAta: accounts.lpLiquidityQuoteAta,
+      userQuoteAta: accounts.userQuoteAta,
+      lpShareMint: accounts.lpShareMintPubKey,
+      userShareAta: accounts.userShareAta,
+      liquidityProvider: accounts.liquidityProviderPubKey,
+      config: (await getConfigAccounts(program, accounts.tokenQuotePubKey)).configPubKey,
+      user: wallet.payer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([wallet.payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  assert(!!tx, "Tx should not be empty");
+
+  const lpAccount = await program.account.constantProductLp.fetch(accounts.lpPubKey);
+  expect(lpAccount.reserveBase.toNumber()).to.be.eq(baseAmount);
+  expect(lpAccount.reserveQuote.toNumber()).to.be.eq(quoteAmount);
+
+  const provider = await program.account.liquidityProvider.fetch(accounts.liquidityProviderPubKey);
+  assert(provider.share.toNumber() > 0, "User must receive lp share");
+}
+
+async function test__cp_swap(program: Program<SimpleLiquidityPool>) {
+  const accounts = await getCpLpAccounts(program);
+
+  const before = await program.account.constantProductLp.fetch(accounts.lpPubKey);
+  const kBefore = BigInt(before.reserveBase.toString()) * BigInt(before.reserveQuote.toString());
+
+  const tx = await cp_swap(program, {from: NATIVE_MINT, to: accounts.tokenQuotePubKey, amount: 0.01 * 1e9});
+  assert(!!tx, "Tx should not be empty");
+  const events = await getTxEvents(program, tx);
+  assert(!!events.find(e => e.name === "Swapped"), "Swap must emit Swapped event");
+  assert(!!events.find(e => e.name === "FeeCollected"), "Swap must emit FeeCollected event");
+
+  const after = await program.account.constantProductLp.fetch(accounts.lpPubKey);
+  const kAfter = BigInt(after.reserveBase.toString()) * BigInt(after.reserveQuote.toString());
+  assert(kAfter >= kBefore, "k must never decrease");
+  assert(after.feeQuotePerShare.gt(before.feeQuotePerShare), "Swap fee must be shared to share holders");
+}
+
+async function test__cp_withdraw_treasury_sol(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const provider = getCurrentProvider();
+  const {TOKEN_DECIMAL} = getThisProgramConstants(program);
+  const accounts = await getCpLpAccounts(program);
+  const {configPubKey, treasuryPubKey} = await getConfigAccounts(program, accounts.tokenQuotePubKey);
+
+  // token -> SOL: protocol fee is paid as lamports to the treasury itself
+  const treasuryBefore = await provider.connection.getBalance(treasuryPubKey);
+  await cp_swap(program, {from: accounts.tokenQuotePubKey, to: NATIVE_MINT, amount: Math.pow(10, TOKEN_DECIMAL)});
+  const protocolFee = (await provider.connection.getBalance(treasuryPubKey)) - treasuryBefore;
+  assert(protocolFee > 0, "Treasury must receive SOL protocol fee from swap");
+
+  const tx = await program.methods.withdrawCpTreasurySol(new anchor.BN(protocolFee))
+    .accounts({
+      config: configPubKey,
+      treasury: treasuryPubKey,
+      admin: wallet.payer.publicKey,
+    })
+    .signers([wallet.payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  assert(!!tx, "Tx should not be empty");
+  expect(await provider.connection.getBalance(treasuryPubKey)).to.be.eq(treasuryBefore);
+}
+
+async function test__cp_claim_fee(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const accounts = await getCpLpAccounts(program);
+  const provider = getCurrentProvider();
+
+  const quoteBefore = new anchor.BN((await provider.connection.getTokenAccountBalance(accounts.userQuoteAta)).value.amount);
+
+  const tx = await program.methods.cpClaimFee()
+    .accounts({
+      lp: accounts.lpPubKey,
+      tokenQuote: accounts.tokenQuotePubKey,
+      lpFee: accounts.lpFeePubKey,
+      lpFeeQuoteAta: accounts.lpFeeQuoteAta,
+      userQuoteAta: accounts.userQuoteAta,
+      liquidityProvider: accounts.liquidityProviderPubKey,
+      user: wallet.payer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([wallet.payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  assert(!!tx, "Tx should not be empty");
+
+  const quoteAfter = new anchor.BN((await provider.connection.getTokenAccountBalance(accounts.userQuoteAta)).value.amount);
+  assert(quoteAfter.gt(quoteBefore), "User must receive the quote fee");
+}
+
+async function test__cp_withdraw_liquidity(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const accounts = await getCpLpAccounts(program);
+
+  const providerBefore = await program.account.liquidityProvider.fetch(accounts.liquidityProviderPubKey);
+  const share = providerBefore.share.divn(2);
+
+  const tx = await program.methods.cpWithdrawLiquidity(share)
+    .accounts({
+      lp: accounts.lpPubKey,
+      tokenQuote: accounts.tokenQuotePubKey,
+      lpLiquidity: accounts.lpLiquidityPubKey,
+      lpLiquidityQuoteAta: accounts.lpLiquidityQuoteAta,
+      userQuoteAta: accounts.userQuoteAta,
+      lpShareMint: accounts.lpShareMintPubKey,
+      userShareAta: accounts.userShareAta,
+      liquidityProvider: accounts.liquidityProviderPubKey,
+      user: wallet.payer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([wallet.payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  assert(!!tx, "Tx should not be empty");
+
+  const providerAfter = await program.account.liquidityProvider.fetch(accounts.liquidityProviderPubKey);
+  expect(providerAfter.share.toString()).to.be.eq(providerBefore.share.sub(share).toString());
+}
+
+async function cp_swap(program: Program<SimpleLiquidityPool>, option: {
+  from: anchor.web3.PublicKey,
+  to: anchor.web3.PublicKey,
+  amount: number,
+}) {
+  const wallet = getProviderWallet();
+  const {from, to, amount} = option;
+  const accounts = await getCpLpAccounts(program);
+  const {configPubKey, treasuryPubKey, treasuryQuoteAta} = await getConfigAccounts(program, accounts.tokenQuotePubKey);
+
+  return await program.methods.cpSwap(from, to, new anchor.BN(amount), new anchor.BN(1))
+    .accounts({
+      lp: accounts.lpPubKey,
+      tokenQuote: accounts.tokenQuotePubKey,
+      lpLiquidity: accounts.lpLiquidityPubKey,
+      lpLiquidityQuoteAta: accounts.lpLiquidityQuoteAta,
+      lpFee: accounts.lpFeePubKey,
+      lpFeeQuoteAta: accounts.lpFeeQuoteAta,
+      lpShareMint: accounts.lpShareMintPubKey,
+      config: configPubKey,
+      treasury: treasuryPubKey,
+      treasuryQuoteAta: treasuryQuoteAta,
+      userQuoteAta: accounts.userQuoteAta,
+      user: wallet.payer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([wallet.payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+}
+
+export async function getCpLpAccounts(program: Program<SimpleLiquidityPool>, tokenQuote?: anchor.web3.PublicKey) {
+  const wallet = getProviderWallet();
+  const {
+    CP_LP_SEED_PREFIX,
+    CP_LP_LIQUIDITY_PREFIX,
+    CP_LP_FEE_SEED_PREFIX,
+    CP_LP_SHARE_MINT_PREFIX,
+    CP_LP_PROVIDER_SEED_PREFIX,
+  } = getThisProgramConstants(program);
+
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = tokenQuote ?? new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const findPda = (prefix: Buffer, ...keys: anchor.web3.PublicKey[]) => anchor.web3.PublicKey.findProgramAddressSync(
+    [prefix, ...keys.map(k => k.toBuffer())],
+    program.programId
+  )[0];
+
+  const lpPubKey = findPda(CP_LP_SEED_PREFIX, tokenQuotePubKey);
+  const lpLiquidityPubKey = findPda(CP_LP_LIQUIDITY_PREFIX, tokenQuotePubKey);
+  const lpFeePubKey = findPda(CP_LP_FEE_SEED_PREFIX, tokenQuotePubKey);
+  const lpShareMintPubKey = findPda(CP_LP_SHARE_MINT_PREFIX, tokenQuotePubKey);
+  const liquidityProviderPubKey = findPda(CP_LP_PROVIDER_SEED_PREFIX, lpPubKey, wallet.payer.publicKey);
+
+  return {
+    tokenQuotePubKey,
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpFeePubKey,
+    lpShareMintPubKey,
+    liquidityProviderPubKey,
+    lpLiquidityQuoteAta: await anchor.utils.token.associatedAddress({mint: tokenQuotePubKey, owner: lpLiquidityPubKey}),
+    lpFeeQuoteAta: await anchor.utils.token.associatedAddress({mint: tokenQuotePubKey, owner: lpFeePubKey}),
+    userQuoteAta: await anchor.utils.token.associatedAddress({mint: tokenQuotePubKey, owner: wallet.payer.publicKey}),
+    userShareAta: await anchor.utils.token.associatedAddress({mint: lpShareMintPubKey, owner: wallet.payer.publicKey}),
+  };
+}
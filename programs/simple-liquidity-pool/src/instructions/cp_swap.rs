@@ -0,0 +1,270 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::{
+  token,
+  token::spl_token,
+  associated_token,
+};
+use crate::instructions::swap::TransferDest;
+use crate::state::{
+  errors::LpBaseError,
+  events::{Swapped, FeeCollected},
+  ConstantProductLP, FeeSharingLP, ProgramConfig,
+  CP_LP_SEED_PREFIX, CP_LP_LIQUIDITY_PREFIX, CP_LP_FEE_SEED_PREFIX, CP_LP_SHARE_MINT_PREFIX,
+  CONFIG_SEED_PREFIX, TREASURY_SEED_PREFIX,
+};
+
+
+pub fn cp_swap(
+  ctx: Context<CpLpSwap>,
+  from: Pubkey,
+  to: Pubkey,
+  from_amount: u64,
+  min_amount_out: u64,
+) -> Result<()> {
+  let lp = &mut ctx.accounts.lp;
+
+  let (
+    swap_dir,
+    from_amount,
+    to_amount_without_fee,
+    fee_of_to_token
+  ) = lp.preview_swap(from, to, from_amount)?;
+
+  lp.swap(&swap_dir, from_amount, to_amount_without_fee)?;
+
+  let to_amount = to_amount_without_fee - fee_of_to_token;
+  require!(to_amount >= min_amount_out, LpBaseError::SlippageExceeded);
+
+  // protocol takes its cut, the rest belongs to current share holders, they can claim it later
+  let (lp_fee, protocol_fee) = ctx.accounts.config.split_swap_fee(fee_of_to_token);
+  lp.accrue_fee(&swap_dir, lp_fee, ctx.accounts.lp_share_mint.supply)?;
+
+  transfer_token_into_liquidity(&ctx, from, from_amount)?;
+  transfer_token_out_of_liquidity(&ctx, to, lp_fee, TransferDest::LpFee)?;
+  transfer_token_out_of_liquidity(&ctx, to, protocol_fee, TransferDest::Treasury)?;
+  transfer_token_out_of_liquidity(&ctx, to, to_amount, TransferDest::User)?;
+
+  let lp = &ctx.accounts.lp;
+  emit!(Swapped {
+    lp: lp.key(),
+    user: ctx.accounts.user.key(),
+    from_token: from,
+    to_token: to,
+    from_amount,
+    to_amount,
+    fee: fee_of_to_token,
+    amount_base: lp.reserve_base,
+    amount_quote: lp.reserve_quote,
+  });
+  emit!(FeeCollected {
+    lp: lp.key(),
+    token: to,
+    lp_fee,
+    protocol_fee,
+  });
+
+  Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CpLpSwap<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_SEED_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.bump,
+  )]
+  pub lp: Account<'info, ConstantProductLP>,
+
+  #[account()]
+  pub token_quote: Account<'info, token::Mint>,
+
+
+  // lp liquidity: store SOL liquidity
+  /// CHECK: Just to store SOL
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_LIQUIDITY_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.liquidity_bump,
+  )]
+  pub lp_liquidity: UncheckedAccount<'info>,
+
+  // lp liquidity: store SPL liquidity
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_liquidity,
+  )]
+  pub lp_liquidity_quote_ata: Account<'info, token::TokenAccount>,
+
+  // lp fee: store SOL fee collected, share holders can claim it
+  /// CHECK: Just to store SOL
+  #[account(
+    mut,
+    seeds = [CP_LP_FEE_SEED_PREFIX, token_quote.key().as_ref()],
+    bump = lp.fee_bump,
+  )]
+  pub lp_fee: UncheckedAccount<'info>,
+
+  // lp fee: store SPL fee collected, share holders can claim it
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_fee,
+  )]
+  pub lp_fee_quote_ata: Account<'info, token::TokenAccount>,
+
+  // lp share: supply is needed to share the fee
+  #[account(
+    seeds = [
+      CP_LP_SHARE_MINT_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.share_mint_bump,
+  )]
+  pub lp_share_mint: Account<'info, token::Mint>,
+
+  // protocol cut of the swap fee goes to treasury
+  // nothing can be done while the program is paused
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    constraint = !config.paused @ LpBaseError::ProgramPaused,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  /// CHECK: Just to store SOL
+  #[account(
+    mut,
+    seeds = [TREASURY_SEED_PREFIX],
+    bump = config.treasury_bump,
+  )]
+  pub treasury: UncheckedAccount<'info>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = treasury,
+  )]
+  pub treasury_quote_ata: Account<'info, token::TokenAccount>,
+
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = user,
+  )]
+  pub user_quote_ata: Account<'info, token::TokenAccount>,
+
+
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, token::Token>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+
+///
+/// Transfer from_token from user wallet into pool
+///
+fn transfer_token_into_liquidity<'info>(
+  ctx: &Context<CpLpSwap<'info>>,
+  for_token: Pubkey,
+  amount: u64,
+) -> Result<()> {
+  msg!("[transfer_token_into_liquidity] Transferring {} {} tokens ...", amount, for_token.key().to_string());
+
+  let is_native_and_base_token = for_token == spl_token::native_mint::id();
+
+  if is_native_and_base_token {
+    // case native SOL
+    system_program::transfer(
+      CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+          from: ctx.accounts.user.to_account_info(),
+          to: ctx.accounts.lp_liquidity.to_account_info(),
+        },
+      ),
+      amount,
+    )
+  } else {
+    // case SPL token
+    token::transfer(
+      CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        token::Transfer {
+          from: ctx.accounts.user_quote_ata.to_account_info(),
+          to: ctx.accounts.lp_liquidity_quote_ata.to_account_info(),
+          authority: ctx.accounts.user.to_account_info(),
+        },
+      ),
+      amount,
+    )
+  }
+}
+
+/// Transfer to_token from Liquidity to user, lp fee or treasury
+fn transfer_token_out_of_liquidity<'info>(
+  ctx: &Context<CpLpSwap<'info>>,
+  for_token: Pubkey,
+  amount: u64,
+  to: TransferDest,
+) -> Result<()> {
+  if amount == 0 {
+    return Ok(());
+  }
+
+  msg!("[transfer_token_out_of_liquidity] Transferring {} {} tokens ...", amount, for_token.key().to_string());
+
+  let is_native_and_base_token = for_token == spl_token::native_mint::id();
+
+  if is_native_and_base_token {
+    let destination = match to {
+      TransferDest::User => ctx.accounts.user.to_account_info(),
+      TransferDest::LpFee => ctx.accounts.lp_fee.to_account_info(),
+      TransferDest::Treasury => ctx.accounts.treasury.to_account_info(),
+    };
+    **ctx.accounts.lp_liquidity
+      .to_account_info()
+      .try_borrow_mut_lamports()? -= amount;
+    **destination.try_borrow_mut_lamports()? += amount;
+    Ok(())
+  } else {
+    let token_quote_pubkey = ctx.accounts.token_quote.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+      CP_LP_LIQUIDITY_PREFIX,
+      token_quote_pubkey.as_ref(),
+      &[ctx.accounts.lp.liquidity_bump],
+    ]];
+
+    // case SPL token
+    token::transfer(
+      CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token::Transfer {
+          from: ctx.accounts.lp_liquidity_quote_ata.to_account_info(),
+          to: match to {
+            TransferDest::User => ctx.accounts.user_quote_ata.to_account_info(),
+            TransferDest::LpFee => ctx.accounts.lp_fee_quote_ata.to_account_info(),
+            TransferDest::Treasury => ctx.accounts.treasury_quote_ata.to_account_info(),
+          },
+          authority: ctx.accounts.lp_liquidity.to_account_info(),
+        },
+        signer_seeds,
+      ),
+      amount,
+    )
+  }
+}
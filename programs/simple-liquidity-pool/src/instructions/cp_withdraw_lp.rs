@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  token,
+  token::spl_token,
+  associated_token,
+};
+use crate::instructions::share_token::burn_share;
+use crate::state::{
+  events::LiquidityWithdrawn,
+  ConstantProductLP, LiquidityProvider,
+  CP_LP_SEED_PREFIX, CP_LP_LIQUIDITY_PREFIX, CP_LP_PROVIDER_SEED_PREFIX, CP_LP_SHARE_MINT_PREFIX,
+};
+
+pub fn cp_withdraw_liquidity(
+  ctx: Context<CpLpWithdrawLiquidity>,
+  share: u64,
+) -> Result<()> {
+  // Only burn the share this user got from add_liquidity
+  // fee earned so far stays claimable in liquidity_provider
+  ctx.accounts.liquidity_provider.withdraw(&mut *ctx.accounts.lp, share)?;
+
+  let share_supply: u64 = ctx.accounts.lp_share_mint.supply;
+  let lp = &mut ctx.accounts.lp;
+  let (base_amount, quote_amount) = lp.withdraw_liquidity(share, share_supply)?;
+
+  burn_share_of_user(&ctx, share)?;
+
+  if base_amount > 0 {
+    transfer_token_out_of_pool(&ctx, spl_token::native_mint::id(), base_amount)?;
+  }
+  if quote_amount > 0 {
+    transfer_token_out_of_pool(&ctx, ctx.accounts.token_quote.key(), quote_amount)?;
+  }
+
+  let lp = &ctx.accounts.lp;
+  emit!(LiquidityWithdrawn {
+    lp: lp.key(),
+    user: ctx.accounts.user.key(),
+    token_base: lp.token_base,
+    token_quote: lp.token_quote,
+    base_amount,
+    quote_amount,
+    share,
+    amount_base: lp.reserve_base,
+    amount_quote: lp.reserve_quote,
+  });
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct CpLpWithdrawLiquidity<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_SEED_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.bump,
+  )]
+  pub lp: Account<'info, ConstantProductLP>,
+
+  #[account()]
+  pub token_quote: Account<'info, token::Mint>,
+
+
+  // lp liquidity: store SOL liquidity
+  /// CHECK: will handle validation in code if needed
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_LIQUIDITY_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.liquidity_bump,
+  )]
+  pub lp_liquidity: UncheckedAccount<'info>,
+
+  // lp liquidity: store SPL liquidity
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_liquidity,
+  )]
+  pub lp_liquidity_quote_ata: Account<'info, token::TokenAccount>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = user,
+  )]
+  pub user_quote_ata: Account<'info, token::TokenAccount>,
+
+  // lp share: burned on withdrawal
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_SHARE_MINT_PREFIX,
+      token_quote.key().as_ref()
+    ],
+    bump = lp.share_mint_bump,
+  )]
+  pub lp_share_mint: Account<'info, token::Mint>,
+
+  #[account(
+    mut,
+    associated_token::mint = lp_share_mint,
+    associated_token::authority = user,
+  )]
+  pub user_share_ata: Account<'info, token::TokenAccount>,
+
+  // seeds contain user key, so a user can only withdraw from his own record
+  #[account(
+    mut,
+    seeds = [
+      CP_LP_PROVIDER_SEED_PREFIX,
+      lp.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump = liquidity_provider.bump,
+  )]
+  pub liquidity_provider: Account<'info, LiquidityProvider>,
+
+
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, token::Token>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+
+///
+/// Burn lp share from user wallet, see share_token.rs
+///
+fn burn_share_of_user<'info>(
+  ctx: &Context<CpLpWithdrawLiquidity<'info>>,
+  share: u64,
+) -> Result<()> {
+  msg!("[burn_share_of_user] Burning {} share ...", share);
+
+  let token_quote_pubkey = ctx.accounts.token_quote.key();
+  let lp_seeds: &[&[u8]] = &[
+    CP_LP_SEED_PREFIX,
+    token_quote_pubkey.as_ref(),
+    &[ctx.accounts.lp.bump],
+  ];
+
+  burn_share(
+    &ctx.accounts.lp_share_mint.to_account_info(),
+    &ctx.accounts.user_share_ata,
+    &ctx.accounts.user.to_account_info(),
+    &ctx.accounts.lp.to_account_info(),
+    lp_seeds,
+    &ctx.accounts.token_program.to_account_info(),
+    share,
+  )
+}
+
+///
+/// Transfer token from pool back to user wallet
+/// Amounts are taken from the cached reserves, so rent-exempt lamports are never touched
+///
+fn transfer_token_out_of_pool<'info>(
+  ctx: &Context<CpLpWithdrawLiquidity<'info>>,
+  for_token: Pubkey,
+  amount: u64,
+) -> Result<()> {
+  msg!("[transfer_token_out_of_pool] Transferring {} {} tokens ...", amount, for_token.key().to_string());
+
+  let is_native_and_base_token = for_token == spl_token::native_mint::id();
+
+  if is_native_and_base_token {
+    // case native SOL
+    **ctx.accounts.lp_liquidity
+      .to_account_info()
+      .try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.user
+      .to_account_info()
+      .try_borrow_mut_lamports()? += amount;
+    Ok(())
+  } else {
+    // case SPL token
+    let token_quote_pubkey = ctx.accounts.token_quote.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+      CP_LP_LIQUIDITY_PREFIX,
+      token_quote_pubkey.as_ref(),
+      &[ctx.accounts.lp.liquidity_bump],
+    ]];
+
+    token::transfer(
+      CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token::Transfer {
+          from: ctx.accounts.lp_liquidity_quote_ata.to_account_info(),
+          to: ctx.accounts.user_quote_ata.to_account_info(),
+          authority: ctx.accounts.lp_liquidity.to_account_info(),
+        },
+        signer_seeds,
+      ),
+      amount,
+    )
+  }
+}
@@ -1,35 +1,58 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
   token,
-  token::spl_token,
+  token_interface,
   associated_token,
 };
-use crate::state::{FixedRateLP, LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_FEE_SEED_PREFIX};
+use crate::state::{
+  errors::LpBaseError,
+  events::PoolInitialized,
+  FixedRateLP, LpInitParams, ProgramConfig,
+  CONFIG_SEED_PREFIX,
+  LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_FEE_SEED_PREFIX, LP_SHARE_MINT_PREFIX,
+};
+
 
+pub fn init(ctx: Context<LpInit>, fixed_rate: u32, fee_bps: Option<u16>, pool_index: u16) -> Result<()> {
+  let quote_mint_authority: Option<Pubkey> = ctx.accounts.token_quote.mint_authority.into();
+  ctx.accounts.config.require_can_create_pool(ctx.accounts.user.key(), quote_mint_authority)?;
+
+  let fee_bps = fee_bps.unwrap_or(ctx.accounts.config.default_fee_bps);
 
-pub fn init(ctx: Context<LpInit>, fixed_rate: u32) -> Result<()> {
   let lp = &mut ctx.accounts.lp;
   // msg!("Initializing liquidity pool {:?}", lp);
 
-  let lp_bump = *ctx.bumps.get("lp").unwrap();
-  let lp_liquidity_bump = *ctx.bumps.get("lp_liquidity").unwrap();
-  let lp_fee_bump = *ctx.bumps.get("lp_fee").unwrap();
-  lp.init(
-    spl_token::native_mint::id(),
-    ctx.accounts.token_quote.key(),
+  lp.init(LpInitParams {
+    authority: ctx.accounts.user.key(),
+    token_base: ctx.accounts.token_base.key(),
+    token_quote: ctx.accounts.token_quote.key(),
+    pool_index,
     fixed_rate,
-    lp_bump,
-    lp_liquidity_bump,
-    lp_fee_bump,
-  )?;
+    fee_bps,
+    bump: *ctx.bumps.get("lp").unwrap(),
+    liquidity_bump: *ctx.bumps.get("lp_liquidity").unwrap(),
+    fee_bump: *ctx.bumps.get("lp_fee").unwrap(),
+    share_mint_bump: *ctx.bumps.get("lp_share_mint").unwrap(),
+  })?;
+
+  emit!(PoolInitialized {
+    lp: lp.key(),
+    authority: lp.authority,
+    token_base: lp.token_base,
+    token_quote: lp.token_quote,
+    pool_index: lp.pool_index,
+    rate: lp.rate,
+    fee_bps: lp.fee_bps,
+  });
 
   Ok(())
 }
 
 
 #[derive(Accounts)]
+#[instruction(fixed_rate: u32, fee_bps: Option<u16>, pool_index: u16)]
 pub struct LpInit<'info> {
-  // Can be hacked? because public auth here
+  // The user who init the lp will become the lp authority
   // lp state data
   #[account(
     init,
@@ -37,69 +60,133 @@ pub struct LpInit<'info> {
     space = 8 + FixedRateLP::MAXIMUM_SIZE,
     seeds = [
       LP_SEED_PREFIX,
-      token_quote.key().as_ref()
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).0.as_ref(),
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).1.as_ref(),
+      pool_index.to_le_bytes().as_ref(),
     ],
     bump,
   )]
   pub lp: Account<'info, FixedRateLP>,
 
-  // base Token Mint Address: Read more in README.md
-  // #[account()]
-  // pub token_base: Account<'info, token::Mint>,
-  #[account()]
-  pub token_quote: Account<'info, token::Mint>,
+  // base, quote Token Mint Address: Read more in README.md
+  #[account(mint::token_program = token_base_program)]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(
+    mint::token_program = token_quote_program,
+    constraint = token_quote.key() != token_base.key() @ LpBaseError::InvalidTokenPair,
+  )]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
 
-  // lp liquidity: store SOL liquidity
-  // `lp` account is state, contain data so cannot be used as SOL sender
-  /// CHECK: Just to store SOL
+  // lp liquidity: owner of the liquidity token accounts
+  /// CHECK: data-less PDA, only signs for its token accounts
   #[account(
     init,
     payer = user,
-    space = 8 + 8,
+    space = FixedRateLP::SOL_HOLDER_SIZE,
     seeds = [
       LP_LIQUIDITY_PREFIX,
-      token_quote.key().as_ref()
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).0.as_ref(),
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).1.as_ref(),
+      pool_index.to_le_bytes().as_ref(),
     ],
     bump,
   )]
   pub lp_liquidity: UncheckedAccount<'info>,
 
-  // lp liquidity: store SPL liquidity
+  // lp liquidity: store token liquidity, wrapped SOL included
+  #[account(
+    init,
+    payer = user,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_liquidity_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
   #[account(
     init,
     payer = user,
     associated_token::mint = token_quote,
     associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_quote_program,
   )]
-  pub lp_liquidity_quote_ata: Account<'info, token::TokenAccount>,
+  pub lp_liquidity_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-  // lp fee: store SOL fee collected, for profit sharing later
-  /// CHECK: Just to store SOL
+  // lp fee: owner of the fee token accounts
+  /// CHECK: data-less PDA, only signs for its token accounts
   #[account(
     init,
     payer = user,
-    space = 8 + 8,
-    seeds = [LP_FEE_SEED_PREFIX, token_quote.key().as_ref()],
+    space = FixedRateLP::SOL_HOLDER_SIZE,
+    seeds = [
+      LP_FEE_SEED_PREFIX,
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).0.as_ref(),
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).1.as_ref(),
+      pool_index.to_le_bytes().as_ref(),
+    ],
     bump,
   )]
   pub lp_fee: UncheckedAccount<'info>,
 
-  // lp fee: store SPL fee collected, for profit sharing later
+  // lp fee: store token fee collected, share holders can claim it
+  #[account(
+    init,
+    payer = user,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_fee,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_fee_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
   #[account(
     init,
     payer = user,
     associated_token::mint = token_quote,
     associated_token::authority = lp_fee,
+    associated_token::token_program = token_quote_program,
   )]
-  pub lp_fee_quote_ata: Account<'info, token::TokenAccount>,
+  pub lp_fee_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
+  // lp share: minted to liquidity providers, lp is the mint authority
+  // share is valued in base token, so it has the same decimal
+  // lp is also the freeze authority, share accounts stay frozen: see share_token.rs
+  // close_pool cannot close the mint, so re-init of a closed pool_index reuses it with no share left
+  #[account(
+    init_if_needed,
+    payer = user,
+    mint::decimals = token_base.decimals,
+    mint::authority = lp,
+    mint::freeze_authority = lp,
+    seeds = [
+      LP_SHARE_MINT_PREFIX,
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).0.as_ref(),
+      FixedRateLP::sorted_mints(token_base.key(), token_quote.key()).1.as_ref(),
+      pool_index.to_le_bytes().as_ref(),
+    ],
+    bump,
+    constraint = lp_share_mint.supply == 0 @ LpBaseError::PoolNotEmpty,
+  )]
+  pub lp_share_mint: Account<'info, token::Mint>,
+
+
+  // global config: nothing can be done while the program is paused
+  // it also decides who can create a lp, see ProgramConfig::require_can_create_pool
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    constraint = !config.paused @ LpBaseError::ProgramPaused,
+  )]
+  pub config: Account<'info, ProgramConfig>,
 
   #[account(mut)]
   pub user: Signer<'info>,
 
   pub rent: Sysvar<'info, Rent>,
   pub system_program: Program<'info, System>,
+  // share mint is a legacy SPL token, base and quote can be either SPL token or Token-2022
   pub token_program: Program<'info, token::Token>,
+  pub token_base_program: Interface<'info, token_interface::TokenInterface>,
+  pub token_quote_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
 }
@@ -2,12 +2,12 @@ import * as anchor from "@project-serum/anchor";
 import {Program} from "@project-serum/anchor";
 import {SimpleLiquidityPool, IDL as SimpleLiquidityPoolIdl} from "../../../../target/types/simple_liquidity_pool";
 import {sleep} from "../../../../tests/helpers/time";
-import {getProgramConstant, getProgramIdlConstant, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {getCurrentProvider, getProgramConstant, getProgramIdlConstant, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
 import {assert, expect} from "chai";
-import {NATIVE_MINT, NATIVE_MINT_2022} from "@solana/spl-token"
+import {NATIVE_MINT, NATIVE_MINT_2022, createMint} from "@solana/spl-token"
 import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
 import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
-import {getThisProgramConstants} from "./utils.test";
+import {getConfigAccounts, getLpAccounts, getThisProgramConstants} from "./utils.test";
 
 
 export default function test__init(program: Program<SimpleLiquidityPool>) {
@@ -16,6 +16,11 @@ export default function test__init(program: Program<SimpleLiquidityPool>) {
   it("can init lp and can init only once", async () => test_init_lp_only_once(program));
 
   it("Other wallet cannot init same pair", async () => test_reinit_lp_by_other_wallet(program));
+  it("Cannot init same pair in reverse order", async () => test_init_reversed_pair(program));
+  it("Cannot init lp with the same base and quote token", async () => test_init_same_token_pair(program));
+  it("Can init another lp of the same pair with another pool index", async () => test_init_other_pool_index(program));
+  it("Wallet without pool creation right cannot init lp", async () => test_init_by_not_allowed_creator(program));
+  it("Quote mint authority can init lp", async () => test_init_by_quote_mint_authority(program));
 }
 
 /**
@@ -29,12 +34,12 @@ async function test_init_lp_only_once(program: Program<SimpleLiquidityPool>) {
 
   const wallet = getProviderWallet();
 
-  // const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
   const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
   const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
   const {tx, liquidityPoolPubKey} = await init_new_lp(
     program,
-    // tokenBasePubKey,
+    tokenBasePubKey,
     tokenQuotePubKey,
     wallet.payer,
   );
@@ -44,7 +49,7 @@ async function test_init_lp_only_once(program: Program<SimpleLiquidityPool>) {
   try {
     const {tx} = await init_new_lp(
       program,
-      // tokenBasePubKey,
+      tokenBasePubKey,
       tokenQuotePubKey,
       wallet.payer,
       false,
@@ -61,6 +66,7 @@ async function test_init_lp_only_once(program: Program<SimpleLiquidityPool>) {
   // Account must be created
   const lpAccount = await program.account.fixedRateLp.fetch(liquidityPoolPubKey);
   assert(lpAccount.bump > 0, "Bump must be saved in state");
+  assert(lpAccount.authority.equals(wallet.payer.publicKey), "Init wallet must be the lp authority");
 }
 
 async function test_reinit_lp_by_other_wallet(program: Program<SimpleLiquidityPool>) {
@@ -70,7 +76,7 @@ async function test_reinit_lp_by_other_wallet(program: Program<SimpleLiquidityPo
   const walletKeyPair = anchor.web3.Keypair.generate();
   await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
 
-  // const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
   const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
   const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
 
@@ -78,7 +84,7 @@ async function test_reinit_lp_by_other_wallet(program: Program<SimpleLiquidityPo
   try {
     const {tx} = await init_new_lp(
       program,
-      // tokenBasePubKey,
+      tokenBasePubKey,
       tokenQuotePubKey,
       walletKeyPair,
       false,
@@ -93,76 +99,159 @@ async function test_reinit_lp_by_other_wallet(program: Program<SimpleLiquidityPo
   expect(tx2).to.be.empty;
 }
 
-async function init_new_lp(
+async function test_init_reversed_pair(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  // <quote, base> resolves to the same lp as <base, quote>
+  let tx2 = "";
+  try {
+    const {tx} = await init_new_lp(
+      program,
+      tokenQuotePubKey,
+      tokenBasePubKey,
+      wallet.payer,
+      false,
+    );
+    tx2 = tx;
+  } catch (e) {
+    expect(e.message.endsWith("error: 0x0"))
+  }
+  expect(tx2).to.be.empty;
+}
+
+async function test_init_same_token_pair(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  let tx2 = "";
+  try {
+    const {tx} = await init_new_lp(
+      program,
+      tokenQuotePubKey,
+      tokenQuotePubKey,
+      wallet.payer,
+      false,
+    );
+    tx2 = tx;
+  } catch (e) {
+    assert(e.message.indexOf("InvalidTokenPair") > -1, "Should throw InvalidTokenPair");
+  }
+  expect(tx2).to.be.empty;
+}
+
+async function test_init_other_pool_index(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  const poolIndex = 1;
+  const {tx, liquidityPoolPubKey} = await init_new_lp(
+    program,
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    wallet.payer,
+    true,
+    anchor.utils.token.TOKEN_PROGRAM_ID,
+    poolIndex,
+  );
+  assert(!!tx, "Tx should not be empty");
+
+  const {lpPubKey: defaultLpPubKey} = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+  assert(!liquidityPoolPubKey.equals(defaultLpPubKey), "Another pool index must be another lp");
+  const lpAccount = await program.account.fixedRateLp.fetch(liquidityPoolPubKey);
+  expect(lpAccount.poolIndex).to.be.eq(poolIndex);
+}
+
+async function test_init_by_not_allowed_creator(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(1, walletKeyPair.publicKey);
+
+  // quote mint authority is the provider wallet, not the creator
+  const tokenQuotePubKey = await createMint(getCurrentProvider().connection, wallet.payer, wallet.publicKey, null, 6);
+
+  let tx2 = "";
+  try {
+    const {tx} = await init_new_lp(program, NATIVE_MINT, tokenQuotePubKey, walletKeyPair, false);
+    tx2 = tx;
+  } catch (e) {
+    assert(e.message.indexOf("PoolCreatorNotAllowed") > -1, "Should throw PoolCreatorNotAllowed");
+  }
+  expect(tx2).to.be.empty;
+}
+
+async function test_init_by_quote_mint_authority(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(1, walletKeyPair.publicKey);
+
+  // token launcher creates the pool of his own token
+  const tokenQuotePubKey = await createMint(getCurrentProvider().connection, walletKeyPair, walletKeyPair.publicKey, null, 6);
+
+  const {tx, liquidityPoolPubKey} = await init_new_lp(program, NATIVE_MINT, tokenQuotePubKey, walletKeyPair);
+  assert(!!tx, "Tx should not be empty");
+
+  const lpAccount = await program.account.fixedRateLp.fetch(liquidityPoolPubKey);
+  assert(lpAccount.authority.equals(walletKeyPair.publicKey), "Mint authority must be the lp authority");
+}
+
+export async function init_new_lp(
   program: Program<SimpleLiquidityPool>,
-  // base: anchor.web3.PublicKey,
+  base: anchor.web3.PublicKey,
   quote: anchor.web3.PublicKey,
   authority: anchor.web3.Keypair,
   logError = true,
+  quoteTokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID,
+  poolIndex = 0,
 ) {
+  const {LP_RATE_DECIMAL} = getThisProgramConstants(program);
+
   const {
-    LP_SEED_PREFIX,
-    LP_LIQUIDITY_PREFIX,
-    LP_FEE_SEED_PREFIX,
-    LP_RATE_DECIMAL,
-  } = getThisProgramConstants(program);
-
-  const [lpPubKey, bump] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_SEED_PREFIX,
-      // base.toBuffer(),
-      quote.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpFeePubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_FEE_SEED_PREFIX,
-      quote.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpLiquidityPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_LIQUIDITY_PREFIX,
-      quote.toBuffer(),
-    ],
-    program.programId
-  ))
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpFeePubKey,
+    lpFeeBaseAta,
+    lpFeeQuoteAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, base, quote, quoteTokenProgram, poolIndex);
   VERBOSE && console.log('{init_new_lp} liquidityPoolPubKey, FeePubKey: ', {
     lpPubKey: lpPubKey.toString(),
     lpFeePubKey: lpFeePubKey.toString(),
     lpLiquidityPubKey: lpLiquidityPubKey.toString(),
   });
 
-
-  // const baseAta = await anchor.utils.token.associatedAddress({
-  //   mint: base,
-  //   owner: liquidityPoolPubKey
-  // });
-  const lpLiquidityQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: quote,
-    owner: lpLiquidityPubKey
-    // owner: lpPubKey,
-  });
-  const lpFeeQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: quote,
-    owner: lpFeePubKey
-  });
+  const {configPubKey} = await getConfigAccounts(program, quote);
 
   const fixedRate = 10;
-  const tx = await program.methods.initialize(fixedRate * Math.pow(10, LP_RATE_DECIMAL))
+  const feeBps = 500; // 5%
+  const tx = await program.methods.initialize(fixedRate * Math.pow(10, LP_RATE_DECIMAL), feeBps, poolIndex)
     .accounts({
       lp: lpPubKey,
+      tokenBase: base,
       tokenQuote: quote,
       lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
       lpLiquidityQuoteAta: lpLiquidityQuoteAta,
       lpFee: lpFeePubKey,
+      lpFeeBaseAta: lpFeeBaseAta,
       lpFeeQuoteAta: lpFeeQuoteAta,
+      lpShareMint: lpShareMintPubKey,
+      config: configPubKey,
       user: authority.publicKey,
       rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: quoteTokenProgram,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     })
     .signers([authority])
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::program::SimpleLiquidityPool;
+use crate::state::{
+  errors::LpBaseError,
+  ProgramConfig,
+  CONFIG_SEED_PREFIX, TREASURY_SEED_PREFIX,
+};
+
+///
+/// Init the global program config, can be called only once
+/// Only the program upgrade authority can call it, and becomes the admin
+///
+pub fn init_config(ctx: Context<ConfigInit>, protocol_fee_share_bps: u16, default_fee_bps: u16) -> Result<()> {
+  let config = &mut ctx.accounts.config;
+
+  let config_bump = *ctx.bumps.get("config").unwrap();
+  let treasury_bump = *ctx.bumps.get("treasury").unwrap();
+  config.init(
+    ctx.accounts.admin.key(),
+    ctx.accounts.treasury.key(),
+    protocol_fee_share_bps,
+    default_fee_bps,
+    config_bump,
+    treasury_bump,
+  )
+}
+
+
+#[derive(Accounts)]
+pub struct ConfigInit<'info> {
+  #[account(
+    init,
+    payer = admin,
+    space = 8 + ProgramConfig::MAXIMUM_SIZE,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  // treasury: store SOL protocol fee, and own the SPL protocol fee ATAs
+  /// CHECK: Just to store SOL
+  #[account(
+    init,
+    payer = admin,
+    space = ProgramConfig::SOL_HOLDER_SIZE,
+    seeds = [TREASURY_SEED_PREFIX],
+    bump,
+  )]
+  pub treasury: UncheckedAccount<'info>,
+
+  #[account(mut)]
+  pub admin: Signer<'info>,
+
+  // the deployer, not whoever comes first: admin must be the program upgrade authority
+  #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+  pub program: Program<'info, SimpleLiquidityPool>,
+  #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ LpBaseError::InvalidUpgradeAuthority)]
+  pub program_data: Account<'info, ProgramData>,
+
+  pub system_program: Program<'info, System>,
+}
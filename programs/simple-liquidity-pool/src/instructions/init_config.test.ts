@@ -0,0 +1,238 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {Keypair} from "@solana/web3.js";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getConfigAccounts} from "./utils.test";
+
+
+export default function test__init_config(program: Program<SimpleLiquidityPool>) {
+  // NOTE: config is global, this test must run only once per program deployment
+  it("only the upgrade authority can init program config, and only once", async () => test__init_config_only_once(program));
+  it("Admin can update protocol fee share", async () => test__update_config_by_admin(program));
+  it("Other wallet cannot update config", async () => test__update_config_by_other_wallet(program));
+  it("Admin can pause and unpause the program", async () => test__pause_by_admin(program));
+  it("Admin handover needs propose and accept", async () => test__admin_handover(program));
+  it("Only admin can change who can create a lp", async () => test__set_pool_creation(program));
+}
+
+async function test__init_config_only_once(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {configPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+
+  const protocolFeeShareBps = 1667; // ~1/6 of the swap fee
+  const defaultFeeBps = 30; // 0.3%
+
+  // only the upgrade authority (the deployer wallet) can init the config
+  const otherWallet = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, otherWallet.publicKey);
+  let otherTx = "";
+  try {
+    otherTx = await init_config(program, {protocolFeeShareBps, defaultFeeBps, admin: otherWallet, showException: false});
+  } catch (e) {
+    assert(e.message.indexOf("InvalidUpgradeAuthority") > -1, "Should throw InvalidUpgradeAuthority");
+  }
+  expect(otherTx).to.be.empty;
+
+  const tx = await init_config(program, {protocolFeeShareBps, defaultFeeBps, admin: wallet.payer, showException: true});
+  assert(!!tx, "Tx should not be empty");
+
+  let tx2 = "";
+  try {
+    tx2 = await init_config(program, {protocolFeeShareBps, defaultFeeBps, admin: wallet.payer, showException: false});
+  } catch (e) {
+    // account already in use
+  }
+  expect(tx2).to.be.empty;
+
+  const config = await program.account.programConfig.fetch(configPubKey);
+  assert(config.admin.equals(wallet.payer.publicKey), "Init wallet must be the admin");
+  expect(config.protocolFeeShareBps).to.be.eq(protocolFeeShareBps);
+  expect(config.defaultFeeBps).to.be.eq(defaultFeeBps);
+  expect(config.paused).to.be.false;
+}
+
+async function test__update_config_by_admin(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {configPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+  const config = await program.account.programConfig.fetch(configPubKey);
+
+  await update_config(program, {protocolFeeShareBps: 1000, defaultFeeBps: 100, admin: wallet.payer, showException: true});
+  expect((await program.account.programConfig.fetch(configPubKey)).protocolFeeShareBps).to.be.eq(1000);
+
+  // Revert, other tests are expecting the original one
+  await update_config(program, {
+    protocolFeeShareBps: config.protocolFeeShareBps,
+    defaultFeeBps: config.defaultFeeBps,
+    admin: wallet.payer,
+    showException: true,
+  });
+  expect((await program.account.programConfig.fetch(configPubKey)).protocolFeeShareBps).to.be.eq(config.protocolFeeShareBps);
+}
+
+async function test__update_config_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await update_config(program, {protocolFeeShareBps: 0, defaultFeeBps: 0, admin: walletKeyPair, showException: false});
+  } catch (e) {
+    assert(e.message.indexOf("InvalidAdmin") > -1, "Should throw InvalidAdmin");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__pause_by_admin(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {configPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+
+  await program.methods.setPaused(true)
+    .accounts({config: configPubKey, admin: wallet.payer.publicKey})
+    .signers([wallet.payer])
+    .rpc();
+  expect((await program.account.programConfig.fetch(configPubKey)).paused).to.be.true;
+
+  // Unpause, other tests need to swap
+  await program.methods.setPaused(false)
+    .accounts({config: configPubKey, admin: wallet.payer.publicKey})
+    .signers([wallet.payer])
+    .rpc();
+  expect((await program.account.programConfig.fetch(configPubKey)).paused).to.be.false;
+}
+
+async function test__admin_handover(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {configPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+  const newAdmin = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, newAdmin.publicKey);
+
+  // cannot accept without a proposal
+  let tx = "";
+  try {
+    tx = await program.methods.acceptAdmin()
+      .accounts({config: configPubKey, pendingAdmin: newAdmin.publicKey})
+      .signers([newAdmin])
+      .rpc();
+  } catch (e) {
+    assert(e.message.indexOf("InvalidPendingAdmin") > -1, "Should throw InvalidPendingAdmin");
+  }
+  expect(tx).to.be.empty;
+
+  await program.methods.proposeAdmin(newAdmin.publicKey)
+    .accounts({config: configPubKey, admin: wallet.payer.publicKey})
+    .signers([wallet.payer])
+    .rpc();
+  // proposing does not change the admin yet
+  assert((await program.account.programConfig.fetch(configPubKey)).admin.equals(wallet.payer.publicKey), "Admin must not change before accept");
+
+  await program.methods.acceptAdmin()
+    .accounts({config: configPubKey, pendingAdmin: newAdmin.publicKey})
+    .signers([newAdmin])
+    .rpc();
+  assert((await program.account.programConfig.fetch(configPubKey)).admin.equals(newAdmin.publicKey), "New admin must be set after accept");
+
+  // Hand it back, other tests are expecting the provider wallet as admin
+  await program.methods.proposeAdmin(wallet.payer.publicKey)
+    .accounts({config: configPubKey, admin: newAdmin.publicKey})
+    .signers([newAdmin])
+    .rpc();
+  await program.methods.acceptAdmin()
+    .accounts({config: configPubKey, pendingAdmin: wallet.payer.publicKey})
+    .signers([wallet.payer])
+    .rpc();
+  assert((await program.account.programConfig.fetch(configPubKey)).admin.equals(wallet.payer.publicKey), "Admin must be handed back");
+}
+
+async function test__set_pool_creation(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {configPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+  const otherWallet = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, otherWallet.publicKey);
+
+  // pool creation is permissioned by default
+  expect((await program.account.programConfig.fetch(configPubKey)).permissionlessInit).to.be.false;
+
+  let tx = "";
+  try {
+    tx = await program.methods.setPoolCreation(true, anchor.web3.PublicKey.default)
+      .accounts({config: configPubKey, admin: otherWallet.publicKey})
+      .signers([otherWallet])
+      .rpc();
+  } catch (e) {
+    assert(e.message.indexOf("InvalidAdmin") > -1, "Should throw InvalidAdmin");
+  }
+  expect(tx).to.be.empty;
+
+  await program.methods.setPoolCreation(false, otherWallet.publicKey)
+    .accounts({config: configPubKey, admin: wallet.payer.publicKey})
+    .signers([wallet.payer])
+    .rpc();
+  assert((await program.account.programConfig.fetch(configPubKey)).poolCreator.equals(otherWallet.publicKey), "Pool creator must be approved");
+
+  // Revert, other tests are expecting no approved creator
+  await program.methods.setPoolCreation(false, anchor.web3.PublicKey.default)
+    .accounts({config: configPubKey, admin: wallet.payer.publicKey})
+    .signers([wallet.payer])
+    .rpc();
+  assert((await program.account.programConfig.fetch(configPubKey)).poolCreator.equals(anchor.web3.PublicKey.default), "Pool creator must be reset");
+}
+
+export async function init_config(program: Program<SimpleLiquidityPool>, option: {
+  protocolFeeShareBps: number,
+  defaultFeeBps: number,
+  admin: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{init_config} : ', Date.now());
+  const {protocolFeeShareBps, defaultFeeBps, admin, showException} = option;
+  const {configPubKey, treasuryPubKey, programDataPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+
+  const tx = await program.methods.initConfig(protocolFeeShareBps, defaultFeeBps)
+    .accounts({
+      config: configPubKey,
+      treasury: treasuryPubKey,
+      admin: admin.publicKey,
+      program: program.programId,
+      programData: programDataPubKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([admin])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{init_config} tx: ', tx);
+
+  return tx;
+}
+
+export async function update_config(program: Program<SimpleLiquidityPool>, option: {
+  protocolFeeShareBps: number,
+  defaultFeeBps: number,
+  admin: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{update_config} : ', Date.now());
+  const {protocolFeeShareBps, defaultFeeBps, admin, showException} = option;
+  const {configPubKey} = await getConfigAccounts(program, NATIVE_MINT);
+
+  const tx = await program.methods.updateConfig(protocolFeeShareBps, defaultFeeBps)
+    .accounts({
+      config: configPubKey,
+      admin: admin.publicKey,
+    })
+    .signers([admin])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{update_config} tx: ', tx);
+
+  return tx;
+}
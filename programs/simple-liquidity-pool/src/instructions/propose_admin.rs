@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+  errors::LpBaseError,
+  ProgramConfig,
+  CONFIG_SEED_PREFIX,
+};
+
+///
+/// Step 1 of admin handover, new_admin must call accept_admin to finish it
+///
+pub fn propose_admin(ctx: Context<ConfigProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+  let config = &mut ctx.accounts.config;
+  msg!("[propose_admin] pending_admin: {} => {}", config.pending_admin, new_admin);
+
+  config.propose_admin(new_admin);
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct ConfigProposeAdmin<'info> {
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    has_one = admin @ LpBaseError::InvalidAdmin,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  pub admin: Signer<'info>,
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+  errors::LpBaseError,
+  FixedRateLP, ProgramConfig, SwapDir,
+  LP_SEED_PREFIX, CONFIG_SEED_PREFIX,
+};
+
+///
+/// Result of FixedRateLP::preview_swap, same order as its tuple
+///
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SwapQuote {
+  pub swap_dir: SwapDir,
+  pub from_amount: u64,
+  pub to_amount_without_fee: u64,
+  /// deducted on to token, user receives to_amount_without_fee - fee
+  pub fee: u64,
+}
+
+///
+/// Read-only: run preview_swap against the live reserves, nothing is written and nobody signs
+/// Anchor sends the returned quote with set_return_data, so clients can simulate this instruction
+/// instead of reimplementing the swap math
+///
+/// Fails like swap would when the program is paused or the lp status does not allow swaps
+///
+/// NOTE: a Token-2022 transfer fee on from token is not accounted, the lp swaps what it receives
+///
+pub fn quote_swap(ctx: Context<LpQuoteSwap>, from: Pubkey, to: Pubkey, from_amount: u64) -> Result<SwapQuote> {
+  ctx.accounts.lp.require_can_swap()?;
+
+  let (
+    swap_dir,
+    from_amount,
+    to_amount_without_fee,
+    fee,
+  ) = ctx.accounts.lp.preview_swap(from, to, from_amount)?;
+
+  Ok(SwapQuote {
+    swap_dir,
+    from_amount,
+    to_amount_without_fee,
+    fee,
+  })
+}
+
+
+#[derive(Accounts)]
+pub struct LpQuoteSwap<'info> {
+  #[account(
+    seeds = [
+      LP_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.bump,
+  )]
+  pub lp: Account<'info, FixedRateLP>,
+
+  // global config: nothing can be done while the program is paused
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    constraint = !config.paused @ LpBaseError::ProgramPaused,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+}
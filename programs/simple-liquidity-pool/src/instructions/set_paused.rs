@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+  errors::LpBaseError,
+  ProgramConfig,
+  CONFIG_SEED_PREFIX,
+};
+
+///
+/// Halt (or resume) initialize, add_liquidity and swap of all pools
+///
+pub fn set_paused(ctx: Context<ConfigSetPaused>, paused: bool) -> Result<()> {
+  let config = &mut ctx.accounts.config;
+  msg!("[set_paused] paused: {} => {}", config.paused, paused);
+
+  config.set_paused(paused);
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct ConfigSetPaused<'info> {
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    has_one = admin @ LpBaseError::InvalidAdmin,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  pub admin: Signer<'info>,
+}
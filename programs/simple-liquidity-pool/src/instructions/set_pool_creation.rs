@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+  errors::LpBaseError,
+  ProgramConfig,
+  CONFIG_SEED_PREFIX,
+};
+
+///
+/// Open initialize to everyone, or restrict it to admin, pool_creator and the quote mint authority
+///
+pub fn set_pool_creation(ctx: Context<ConfigSetPoolCreation>, permissionless_init: bool, pool_creator: Pubkey) -> Result<()> {
+  let config = &mut ctx.accounts.config;
+  msg!("[set_pool_creation] permissionless_init: {} => {}", config.permissionless_init, permissionless_init);
+  msg!("[set_pool_creation] pool_creator: {} => {}", config.pool_creator, pool_creator);
+
+  config.set_pool_creation(permissionless_init, pool_creator);
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct ConfigSetPoolCreation<'info> {
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    has_one = admin @ LpBaseError::InvalidAdmin,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  pub admin: Signer<'info>,
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+use crate::state::{
+  errors::LpBaseError,
+  events::PoolUpdated,
+  FixedRateLP, LpStatus,
+  LP_SEED_PREFIX,
+};
+
+pub fn set_status(ctx: Context<LpSetStatus>, status: LpStatus) -> Result<()> {
+  let lp = &mut ctx.accounts.lp;
+  msg!("[set_status] status: {:?} => {:?}", lp.status, status);
+
+  lp.set_status(status);
+
+  emit_pool_updated(lp);
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct LpSetStatus<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      LP_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.bump,
+    has_one = token_base @ LpBaseError::InvalidTokenPair,
+    has_one = token_quote @ LpBaseError::InvalidTokenPair,
+    has_one = authority @ LpBaseError::InvalidAuthority,
+  )]
+  pub lp: Account<'info, FixedRateLP>,
+
+  #[account()]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account()]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+  pub authority: Signer<'info>,
+}
+
+
+/// PoolUpdated with the lp state after set_status, update_rate or update_fee
+pub(crate) fn emit_pool_updated(lp: &Account<FixedRateLP>) {
+  emit!(PoolUpdated {
+    lp: lp.key(),
+    authority: lp.authority,
+    status: lp.status,
+    rate: lp.rate,
+    fee_bps: lp.fee_bps,
+  });
+}
@@ -0,0 +1,131 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair} from "@solana/web3.js";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getConfigAccounts, getLpAccounts, getThisProgramConstants, getTxEvents} from "./utils.test";
+import {add_liquidity_to_exist_lp} from "./add_lp.test";
+import {swap_exact_out} from "./swap_exact_out.test";
+
+
+export default function test__set_status(program: Program<SimpleLiquidityPool>) {
+  it("Cannot swap when lp swaps are paused", async () => test__swaps_paused(program));
+  it("Cannot add liquidity when lp is withdraw only", async () => test__withdraw_only(program));
+  it("Other wallet cannot set lp status", async () => test__set_status_by_other_wallet(program));
+}
+
+async function test__swaps_paused(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {TOKEN_DECIMAL} = getThisProgramConstants(program);
+  const {tokenQuotePubKey} = await getSetStatusAccounts(program);
+
+  const setTx = await set_status(program, {status: {swapsPaused: {}}, authority: wallet.payer, showException: true});
+  const updated = (await getTxEvents(program, setTx)).find(e => e.name === "PoolUpdated");
+  assert(!!updated && "swapsPaused" in updated.data.status, "set_status must emit PoolUpdated with the new status");
+
+  let tx = "";
+  try {
+    tx = await swap_exact_out(program, {
+      from: NATIVE_MINT,
+      to: tokenQuotePubKey,
+      toAmount: 0.1 * Math.pow(10, TOKEN_DECIMAL),
+      maxAmountIn: 1e9,
+      payer: wallet.payer,
+      showException: false,
+    });
+  } catch (e) {
+    assert(e.message.indexOf("SwapsPaused") > -1, "Should throw SwapsPaused");
+  }
+  expect(tx).to.be.empty;
+
+  // quote_swap runs the same status check, a paused lp gives no quote
+  const {lpPubKey} = await getLpAccounts(program, NATIVE_MINT, tokenQuotePubKey);
+  const {configPubKey} = await getConfigAccounts(program, tokenQuotePubKey);
+  let quote = null;
+  try {
+    quote = await program.methods.quoteSwap(NATIVE_MINT, tokenQuotePubKey, new anchor.BN(1e9))
+      .accounts({lp: lpPubKey, config: configPubKey})
+      .view();
+  } catch (e) {
+    assert(e.message.indexOf("SwapsPaused") > -1, "Should throw SwapsPaused");
+  }
+  expect(quote).to.be.null;
+
+  // Revert, other tests are expecting an active lp
+  await set_status(program, {status: {active: {}}, authority: wallet.payer, showException: true});
+}
+
+async function test__withdraw_only(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+
+  await set_status(program, {status: {withdrawOnly: {}}, authority: wallet.payer, showException: true});
+
+  let tx = "";
+  try {
+    tx = await add_liquidity_to_exist_lp(program, {solAmount: 0.01, tokenAmount: 0.1});
+  } catch (e) {
+    assert(e.message.indexOf("WithdrawOnly") > -1, "Should throw WithdrawOnly");
+  }
+  expect(tx).to.be.empty;
+
+  // Revert, other tests are expecting an active lp
+  await set_status(program, {status: {active: {}}, authority: wallet.payer, showException: true});
+}
+
+async function test__set_status_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await set_status(program, {status: {withdrawOnly: {}}, authority: walletKeyPair, showException: false});
+  } catch (e) {
+    assert(e.message.indexOf("InvalidAuthority") > -1, "Should throw InvalidAuthority");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function getSetStatusAccounts(program: Program<SimpleLiquidityPool>) {
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const {lpPubKey} = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+
+  return {
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    lpPubKey,
+  }
+}
+
+export async function set_status(program: Program<SimpleLiquidityPool>, option: {
+  // anchor enum: {active: {}} | {swapsPaused: {}} | {withdrawOnly: {}}
+  status: object,
+  authority: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{set_status} : ', Date.now());
+  const {status, authority, showException} = option;
+  const {tokenBasePubKey, tokenQuotePubKey, lpPubKey} = await getSetStatusAccounts(program);
+
+  const tx = await program.methods.setStatus(status as any)
+    .accounts({
+      lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      authority: authority.publicKey,
+    })
+    .signers([authority])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{set_status} tx: ', tx);
+
+  return tx;
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+
+///
+/// Lp share token accounts are kept frozen by the lp, freeze authority of the share mint
+///
+/// So a share can neither be transferred nor burned outside of add/withdraw liquidity:
+/// the share supply always equals the sum of LiquidityProvider.share,
+/// which is what withdraw_liquidity and the fee accounting rely on
+///
+/// lp_seeds: signer seeds of the lp, mint and freeze authority of share_mint
+///
+pub(crate) fn mint_share<'info>(
+  share_mint: &AccountInfo<'info>,
+  user_share_ata: &Account<'info, token::TokenAccount>,
+  lp: &AccountInfo<'info>,
+  lp_seeds: &[&[u8]],
+  token_program: &AccountInfo<'info>,
+  share: u64,
+) -> Result<()> {
+  let signer_seeds: &[&[&[u8]]] = &[lp_seeds];
+
+  // a new share account starts unfrozen
+  if user_share_ata.is_frozen() {
+    thaw_share_account(share_mint, user_share_ata, lp, signer_seeds, token_program)?;
+  }
+
+  token::mint_to(
+    CpiContext::new_with_signer(
+      token_program.clone(),
+      token::MintTo {
+        mint: share_mint.clone(),
+        to: user_share_ata.to_account_info(),
+        authority: lp.clone(),
+      },
+      signer_seeds,
+    ),
+    share,
+  )?;
+
+  freeze_share_account(share_mint, user_share_ata, lp, signer_seeds, token_program)
+}
+
+///
+/// Burn `share` from the user share account, the user signs the burn
+///
+/// An emptied share account is left unfrozen, so the user can close it and get its rent back
+///
+pub(crate) fn burn_share<'info>(
+  share_mint: &AccountInfo<'info>,
+  user_share_ata: &Account<'info, token::TokenAccount>,
+  user: &AccountInfo<'info>,
+  lp: &AccountInfo<'info>,
+  lp_seeds: &[&[u8]],
+  token_program: &AccountInfo<'info>,
+  share: u64,
+) -> Result<()> {
+  let signer_seeds: &[&[&[u8]]] = &[lp_seeds];
+
+  if user_share_ata.is_frozen() {
+    thaw_share_account(share_mint, user_share_ata, lp, signer_seeds, token_program)?;
+  }
+
+  token::burn(
+    CpiContext::new(
+      token_program.clone(),
+      token::Burn {
+        mint: share_mint.clone(),
+        from: user_share_ata.to_account_info(),
+        authority: user.clone(),
+      },
+    ),
+    share,
+  )?;
+
+  // burn succeeded, so amount >= share
+  if user_share_ata.amount > share {
+    freeze_share_account(share_mint, user_share_ata, lp, signer_seeds, token_program)?;
+  }
+
+  Ok(())
+}
+
+fn thaw_share_account<'info>(
+  share_mint: &AccountInfo<'info>,
+  user_share_ata: &Account<'info, token::TokenAccount>,
+  lp: &AccountInfo<'info>,
+  signer_seeds: &[&[&[u8]]],
+  token_program: &AccountInfo<'info>,
+) -> Result<()> {
+  token::thaw_account(
+    CpiContext::new_with_signer(
+      token_program.clone(),
+      token::ThawAccount {
+        account: user_share_ata.to_account_info(),
+        mint: share_mint.clone(),
+        authority: lp.clone(),
+      },
+      signer_seeds,
+    ),
+  )
+}
+
+fn freeze_share_account<'info>(
+  share_mint: &AccountInfo<'info>,
+  user_share_ata: &Account<'info, token::TokenAccount>,
+  lp: &AccountInfo<'info>,
+  signer_seeds: &[&[&[u8]]],
+  token_program: &AccountInfo<'info>,
+) -> Result<()> {
+  token::freeze_account(
+    CpiContext::new_with_signer(
+      token_program.clone(),
+      token::FreezeAccount {
+        account: user_share_ata.to_account_info(),
+        mint: share_mint.clone(),
+        authority: lp.clone(),
+      },
+      signer_seeds,
+    ),
+  )
+}
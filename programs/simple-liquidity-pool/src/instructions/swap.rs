@@ -1,13 +1,17 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program;
 use anchor_spl::{
   token,
+  token_interface,
   token::spl_token,
   associated_token,
 };
+use crate::instructions::wrap_sol::{open_wsol_temp_account, close_wsol_temp_account};
 use crate::state::{
-  FixedRateLP,
-  LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_FEE_SEED_PREFIX,
+  errors::LpBaseError,
+  events::{Swapped, FeeCollected},
+  FixedRateLP, FeeSharingLP, ProgramConfig,
+  LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_FEE_SEED_PREFIX, LP_SHARE_MINT_PREFIX, LP_WSOL_TEMP_SEED_PREFIX,
+  CONFIG_SEED_PREFIX, TREASURY_SEED_PREFIX,
 };
 
 
@@ -16,24 +20,47 @@ pub fn swap(
   from: Pubkey,
   to: Pubkey,
   from_amount: u64,
+  min_amount_out: u64,
+  wrap_sol: bool,
 ) -> Result<()> {
-  let lp = &mut ctx.accounts.lp;
+  if wrap_sol {
+    open_user_wsol(&ctx, from, from_amount)?;
+  }
+
+  // Token-2022 transfer fee is withheld in the lp account, only what was received is swapped
+  let received_amount = transfer_token_into_liquidity(&ctx, from, from_amount, wrap_sol)?;
 
-  let current_base_liquidity: u64 = ctx.accounts.lp_liquidity.lamports();
-  let current_quote_liquidity: u64 = ctx.accounts.lp_liquidity_quote_ata.amount;
+  let lp = &mut ctx.accounts.lp;
 
   let (
-    _,
+    swap_dir,
     from_amount,
     to_amount_without_fee,
     fee_of_to_token
-  ) = lp.preview_swap(from, to, from_amount, current_base_liquidity, current_quote_liquidity)?;
+  ) = lp.preview_swap(from, to, received_amount)?;
+
+  lp.swap(&swap_dir, from_amount, to_amount_without_fee)?;
 
   let to_amount = to_amount_without_fee - fee_of_to_token;
+  require!(to_amount >= min_amount_out, LpBaseError::SlippageExceeded);
+
+  // protocol takes its cut, the rest belongs to current share holders, they can claim it later
+  let (lp_fee, protocol_fee) = ctx.accounts.config.split_swap_fee(fee_of_to_token);
 
-  transfer_token_into_liquidity(&ctx, from, from_amount)?;
-  transfer_token_out_of_liquidity(&ctx, to, fee_of_to_token, true)?;
-  transfer_token_out_of_liquidity(&ctx, to, to_amount, false)?;
+  // a Token-2022 transfer fee is withheld on the way out too: only what arrived is shared and counted for slippage
+  let lp_fee_received = transfer_token_out_of_liquidity(&ctx, to, lp_fee, TransferDest::LpFee, wrap_sol)?;
+  transfer_token_out_of_liquidity(&ctx, to, protocol_fee, TransferDest::Treasury, wrap_sol)?;
+  let to_amount_received = transfer_token_out_of_liquidity(&ctx, to, to_amount, TransferDest::User, wrap_sol)?;
+  require!(to_amount_received >= min_amount_out, LpBaseError::SlippageExceeded);
+
+  let share_supply = ctx.accounts.lp_share_mint.supply;
+  ctx.accounts.lp.accrue_fee(&swap_dir, lp_fee_received, share_supply)?;
+
+  emit_swap_events(&ctx, from, to, from_amount, to_amount_received, fee_of_to_token, lp_fee_received, protocol_fee);
+
+  if wrap_sol {
+    close_user_wsol(&ctx)?;
+  }
 
   Ok(())
 }
@@ -45,62 +72,160 @@ pub struct LpSwap<'info> {
     mut,
     seeds = [
       LP_SEED_PREFIX,
-      token_quote.key().as_ref()
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
     ],
     bump = lp.bump,
+    has_one = token_base @ LpBaseError::InvalidTokenPair,
+    has_one = token_quote @ LpBaseError::InvalidTokenPair,
   )]
   pub lp: Account<'info, FixedRateLP>,
 
-  #[account(mut)]
-  pub token_quote: Account<'info, token::Mint>,
+  #[account(mint::token_program = token_base_program)]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mint::token_program = token_quote_program)]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
 
 
-  // lp liquidity: store SOL liquidity
-  // `lp` account is state, contain data so cannot be used as SOL sender
-  /// CHECK: Just to store SOL
+  // lp liquidity: owner of the liquidity token accounts
+  /// CHECK: data-less PDA, only signs for its token accounts
   #[account(
     mut,
     seeds = [
       LP_LIQUIDITY_PREFIX,
-      token_quote.key().as_ref()
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
     ],
     bump = lp.liquidity_bump,
   )]
   pub lp_liquidity: UncheckedAccount<'info>,
 
-  // lp liquidity: store SPL liquidity
+  // lp liquidity: store token liquidity, wrapped SOL included
+  #[account(
+    mut,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_liquidity_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
   #[account(
     mut,
     associated_token::mint = token_quote,
     associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_quote_program,
   )]
-  pub lp_liquidity_quote_ata: Account<'info, token::TokenAccount>,
+  pub lp_liquidity_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
 
-  // lp fee: store SOL fee collected, for profit sharing later
-  /// CHECK: Just to store SOL
+  // lp fee: owner of the fee token accounts
+  /// CHECK: data-less PDA, only signs for its token accounts
   #[account(
     mut,
-    seeds = [LP_FEE_SEED_PREFIX, token_quote.key().as_ref()],
+    seeds = [
+      LP_FEE_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
     bump = lp.fee_bump,
   )]
   pub lp_fee: UncheckedAccount<'info>,
 
-  // lp fee: store SPL fee collected, for profit sharing later
+  // lp fee: store token fee collected, share holders can claim it
+  #[account(
+    mut,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_fee,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_fee_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
   #[account(
     mut,
     associated_token::mint = token_quote,
     associated_token::authority = lp_fee,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub lp_fee_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // lp share: supply is needed to share the fee
+  #[account(
+    seeds = [
+      LP_SHARE_MINT_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.share_mint_bump,
   )]
-  pub lp_fee_quote_ata: Account<'info, token::TokenAccount>,
+  pub lp_share_mint: Box<Account<'info, token::Mint>>,
 
+  // protocol cut of the swap fee goes to treasury
+  // nothing can be done while the program is paused
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    constraint = !config.paused @ LpBaseError::ProgramPaused,
+  )]
+  pub config: Box<Account<'info, ProgramConfig>>,
+
+  /// CHECK: Just to store SOL
+  #[account(
+    mut,
+    seeds = [TREASURY_SEED_PREFIX],
+    bump = config.treasury_bump,
+  )]
+  pub treasury: UncheckedAccount<'info>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_base,
+    associated_token::authority = treasury,
+    associated_token::token_program = token_base_program,
+  )]
+  pub treasury_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = treasury,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub treasury_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+
+  // user token accounts: the SOL side can be left out when wrap_sol is set,
+  // user_wsol_temp replaces it so no wSOL ATA is created for the user
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_base,
+    associated_token::authority = user,
+    associated_token::token_program = token_base_program,
+  )]
+  pub user_base_ata: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
 
   #[account(
     init_if_needed,
     payer = user,
     associated_token::mint = token_quote,
     associated_token::authority = user,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub user_quote_ata: Option<Box<InterfaceAccount<'info, token_interface::TokenAccount>>>,
+
+  // user pays and receives native SOL through it when wrap_sol is set, see wrap_sol.rs
+  /// CHECK: created and closed within the instruction
+  #[account(
+    mut,
+    seeds = [LP_WSOL_TEMP_SEED_PREFIX, user.key().as_ref()],
+    bump,
   )]
-  pub user_quote_ata: Account<'info, token::TokenAccount>,
+  pub user_wsol_temp: UncheckedAccount<'info>,
 
 
   #[account(mut)]
@@ -109,108 +234,207 @@ pub struct LpSwap<'info> {
   // pub rent: Sysvar<'info, Rent>,
   pub system_program: Program<'info, System>,
   pub token_program: Program<'info, token::Token>,
+  pub token_base_program: Interface<'info, token_interface::TokenInterface>,
+  pub token_quote_program: Interface<'info, token_interface::TokenInterface>,
   pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
 }
 
 
 ///
-/// Transfer from_token from user wallet into pool
+/// Wrap the SOL user pays into his temporary wSOL account,
+/// it also receives the SOL output until close_user_wsol
+///
+pub(crate) fn open_user_wsol<'info>(
+  ctx: &Context<LpSwap<'info>>,
+  from_token: Pubkey,
+  from_amount: u64,
+) -> Result<()> {
+  let native_mint = if ctx.accounts.token_base.key() == spl_token::native_mint::id() {
+    ctx.accounts.token_base.to_account_info()
+  } else {
+    ctx.accounts.token_quote.to_account_info()
+  };
+  let amount = if from_token == spl_token::native_mint::id() { from_amount } else { 0 };
+
+  open_wsol_temp_account(
+    &ctx.accounts.user_wsol_temp.to_account_info(),
+    *ctx.bumps.get("user_wsol_temp").unwrap(),
+    &native_mint,
+    &ctx.accounts.user.to_account_info(),
+    &ctx.accounts.system_program.to_account_info(),
+    &ctx.accounts.token_program.to_account_info(),
+    amount,
+  )
+}
+
+/// Unwrap the SOL output back to user wallet
+pub(crate) fn close_user_wsol<'info>(ctx: &Context<LpSwap<'info>>) -> Result<()> {
+  close_wsol_temp_account(
+    &ctx.accounts.user_wsol_temp.to_account_info(),
+    &ctx.accounts.user.to_account_info(),
+    &ctx.accounts.token_program.to_account_info(),
+  )
+}
+
+/// User token account of this side of the pair: the temporary wSOL account replaces the ATA when wrapping
+fn user_token_account<'info>(ctx: &Context<LpSwap<'info>>, for_token: Pubkey, wrap_sol: bool) -> Result<AccountInfo<'info>> {
+  if wrap_sol && for_token == spl_token::native_mint::id() {
+    return Ok(ctx.accounts.user_wsol_temp.to_account_info());
+  }
+
+  let user_ata = if for_token == ctx.accounts.lp.token_base {
+    &ctx.accounts.user_base_ata
+  } else {
+    &ctx.accounts.user_quote_ata
+  };
+  user_ata
+    .as_ref()
+    .map(|ata| ata.to_account_info())
+    .ok_or_else(|| error!(LpBaseError::MissingUserTokenAccount))
+}
+
+///
+/// Transfer from_token from user wallet into pool, return the amount the pool actually received
+///
+/// Every mint, wrapped SOL included, goes to the lp token account of its side
+/// A Token-2022 mint with transfer fee extension credits less than `amount` to the lp
 ///
-fn transfer_token_into_liquidity<'info>(
+pub(crate) fn transfer_token_into_liquidity<'info>(
   ctx: &Context<LpSwap<'info>>,
   for_token: Pubkey,
   amount: u64,
-  // is_fee_transfer: bool, // liquidity or fee, will expand to enum if have some more destinations
-) -> Result<()> {
+  wrap_sol: bool,
+) -> Result<u64> {
   msg!("[transfer_token_into_liquidity] Transferring {} {} tokens ...", amount, for_token.key().to_string());
 
-  let is_native_and_base_token = for_token == spl_token::native_mint::id();
-
-  if is_native_and_base_token {
-    // case native SOL
-    system_program::transfer(
-      CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        system_program::Transfer {
-          from: ctx.accounts.user.to_account_info(),
-          to: ctx.accounts.lp_liquidity.to_account_info(),
-        },
-      ),
-      amount,
-    )
+  let (mint, to, token_program) = if for_token == ctx.accounts.lp.token_base {
+    (&ctx.accounts.token_base, &ctx.accounts.lp_liquidity_base_ata, ctx.accounts.token_base_program.to_account_info())
   } else {
-    // case SPL token
-    token::transfer(
-      CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.user_quote_ata.to_account_info(),
-          to: ctx.accounts.lp_liquidity_quote_ata.to_account_info(),
-          authority: ctx.accounts.user.to_account_info(),
-        },
-      ),
-      amount,
-    )
-  }
+    (&ctx.accounts.token_quote, &ctx.accounts.lp_liquidity_quote_ata, ctx.accounts.token_quote_program.to_account_info())
+  };
+  let balance_before = token::accessor::amount(&to.to_account_info())?;
+
+  token_interface::transfer_checked(
+    CpiContext::new(
+      token_program,
+      token_interface::TransferChecked {
+        from: user_token_account(ctx, for_token, wrap_sol)?,
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+      },
+    ),
+    amount,
+    mint.decimals,
+  )?;
+
+  let balance_after = token::accessor::amount(&to.to_account_info())?;
+  Ok(balance_after - balance_before)
 }
 
-/// Transfer to_token from Liquidity to user
-fn transfer_token_out_of_liquidity<'info>(
+/// Swapped, and FeeCollected for the fee split, reserves are read after the swap
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn emit_swap_events<'info>(
+  ctx: &Context<LpSwap<'info>>,
+  from_token: Pubkey,
+  to_token: Pubkey,
+  from_amount: u64,
+  to_amount: u64,
+  fee: u64,
+  lp_fee: u64,
+  protocol_fee: u64,
+) {
+  let lp = &ctx.accounts.lp;
+  emit!(Swapped {
+    lp: lp.key(),
+    user: ctx.accounts.user.key(),
+    from_token,
+    to_token,
+    from_amount,
+    to_amount,
+    fee,
+    amount_base: lp.amount_base,
+    amount_quote: lp.amount_quote,
+  });
+  emit!(FeeCollected {
+    lp: lp.key(),
+    token: to_token,
+    lp_fee,
+    protocol_fee,
+  });
+}
+
+/// Where the to_token goes when it leaves the liquidity
+pub(crate) enum TransferDest {
+  User,
+  /// swap fee for liquidity providers
+  LpFee,
+  /// protocol cut of the swap fee
+  Treasury,
+}
+
+///
+/// Transfer to_token from Liquidity to user, lp fee or treasury, return the amount the destination actually received
+///
+/// A Token-2022 mint with transfer fee extension credits less than `amount` to the destination
+///
+pub(crate) fn transfer_token_out_of_liquidity<'info>(
   ctx: &Context<LpSwap<'info>>,
   for_token: Pubkey,
   amount: u64,
-  // Transfer swap fee from Liquidity to Fee
-  is_fee_transfer: bool,
-) -> Result<()> {
+  to: TransferDest,
+  wrap_sol: bool,
+) -> Result<u64> {
+  if amount == 0 {
+    return Ok(0);
+  }
+
   msg!("[transfer_token_out_of_liquidity] Transferring {} {} tokens ...", amount, for_token.key().to_string());
 
-  let token_quote_pubkey = ctx.accounts.token_quote.key().clone();
-
-
-  let is_native_and_base_token = for_token == spl_token::native_mint::id();
-
-  if is_native_and_base_token {
-    **ctx.accounts.lp_liquidity
-      .to_account_info()
-      .try_borrow_mut_lamports()? -= amount;
-    if is_fee_transfer {
-      **ctx.accounts.lp_fee
-        .to_account_info()
-        .try_borrow_mut_lamports()? += amount;
-    } else {
-      **ctx.accounts.user
-        .to_account_info()
-        .try_borrow_mut_lamports()? += amount;
-    }
-    Ok(())
+  let bump = ctx.accounts.lp.liquidity_bump;
+  let (mint_a, mint_b) = ctx.accounts.lp.seed_mints();
+  let pool_index = ctx.accounts.lp.pool_index.to_le_bytes();
+  let signer_seeds: &[&[&[u8]]] = &[&[
+    LP_LIQUIDITY_PREFIX,
+    mint_a.as_ref(),
+    mint_b.as_ref(),
+    pool_index.as_ref(),
+    &[bump],
+  ]];
+
+  let accounts = &ctx.accounts;
+  let is_base = for_token == accounts.lp.token_base;
+  let (mint, from, token_program) = if is_base {
+    (&accounts.token_base, &accounts.lp_liquidity_base_ata, accounts.token_base_program.to_account_info())
   } else {
-    let bump = ctx.accounts.lp.liquidity_bump;
-    msg!("[transfer_token_out_of_liquidity] lp.bump: {}", bump);
-
-    let signer_seeds: &[&[&[u8]]] = &[&[
-      LP_LIQUIDITY_PREFIX,
-      token_quote_pubkey.as_ref(),
-      &[bump],
-    ]];
-
-    // case SPL token
-    token::transfer(
-      CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        token::Transfer {
-          from: ctx.accounts.lp_liquidity_quote_ata.to_account_info(),
-          to: if is_fee_transfer {
-            ctx.accounts.lp_fee_quote_ata.to_account_info()
-          } else {
-            ctx.accounts.user_quote_ata.to_account_info()
-          },
-          authority: ctx.accounts.lp_liquidity.to_account_info(),
-        },
-        signer_seeds,
-      ),
-      amount,
-    )
-  }
+    (&accounts.token_quote, &accounts.lp_liquidity_quote_ata, accounts.token_quote_program.to_account_info())
+  };
+  let destination = match (to, is_base) {
+    (TransferDest::User, _) => user_token_account(ctx, for_token, wrap_sol)?,
+    (TransferDest::LpFee, true) => accounts.lp_fee_base_ata.to_account_info(),
+    (TransferDest::LpFee, false) => accounts.lp_fee_quote_ata.to_account_info(),
+    (TransferDest::Treasury, true) => accounts.treasury_base_ata.to_account_info(),
+    (TransferDest::Treasury, false) => accounts.treasury_quote_ata.to_account_info(),
+  };
+  let balance_before = token::accessor::amount(&destination)?;
+
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      token_program,
+      token_interface::TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: destination.clone(),
+        authority: accounts.lp_liquidity.to_account_info(),
+      },
+      signer_seeds,
+    ),
+    amount,
+    mint.decimals,
+  )?;
+
+  let balance_after = token::accessor::amount(&destination)?;
+  Ok(balance_after - balance_before)
 }
 
 
@@ -222,5 +446,5 @@ fn transfer_token_out_of_liquidity<'info>(
 // ) -> Result<()> {
 //   msg!("[transfer_swap_fee] Transferring {} {} tokens ...", amount, of_token.key().to_string());
 //
-//   transfer_token_out_of_liquidity(ctx, of_token, amount, true)
+//   transfer_token_out_of_liquidity(ctx, of_token, amount, TransferDest::LpFee)
 // }
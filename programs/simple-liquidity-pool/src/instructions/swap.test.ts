@@ -7,15 +7,18 @@ import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructi
 import {Keypair, PublicKey} from "@solana/web3.js";
 import {NATIVE_MINT} from "@solana/spl-token";
 import {add_liquidity_to_exist_lp} from "./add_lp.test";
-import {getThisProgramConstants} from "./utils.test";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getConfigAccounts, getLpAccounts, getTxEvents, getThisProgramConstants, getWsolTempAccount} from "./utils.test";
 
 
 export default function test__swap(program: Program<SimpleLiquidityPool>) {
   it("Can swap SOL to token with fee deducted on token", async () => test__swap_sol_to_token(program));
   it("Can swap token to SOL with fee deducted on SOL", async () => test__swap_token_to_sol(program));
+  it("Can swap wrapped SOL without a wSOL ATA", async () => test__swap_sol_without_wsol_ata(program));
   it("Cannot swap more than liquidity", async () => test__swap_over_liquidity(program));
+  it("Cannot swap when output is less than min_amount_out", async () => test__swap_slippage_exceeded(program));
+  it("quote_swap returns the exact swap output without any signer", async () => test__quote_swap(program));
   // it("Can swap by everyone", async () => TODO(program));
-  // it("Only liquidity provider can withdraw profit", async () => TODO(program));
 }
 
 async function test__swap_sol_to_token(program: Program<SimpleLiquidityPool>) {
@@ -52,6 +55,27 @@ async function test__swap_token_to_sol(program: Program<SimpleLiquidityPool>) {
   });
 }
 
+async function test__swap_sol_without_wsol_ata(program: Program<SimpleLiquidityPool>) {
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const myTokenPubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  // a fresh user who never held wSOL
+  const user = Keypair.generate();
+  await airDropSolIfBalanceLowerThan(1, user.publicKey);
+
+  await test__swap_token(program, {
+    from: NATIVE_MINT,
+    to: myTokenPubKey,
+    fromAmount: 0.01,
+    payer: user,
+    showException: true,
+  });
+
+  // SOL was paid through the temporary wSOL account, no wSOL ATA was created for the user
+  const userWsolAta = await anchor.utils.token.associatedAddress({mint: NATIVE_MINT, owner: user.publicKey});
+  expect(await getCurrentProvider().connection.getAccountInfo(userWsolAta)).to.be.null;
+}
+
 async function test__swap_over_liquidity(program: Program<SimpleLiquidityPool>) {
   const wallet = getProviderWallet();
   const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
@@ -76,10 +100,68 @@ async function test__swap_over_liquidity(program: Program<SimpleLiquidityPool>)
   expect(tx).to.be.empty;
 }
 
+async function test__swap_slippage_exceeded(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const myTokenPubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  let tx = "";
+  try {
+    tx = await test__swap_token(program, {
+      from: NATIVE_MINT,
+      to: myTokenPubKey,
+      fromAmount: 0.01,
+      minAmountOut: Number.MAX_SAFE_INTEGER, // no way to receive this much
+      payer: wallet.payer,
+      showException: false,
+    });
+  } catch (e) {
+    assert(e.message.indexOf("SlippageExceeded") > -1, "Should throw SlippageExceeded");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__quote_swap(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const myTokenPubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const {lpPubKey} = await getLpAccounts(program, NATIVE_MINT, myTokenPubKey);
+  const {configPubKey} = await getConfigAccounts(program, myTokenPubKey);
+
+  const fromAmount = 0.02;
+  const lpBefore = await program.account.fixedRateLp.fetch(lpPubKey);
+  const quote = await program.methods.quoteSwap(NATIVE_MINT, myTokenPubKey, new anchor.BN(fromAmount * 1e9))
+    .accounts({lp: lpPubKey, config: configPubKey})
+    .view();
+  VERBOSE && console.log('{test__quote_swap} quote: ', quote);
+  expect(quote.fromAmount.toNumber()).to.be.eq(fromAmount * 1e9);
+  assert(quote.fee.toNumber() <= quote.toAmountWithoutFee.toNumber(), "Fee cannot exceed the output");
+
+  // quoting does not change the lp
+  const lpAfterQuote = await program.account.fixedRateLp.fetch(lpPubKey);
+  expect(lpAfterQuote.amountBase.toNumber()).to.be.eq(lpBefore.amountBase.toNumber());
+
+  // swapping the same amount gives exactly the quoted output
+  const toAmount = quote.toAmountWithoutFee.toNumber() - quote.fee.toNumber();
+  const userQuoteAta = await anchor.utils.token.associatedAddress({mint: myTokenPubKey, owner: wallet.publicKey});
+  const userQuoteBefore = await getTokenBalanceOrZero(userQuoteAta);
+  await test__swap_token(program, {
+    from: NATIVE_MINT,
+    to: myTokenPubKey,
+    fromAmount,
+    minAmountOut: toAmount,
+    payer: wallet.payer,
+    showException: true,
+  });
+  expect(await getTokenBalanceOrZero(userQuoteAta)).to.be.eq(userQuoteBefore + toAmount);
+}
+
 async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
   from: PublicKey,
   to: PublicKey,
   fromAmount: number,
+  // in smallest unit of to token, default 0: accept any output
+  minAmountOut?: number,
   payer: Keypair,
   showException?: boolean,
 }) {
@@ -89,6 +171,7 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
     from: fromPubKey,
     to: toPubKey,
     fromAmount,
+    minAmountOut = 0,
     payer,
     showException,
   } = option;
@@ -102,11 +185,8 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
   const provider = getCurrentProvider();
 
   const {
-    LP_SEED_PREFIX,
-    LP_LIQUIDITY_PREFIX,
-    LP_FEE_SEED_PREFIX,
     LP_RATE_DECIMAL,
-    LP_SWAP_FEE_PERMIL,
+    LP_FEE_BPS_DENOMINATOR,
     TOKEN_DECIMAL,
   } = getThisProgramConstants(program);
 
@@ -125,9 +205,12 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
     - LP dec fromAmount / rate of SOL
     - LP inc fromAmount of token
     - LP FEE inc % of `LP dec` in SOL: +x SOL, +0 Token
+  LP FEE here is the total swap fee: lp fee + protocol fee in treasury
    */
   // NOTE: This fee logic must sync with Smart contract
-  const swap_fee = (toAmount) => toAmount * (LP_SWAP_FEE_PERMIL / 1000);
+  const {lpPubKey: lpPubKeyForFee} = await getLpAccounts(program, basePubKey, quotePubKey);
+  const {feeBps} = await program.account.fixedRateLp.fetch(lpPubKeyForFee);
+  const swap_fee = (toAmount) => toAmount * (feeBps / LP_FEE_BPS_DENOMINATOR);
   const changeMatrix = {
     // [Sol change, token change, fee Sol change, fee token change]
     baseToQuote: [
@@ -158,37 +241,16 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
   });
 
 
-  const [lpPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_SEED_PREFIX,
-      quotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpFeePubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_FEE_SEED_PREFIX,
-      quotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-  const [lpLiquidityPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      LP_LIQUIDITY_PREFIX,
-      quotePubKey.toBuffer(),
-    ],
-    program.programId
-  ))
-
-  const lpLiquidityQuoteAta = await anchor.utils.token.associatedAddress({
-    mint: quotePubKey,
-    owner: lpLiquidityPubKey,
-    // owner: lpPubKey,
-  });
-  const feeAta = await anchor.utils.token.associatedAddress({
-    mint: quotePubKey,
-    owner: lpFeePubKey
-  });
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpFeePubKey,
+    lpFeeBaseAta,
+    lpFeeQuoteAta: feeAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, basePubKey, quotePubKey);
   const userQuoteAta = await anchor.utils.token.associatedAddress({
     mint: quotePubKey,
     owner: payer.publicKey
@@ -202,11 +264,19 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
     before: {quote: 0, base: 0},
     after: {quote: 0, base: 0},
   }
+  const {configPubKey, treasuryPubKey, treasuryQuoteAta} = await getConfigAccounts(program, quotePubKey);
+  const {treasuryQuoteAta: treasuryBaseAta} = await getConfigAccounts(program, basePubKey);
+  // lp fee + protocol fee
+  const getFeeBalances = async () => ({
+    base: new anchor.BN((await provider.connection.getTokenAccountBalance(lpFeeBaseAta)).value.amount).toNumber()
+      + await getTokenBalanceOrZero(treasuryBaseAta),
+    quote: new anchor.BN((await provider.connection.getTokenAccountBalance(feeAta)).value.amount).toNumber()
+      + await getTokenBalanceOrZero(treasuryQuoteAta),
+  });
 
-  lpBalances.before.base = await provider.connection.getBalance(lpLiquidityPubKey);
+  lpBalances.before.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
   lpBalances.before.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
-  lpFeeBalances.before.base = await provider.connection.getBalance(lpFeePubKey);
-  lpFeeBalances.before.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(feeAta)).value.amount).toNumber();
+  lpFeeBalances.before = await getFeeBalances();
 
   VERBOSE && console.log('{test__swap_token} : ', {
     lpPubKey: lpPubKey.toString(),
@@ -225,18 +295,32 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
     fromPubKey,
     toPubKey,
     new anchor.BN(fromAmount * Math.pow(10, fromDecimals)),
+    new anchor.BN(minAmountOut),
+    true, // pay and receive native SOL, lp holds wSOL
   )
     .accounts({
       lp: lpPubKey,
+      tokenBase: basePubKey,
       tokenQuote: quotePubKey,
       lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
       lpLiquidityQuoteAta: lpLiquidityQuoteAta,
       lpFee: lpFeePubKey,
+      lpFeeBaseAta: lpFeeBaseAta,
       lpFeeQuoteAta: feeAta,
+      lpShareMint: lpShareMintPubKey,
+      config: configPubKey,
+      treasury: treasuryPubKey,
+      treasuryBaseAta: treasuryBaseAta,
+      treasuryQuoteAta: treasuryQuoteAta,
+      userBaseAta: program.programId, // base is SOL and wrapped: no user wSOL ATA
       userQuoteAta: userQuoteAta,
+      userWsolTemp: getWsolTempAccount(program, payer.publicKey),
       user: payer.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
       associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
     })
     // .signers([])
@@ -248,10 +332,9 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
     });
   VERBOSE && console.log('{test__swap_token} tx: ', tx);
 
-  lpBalances.after.base = await provider.connection.getBalance(lpLiquidityPubKey);
+  lpBalances.after.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
   lpBalances.after.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
-  lpFeeBalances.after.base = await provider.connection.getBalance(lpFeePubKey);
-  lpFeeBalances.after.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(feeAta)).value.amount).toNumber();
+  lpFeeBalances.after = await getFeeBalances();
 
   // lpBalances must increase
   VERBOSE && console.log('{test__swap_token} lpBalances & fee after: ', lpBalances, lpFeeBalances);
@@ -260,5 +343,26 @@ async function test__swap_token(program: Program<SimpleLiquidityPool>, option: {
   expect(lpFeeBalances.after.base).to.be.approximately(lpFeeBalances.before.base + baseFeeIncAmount, 1e-6, `LP fee base balance must increase ${baseFeeIncAmount}`);
   expect(lpFeeBalances.after.quote).to.be.approximately(lpFeeBalances.before.quote + quoteFeeIncAmount, 1e-6, `LP fee quote balance must increase ${quoteFeeIncAmount}`);
 
+  // temporary wSOL account must be closed back to user
+  expect(await provider.connection.getAccountInfo(getWsolTempAccount(program, payer.publicKey))).to.be.null;
+
+  // indexers read the swap from the Swapped event
+  const events = await getTxEvents(program, tx);
+  const swapped = events.find(e => e.name === "Swapped");
+  assert(!!swapped, "Swap must emit Swapped event");
+  assert(swapped.data.user.equals(payer.publicKey), "Swapped event must carry the user");
+  assert(swapped.data.fromToken.equals(fromPubKey), "Swapped event must carry from token");
+  assert(!!events.find(e => e.name === "FeeCollected"), "Swap must emit FeeCollected event");
+
   return tx;
 }
+
+async function getTokenBalanceOrZero(ata: PublicKey) {
+  const provider = getCurrentProvider();
+  try {
+    return new anchor.BN((await provider.connection.getTokenAccountBalance(ata)).value.amount).toNumber();
+  } catch (e) {
+    // ata is not created yet
+    return 0;
+  }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::instructions::swap::{
+  LpSwap,
+  TransferDest,
+  open_user_wsol,
+  close_user_wsol,
+  transfer_token_into_liquidity,
+  transfer_token_out_of_liquidity,
+  emit_swap_events,
+};
+use crate::state::{
+  errors::LpBaseError,
+  FeeSharingLP,
+};
+
+///
+/// Same as swap but user specify the exact amount he wants to receive (after fee),
+/// and pay whatever it costs, capped by max_amount_in
+///
+pub fn swap_exact_out(
+  ctx: Context<LpSwap>,
+  from: Pubkey,
+  to: Pubkey,
+  to_amount: u64,
+  max_amount_in: u64,
+  wrap_sol: bool,
+) -> Result<()> {
+  let lp = &mut ctx.accounts.lp;
+
+  let (
+    swap_dir,
+    from_amount,
+    to_amount_without_fee,
+    fee_of_to_token
+  ) = lp.preview_swap_exact_out(from, to, to_amount)?;
+
+  require!(from_amount <= max_amount_in, LpBaseError::MaxAmountInExceeded);
+
+  lp.swap(&swap_dir, from_amount, to_amount_without_fee)?;
+
+  // protocol takes its cut, the rest belongs to current share holders, they can claim it later
+  let (lp_fee, protocol_fee) = ctx.accounts.config.split_swap_fee(fee_of_to_token);
+
+  if wrap_sol {
+    open_user_wsol(&ctx, from, from_amount)?;
+  }
+
+  // from_amount was priced before the transfer, a transfer fee would leave the lp short
+  let received_amount = transfer_token_into_liquidity(&ctx, from, from_amount, wrap_sol)?;
+  require_eq!(received_amount, from_amount, LpBaseError::TransferFeeNotSupported);
+
+  let lp_fee_received = transfer_token_out_of_liquidity(&ctx, to, lp_fee, TransferDest::LpFee, wrap_sol)?;
+  transfer_token_out_of_liquidity(&ctx, to, protocol_fee, TransferDest::Treasury, wrap_sol)?;
+
+  // to_amount is exact, a transfer fee on to_token would pay the user less than he asked for
+  let to_amount_received = transfer_token_out_of_liquidity(&ctx, to, to_amount, TransferDest::User, wrap_sol)?;
+  require_eq!(to_amount_received, to_amount, LpBaseError::TransferFeeNotSupported);
+
+  let share_supply = ctx.accounts.lp_share_mint.supply;
+  ctx.accounts.lp.accrue_fee(&swap_dir, lp_fee_received, share_supply)?;
+
+  emit_swap_events(&ctx, from, to, from_amount, to_amount, fee_of_to_token, lp_fee_received, protocol_fee);
+
+  if wrap_sol {
+    close_user_wsol(&ctx)?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,134 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getCurrentProvider, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair, PublicKey} from "@solana/web3.js";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {getConfigAccounts, getLpAccounts, getThisProgramConstants, getWsolTempAccount} from "./utils.test";
+
+
+export default function test__swap_exact_out(program: Program<SimpleLiquidityPool>) {
+  it("Can swap SOL to receive an exact amount of token", async () => test__swap_sol_to_exact_token(program));
+  it("Cannot swap when input is greater than max_amount_in", async () => test__swap_exact_out_max_in_exceeded(program));
+}
+
+async function test__swap_sol_to_exact_token(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {TOKEN_DECIMAL} = getThisProgramConstants(program);
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const myTokenPubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const provider = getCurrentProvider();
+
+  const toAmount = 0.5 * Math.pow(10, TOKEN_DECIMAL);
+  const {userQuoteAta} = await getSwapAccounts(program, myTokenPubKey, wallet.payer.publicKey);
+  const before = new anchor.BN((await provider.connection.getTokenAccountBalance(userQuoteAta)).value.amount);
+
+  const tx = await swap_exact_out(program, {
+    from: NATIVE_MINT,
+    to: myTokenPubKey,
+    toAmount,
+    maxAmountIn: 1e9, // 1 SOL is more than enough
+    payer: wallet.payer,
+    showException: true,
+  });
+  assert(!!tx, "Tx should not be empty");
+
+  // user must receive exactly toAmount, fee is paid on top of it
+  const after = new anchor.BN((await provider.connection.getTokenAccountBalance(userQuoteAta)).value.amount);
+  expect(after.sub(before).toNumber()).to.be.eq(toAmount);
+}
+
+async function test__swap_exact_out_max_in_exceeded(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {TOKEN_DECIMAL} = getThisProgramConstants(program);
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const myTokenPubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  let tx = "";
+  try {
+    tx = await swap_exact_out(program, {
+      from: NATIVE_MINT,
+      to: myTokenPubKey,
+      toAmount: 0.5 * Math.pow(10, TOKEN_DECIMAL),
+      maxAmountIn: 1, // 1 lamport is not enough
+      payer: wallet.payer,
+      showException: false,
+    });
+  } catch (e) {
+    assert(e.message.indexOf("MaxAmountInExceeded") > -1, "Should throw MaxAmountInExceeded");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function getSwapAccounts(program: Program<SimpleLiquidityPool>, quotePubKey: PublicKey, user: PublicKey) {
+  // test lp: base is SOL
+  const basePubKey = NATIVE_MINT;
+
+  return {
+    basePubKey,
+    ...(await getLpAccounts(program, basePubKey, quotePubKey)),
+    userQuoteAta: await anchor.utils.token.associatedAddress({mint: quotePubKey, owner: user}),
+  };
+}
+
+export async function swap_exact_out(program: Program<SimpleLiquidityPool>, option: {
+  from: PublicKey,
+  to: PublicKey,
+  // in smallest unit of to token
+  toAmount: number,
+  // in smallest unit of from token
+  maxAmountIn: number,
+  payer: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{swap_exact_out} : ', Date.now());
+  const {from, to, toAmount, maxAmountIn, payer, showException} = option;
+  const quotePubKey = from.equals(NATIVE_MINT) ? to : from;
+  const accounts = await getSwapAccounts(program, quotePubKey, payer.publicKey);
+  const {configPubKey, treasuryPubKey, treasuryQuoteAta} = await getConfigAccounts(program, quotePubKey);
+  const {treasuryQuoteAta: treasuryBaseAta} = await getConfigAccounts(program, accounts.basePubKey);
+
+  const tx = await program.methods.swapExactOut(
+    from,
+    to,
+    new anchor.BN(toAmount),
+    new anchor.BN(maxAmountIn),
+    true, // pay and receive native SOL, lp holds wSOL
+  )
+    .accounts({
+      lp: accounts.lpPubKey,
+      tokenBase: accounts.basePubKey,
+      tokenQuote: quotePubKey,
+      lpLiquidity: accounts.lpLiquidityPubKey,
+      lpLiquidityBaseAta: accounts.lpLiquidityBaseAta,
+      lpLiquidityQuoteAta: accounts.lpLiquidityQuoteAta,
+      lpFee: accounts.lpFeePubKey,
+      lpFeeBaseAta: accounts.lpFeeBaseAta,
+      lpFeeQuoteAta: accounts.lpFeeQuoteAta,
+      lpShareMint: accounts.lpShareMintPubKey,
+      config: configPubKey,
+      treasury: treasuryPubKey,
+      treasuryBaseAta: treasuryBaseAta,
+      treasuryQuoteAta: treasuryQuoteAta,
+      userBaseAta: program.programId, // base is SOL and wrapped: no user wSOL ATA
+      userQuoteAta: accounts.userQuoteAta,
+      userWsolTemp: getWsolTempAccount(program, payer.publicKey),
+      user: payer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{swap_exact_out} tx: ', tx);
+
+  return tx;
+}
@@ -0,0 +1,214 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getCurrentProvider, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeMintInstruction,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+} from "@solana/spl-token";
+import {getConfigAccounts, getLpAccounts, getThisProgramConstants, getWsolTempAccount} from "./utils.test";
+import {init_new_lp} from "./init.test";
+
+
+export default function test__token_2022(program: Program<SimpleLiquidityPool>) {
+  it("Lp only credits what it received from a Token-2022 transfer fee mint", async () => test__add_liquidity_with_transfer_fee_mint(program));
+  it("Swap into a transfer fee mint only shares the lp fee that arrived", async () => test__swap_to_transfer_fee_mint(program));
+}
+
+const TRANSFER_FEE_BPS = 100; // 1%
+const TOKEN_2022_DECIMAL = 9;
+// created by the add liquidity test, the swap test reuses its lp
+let transferFeeMint: anchor.web3.PublicKey;
+
+async function test__add_liquidity_with_transfer_fee_mint(program: Program<SimpleLiquidityPool>) {
+  console.log('{test__add_liquidity_with_transfer_fee_mint} : ', Date.now());
+  const wallet = getProviderWallet();
+  const provider = getCurrentProvider();
+  const {LP_PROVIDER_SEED_PREFIX} = getThisProgramConstants(program);
+
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const tokenQuotePubKey = await createTransferFeeMint(wallet.payer);
+  transferFeeMint = tokenQuotePubKey;
+  const userQuoteAta = await getOrCreateAssociatedTokenAccount(
+    provider.connection, wallet.payer, tokenQuotePubKey, wallet.publicKey,
+    false, undefined, undefined, TOKEN_2022_PROGRAM_ID,
+  );
+  await mintTo(
+    provider.connection, wallet.payer, tokenQuotePubKey, userQuoteAta.address, wallet.payer,
+    1000 * Math.pow(10, TOKEN_2022_DECIMAL), [], undefined, TOKEN_2022_PROGRAM_ID,
+  );
+
+  const {tx: initTx} = await init_new_lp(program, tokenBasePubKey, tokenQuotePubKey, wallet.payer, true, TOKEN_2022_PROGRAM_ID);
+  assert(!!initTx, "Init tx should not be empty");
+
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey, TOKEN_2022_PROGRAM_ID);
+  const [liquidityProviderPubKey] = anchor.web3.PublicKey.findProgramAddressSync(
+    [LP_PROVIDER_SEED_PREFIX, lpPubKey.toBuffer(), wallet.publicKey.toBuffer()],
+    program.programId
+  );
+  const {configPubKey} = await getConfigAccounts(program, tokenQuotePubKey);
+
+  const quoteAmount = 100 * Math.pow(10, TOKEN_2022_DECIMAL);
+  const transferFee = quoteAmount * TRANSFER_FEE_BPS / 10000;
+
+  const tx = await program.methods.addLiquidity(
+    new anchor.BN(0),
+    new anchor.BN(quoteAmount),
+    false,
+  )
+    .accounts({
+      lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
+      lpLiquidityQuoteAta: lpLiquidityQuoteAta,
+      userBaseAta: await anchor.utils.token.associatedAddress({mint: tokenBasePubKey, owner: wallet.publicKey}),
+      userQuoteAta: userQuoteAta.address,
+      lpShareMint: lpShareMintPubKey,
+      userShareAta: getAssociatedTokenAddressSync(lpShareMintPubKey, wallet.publicKey),
+      userWsolTemp: getWsolTempAccount(program, wallet.publicKey),
+      liquidityProvider: liquidityProviderPubKey,
+      config: configPubKey,
+      user: wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([wallet.payer])
+    .rpc()
+    .catch(e => {
+      console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{test__add_liquidity_with_transfer_fee_mint} tx: ', tx);
+
+  // transfer fee is withheld in the lp account, it is not part of the liquidity
+  const lpQuoteBalance = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
+  const lpAccount = await program.account.fixedRateLp.fetch(lpPubKey);
+  expect(lpQuoteBalance).to.be.eq(quoteAmount - transferFee);
+  expect(lpAccount.amountQuote.toNumber()).to.be.eq(quoteAmount - transferFee);
+}
+
+async function test__swap_to_transfer_fee_mint(program: Program<SimpleLiquidityPool>) {
+  console.log('{test__swap_to_transfer_fee_mint} : ', Date.now());
+  const wallet = getProviderWallet();
+  const provider = getCurrentProvider();
+  const LP_FEE_PER_SHARE_PRECISION = new anchor.BN("1000000000000"); // see state/lp.rs
+
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const tokenQuotePubKey = transferFeeMint;
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpFeePubKey,
+    lpFeeBaseAta,
+    lpFeeQuoteAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey, TOKEN_2022_PROGRAM_ID);
+  const {configPubKey, treasuryPubKey, treasuryQuoteAta} = await getConfigAccounts(program, tokenQuotePubKey);
+  const {treasuryQuoteAta: treasuryBaseAta} = await getConfigAccounts(program, tokenBasePubKey);
+  const userQuoteAta = getAssociatedTokenAddressSync(tokenQuotePubKey, wallet.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+  const getBalance = async (tokenAccount: anchor.web3.PublicKey) =>
+    new anchor.BN((await provider.connection.getTokenAccountBalance(tokenAccount)).value.amount);
+  const lpBefore = await program.account.fixedRateLp.fetch(lpPubKey);
+  const lpFeeBefore = await getBalance(lpFeeQuoteAta);
+  const userBefore = await getBalance(userQuoteAta);
+
+  const tx = await program.methods.swap(
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    new anchor.BN(0.1 * 1e9),
+    new anchor.BN(1),
+    true, // pay native SOL
+  )
+    .accounts({
+      lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
+      lpLiquidityQuoteAta: lpLiquidityQuoteAta,
+      lpFee: lpFeePubKey,
+      lpFeeBaseAta: lpFeeBaseAta,
+      lpFeeQuoteAta: lpFeeQuoteAta,
+      lpShareMint: lpShareMintPubKey,
+      config: configPubKey,
+      treasury: treasuryPubKey,
+      treasuryBaseAta: treasuryBaseAta,
+      treasuryQuoteAta: treasuryQuoteAta,
+      userBaseAta: program.programId, // base is SOL and wrapped: no user wSOL ATA
+      userQuoteAta: userQuoteAta,
+      userWsolTemp: getWsolTempAccount(program, wallet.publicKey),
+      user: wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([wallet.payer])
+    .rpc()
+    .catch(e => {
+      console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{test__swap_to_transfer_fee_mint} tx: ', tx);
+
+  // the lp fee account and the user both paid the transfer fee, share holders are only owed what arrived
+  const lpAfter = await program.account.fixedRateLp.fetch(lpPubKey);
+  const lpFeeReceived = (await getBalance(lpFeeQuoteAta)).sub(lpFeeBefore);
+  const userReceived = (await getBalance(userQuoteAta)).sub(userBefore);
+  const shareSupply = new anchor.BN((await provider.connection.getTokenSupply(lpShareMintPubKey)).value.amount);
+  const feePerShareInc = lpAfter.feeQuotePerShare.sub(lpBefore.feeQuotePerShare);
+  assert(lpFeeReceived.gtn(0) && userReceived.gtn(0), "Lp fee and user must receive quote");
+  expect(feePerShareInc.toString()).to.be.eq(lpFeeReceived.mul(LP_FEE_PER_SHARE_PRECISION).div(shareSupply).toString());
+}
+
+async function createTransferFeeMint(payer: anchor.web3.Keypair) {
+  const provider = getCurrentProvider();
+  const mintKeypair = anchor.web3.Keypair.generate();
+  const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+  const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+  const transaction = new anchor.web3.Transaction().add(
+    anchor.web3.SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: mintKeypair.publicKey,
+      space: mintLen,
+      lamports,
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      mintKeypair.publicKey,
+      payer.publicKey,
+      payer.publicKey,
+      TRANSFER_FEE_BPS,
+      BigInt(Number.MAX_SAFE_INTEGER),
+      TOKEN_2022_PROGRAM_ID,
+    ),
+    createInitializeMintInstruction(mintKeypair.publicKey, TOKEN_2022_DECIMAL, payer.publicKey, null, TOKEN_2022_PROGRAM_ID),
+  );
+  await anchor.web3.sendAndConfirmTransaction(provider.connection, transaction, [payer, mintKeypair]);
+
+  return mintKeypair.publicKey;
+}
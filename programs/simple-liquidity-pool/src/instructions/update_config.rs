@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+  errors::LpBaseError,
+  ProgramConfig,
+  CONFIG_SEED_PREFIX,
+};
+
+pub fn update_config(ctx: Context<ConfigUpdate>, protocol_fee_share_bps: u16, default_fee_bps: u16) -> Result<()> {
+  let config = &mut ctx.accounts.config;
+  msg!("[update_config] protocol_fee_share_bps: {} => {}", config.protocol_fee_share_bps, protocol_fee_share_bps);
+  msg!("[update_config] default_fee_bps: {} => {}", config.default_fee_bps, default_fee_bps);
+
+  config.update_fees(protocol_fee_share_bps, default_fee_bps)
+}
+
+
+#[derive(Accounts)]
+pub struct ConfigUpdate<'info> {
+  #[account(
+    mut,
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    has_one = admin @ LpBaseError::InvalidAdmin,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  pub admin: Signer<'info>,
+}
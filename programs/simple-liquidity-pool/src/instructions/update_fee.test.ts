@@ -0,0 +1,103 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair} from "@solana/web3.js";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getLpAccounts, getThisProgramConstants} from "./utils.test";
+
+
+export default function test__update_fee(program: Program<SimpleLiquidityPool>) {
+  it("Lp authority can update swap fee", async () => test__update_fee_by_authority(program));
+  it("Cannot update fee over LP_MAX_FEE_BPS", async () => test__update_invalid_fee(program));
+  it("Other wallet cannot update fee", async () => test__update_fee_by_other_wallet(program));
+}
+
+async function test__update_fee_by_authority(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {lpPubKey} = await getUpdateFeeAccounts(program);
+
+  const lpAccount = await program.account.fixedRateLp.fetch(lpPubKey);
+  const newFeeBps = 30; // 0.3%
+
+  const tx = await update_fee(program, {feeBps: newFeeBps, authority: wallet.payer, showException: true});
+  assert(!!tx, "Tx should not be empty");
+  expect((await program.account.fixedRateLp.fetch(lpPubKey)).feeBps).to.be.eq(newFeeBps);
+
+  // Revert the fee, other tests are expecting the original one
+  await update_fee(program, {feeBps: lpAccount.feeBps, authority: wallet.payer, showException: true});
+  expect((await program.account.fixedRateLp.fetch(lpPubKey)).feeBps).to.be.eq(lpAccount.feeBps);
+}
+
+async function test__update_invalid_fee(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {LP_MAX_FEE_BPS} = getThisProgramConstants(program);
+
+  let tx = "";
+  try {
+    tx = await update_fee(program, {feeBps: LP_MAX_FEE_BPS + 1, authority: wallet.payer, showException: false});
+  } catch (e) {
+    assert(e.message.indexOf("InvalidFee") > -1, "Should throw InvalidFee");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__update_fee_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await update_fee(program, {
+      feeBps: 0,
+      authority: walletKeyPair,
+      showException: false,
+    });
+  } catch (e) {
+    assert(e.message.indexOf("InvalidAuthority") > -1, "Should throw InvalidAuthority");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function getUpdateFeeAccounts(program: Program<SimpleLiquidityPool>) {
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const {lpPubKey} = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+
+  return {
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    lpPubKey,
+  }
+}
+
+export async function update_fee(program: Program<SimpleLiquidityPool>, option: {
+  feeBps: number,
+  authority: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{update_fee} : ', Date.now());
+  const {feeBps, authority, showException} = option;
+  const {tokenBasePubKey, tokenQuotePubKey, lpPubKey} = await getUpdateFeeAccounts(program);
+
+  const tx = await program.methods.updateFee(feeBps)
+    .accounts({
+      lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      authority: authority.publicKey,
+    })
+    .signers([authority])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{update_fee} tx: ', tx);
+
+  return tx;
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface;
+use crate::instructions::set_status::emit_pool_updated;
+use crate::state::{
+  errors::LpBaseError,
+  FixedRateLP,
+  LP_SEED_PREFIX,
+};
+
+pub fn update_rate(ctx: Context<LpUpdateRate>, fixed_rate: u32) -> Result<()> {
+  let lp = &mut ctx.accounts.lp;
+  msg!("[update_rate] rate: {} => {}", lp.rate, fixed_rate);
+
+  lp.update_rate(fixed_rate)?;
+
+  emit_pool_updated(lp);
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct LpUpdateRate<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      LP_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.bump,
+    has_one = token_base @ LpBaseError::InvalidTokenPair,
+    has_one = token_quote @ LpBaseError::InvalidTokenPair,
+    has_one = authority @ LpBaseError::InvalidAuthority,
+  )]
+  pub lp: Account<'info, FixedRateLP>,
+
+  #[account()]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account()]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+  pub authority: Signer<'info>,
+}
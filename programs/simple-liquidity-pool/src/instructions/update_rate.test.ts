@@ -0,0 +1,104 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair} from "@solana/web3.js";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getLpAccounts, getThisProgramConstants} from "./utils.test";
+
+
+export default function test__update_rate(program: Program<SimpleLiquidityPool>) {
+  it("Lp authority can update rate", async () => test__update_rate_by_authority(program));
+  it("Cannot update to invalid rate", async () => test__update_invalid_rate(program));
+  it("Other wallet cannot update rate", async () => test__update_rate_by_other_wallet(program));
+}
+
+async function test__update_rate_by_authority(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {LP_RATE_DECIMAL} = getThisProgramConstants(program);
+  const {lpPubKey} = await getUpdateRateAccounts(program);
+
+  const lpAccount = await program.account.fixedRateLp.fetch(lpPubKey);
+  const newRate = 12 * Math.pow(10, LP_RATE_DECIMAL);
+
+  const tx = await update_rate(program, {rate: newRate, authority: wallet.payer, showException: true});
+  assert(!!tx, "Tx should not be empty");
+  expect((await program.account.fixedRateLp.fetch(lpPubKey)).rate).to.be.eq(newRate);
+
+  // Revert the rate, other tests are expecting the original one
+  await update_rate(program, {rate: lpAccount.rate, authority: wallet.payer, showException: true});
+  expect((await program.account.fixedRateLp.fetch(lpPubKey)).rate).to.be.eq(lpAccount.rate);
+}
+
+async function test__update_invalid_rate(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+
+  let tx = "";
+  try {
+    tx = await update_rate(program, {rate: 0, authority: wallet.payer, showException: false});
+  } catch (e) {
+    assert(e.message.indexOf("InvalidRate") > -1, "Should throw InvalidRate");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__update_rate_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const {LP_RATE_DECIMAL} = getThisProgramConstants(program);
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await update_rate(program, {
+      rate: 1 * Math.pow(10, LP_RATE_DECIMAL),
+      authority: walletKeyPair,
+      showException: false,
+    });
+  } catch (e) {
+    assert(e.message.indexOf("InvalidAuthority") > -1, "Should throw InvalidAuthority");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function getUpdateRateAccounts(program: Program<SimpleLiquidityPool>) {
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+  const {lpPubKey} = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+
+  return {
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    lpPubKey,
+  }
+}
+
+export async function update_rate(program: Program<SimpleLiquidityPool>, option: {
+  rate: number,
+  authority: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{update_rate} : ', Date.now());
+  const {rate, authority, showException} = option;
+  const {tokenBasePubKey, tokenQuotePubKey, lpPubKey} = await getUpdateRateAccounts(program);
+
+  const tx = await program.methods.updateRate(rate)
+    .accounts({
+      lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      authority: authority.publicKey,
+    })
+    .signers([authority])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{update_rate} tx: ', tx);
+
+  return tx;
+}
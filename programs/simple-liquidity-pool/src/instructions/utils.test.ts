@@ -1,8 +1,14 @@
+import * as anchor from "@project-serum/anchor";
 import {Program} from "@project-serum/anchor";
+import {PublicKey} from "@solana/web3.js";
+import {getAssociatedTokenAddressSync} from "@solana/spl-token";
 import {assert} from "chai";
 import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
 import {getProgramConstant, getProgramIdlConstant} from "../../../../tests/helpers/test-env";
 import {IDL as MoveTokenIdl} from "../../../../target/types/move_token";
+import {sleep} from "../../../../tests/helpers/time";
+
+const BPF_LOADER_UPGRADEABLE_ID = new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111");
 
 export function getThisProgramConstants(program: Program<SimpleLiquidityPool>) {
   const LP_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_SEED_PREFIX", program)), "utf8");
@@ -14,12 +20,37 @@ export function getThisProgramConstants(program: Program<SimpleLiquidityPool>) {
   const LP_FEE_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_FEE_SEED_PREFIX", program)), "utf8");
   assert(LP_FEE_SEED_PREFIX.toString().length > 0, "LP_FEE_SEED_PREFIX empty")
 
+  const LP_PROVIDER_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_PROVIDER_SEED_PREFIX", program)), "utf8");
+  assert(LP_PROVIDER_SEED_PREFIX.toString().length > 0, "LP_PROVIDER_SEED_PREFIX empty")
+
+  const LP_SHARE_MINT_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_SHARE_MINT_PREFIX", program)), "utf8");
+  assert(LP_SHARE_MINT_PREFIX.toString().length > 0, "LP_SHARE_MINT_PREFIX empty")
+
+  const LP_WSOL_TEMP_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("LP_WSOL_TEMP_SEED_PREFIX", program)), "utf8");
+  assert(LP_WSOL_TEMP_SEED_PREFIX.toString().length > 0, "LP_WSOL_TEMP_SEED_PREFIX empty")
+
+  const CP_LP_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("CP_LP_SEED_PREFIX", program)), "utf8");
+  const CP_LP_LIQUIDITY_PREFIX = Buffer.from(JSON.parse(getProgramConstant("CP_LP_LIQUIDITY_PREFIX", program)), "utf8");
+  const CP_LP_FEE_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("CP_LP_FEE_SEED_PREFIX", program)), "utf8");
+  const CP_LP_SHARE_MINT_PREFIX = Buffer.from(JSON.parse(getProgramConstant("CP_LP_SHARE_MINT_PREFIX", program)), "utf8");
+  const CP_LP_PROVIDER_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("CP_LP_PROVIDER_SEED_PREFIX", program)), "utf8");
+  assert(CP_LP_SEED_PREFIX.toString().length > 0, "CP_LP_SEED_PREFIX empty")
+
+  const CONFIG_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("CONFIG_SEED_PREFIX", program)), "utf8");
+  assert(CONFIG_SEED_PREFIX.toString().length > 0, "CONFIG_SEED_PREFIX empty")
+
+  const TREASURY_SEED_PREFIX = Buffer.from(JSON.parse(getProgramConstant("TREASURY_SEED_PREFIX", program)), "utf8");
+  assert(TREASURY_SEED_PREFIX.toString().length > 0, "TREASURY_SEED_PREFIX empty")
+
   const LP_RATE_DECIMAL = parseInt(getProgramConstant("LP_RATE_DECIMAL", program));
   // expect(LP_RATE_DECIMAL).to.be.not.NaN.and.gt(0); // ==> This syntax has Bug in assertion
   assert(LP_RATE_DECIMAL > 0, "LP_RATE_DECIMAL must > 0");
 
-  const LP_SWAP_FEE_PERMIL = parseInt(getProgramConstant("LP_SWAP_FEE_PERMIL", program));
-  assert(LP_SWAP_FEE_PERMIL > 0, "LP_SWAP_FEE_PERMIL must > 0");
+  const LP_FEE_BPS_DENOMINATOR = parseInt(getProgramConstant("LP_FEE_BPS_DENOMINATOR", program));
+  assert(LP_FEE_BPS_DENOMINATOR > 0, "LP_FEE_BPS_DENOMINATOR must > 0");
+
+  const LP_MAX_FEE_BPS = parseInt(getProgramConstant("LP_MAX_FEE_BPS", program));
+  assert(LP_MAX_FEE_BPS > 0, "LP_MAX_FEE_BPS must > 0");
 
   const TOKEN_DECIMAL = parseInt(getProgramIdlConstant("TOKEN_DECIMAL", MoveTokenIdl));
   assert(TOKEN_DECIMAL > 0, "TOKEN_DECIMAL must > 0");
@@ -28,8 +59,106 @@ export function getThisProgramConstants(program: Program<SimpleLiquidityPool>) {
     LP_SEED_PREFIX,
     LP_LIQUIDITY_PREFIX,
     LP_FEE_SEED_PREFIX,
+    LP_PROVIDER_SEED_PREFIX,
+    LP_SHARE_MINT_PREFIX,
+    LP_WSOL_TEMP_SEED_PREFIX,
+    CP_LP_SEED_PREFIX,
+    CP_LP_LIQUIDITY_PREFIX,
+    CP_LP_FEE_SEED_PREFIX,
+    CP_LP_SHARE_MINT_PREFIX,
+    CP_LP_PROVIDER_SEED_PREFIX,
+    CONFIG_SEED_PREFIX,
+    TREASURY_SEED_PREFIX,
     LP_RATE_DECIMAL,
-    LP_SWAP_FEE_PERMIL,
+    LP_FEE_BPS_DENOMINATOR,
+    LP_MAX_FEE_BPS,
     TOKEN_DECIMAL,
   };
 }
+
+/**
+ * Global config accounts, protocol fee of `quote` token is stored in treasuryQuoteAta
+ */
+export async function getConfigAccounts(program: Program<SimpleLiquidityPool>, quote: PublicKey) {
+  const {CONFIG_SEED_PREFIX, TREASURY_SEED_PREFIX} = getThisProgramConstants(program);
+  const [configPubKey] = PublicKey.findProgramAddressSync([CONFIG_SEED_PREFIX], program.programId);
+  const [treasuryPubKey] = PublicKey.findProgramAddressSync([TREASURY_SEED_PREFIX], program.programId);
+  const treasuryQuoteAta = await anchor.utils.token.associatedAddress({mint: quote, owner: treasuryPubKey});
+  // init_config checks the upgrade authority stored in the program data account
+  const [programDataPubKey] = PublicKey.findProgramAddressSync([program.programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID);
+
+  return {
+    configPubKey,
+    treasuryPubKey,
+    treasuryQuoteAta,
+    programDataPubKey,
+  };
+}
+
+/**
+ * FixedRateLP PDAs and token accounts of the <base, quote> pair
+ * PDA seeds contain both mints sorted by key, same as FixedRateLP::sorted_mints
+ */
+/**
+ * quoteTokenProgram: TOKEN_2022_PROGRAM_ID when quote is a Token-2022 mint, its ATAs are derived with it
+ * poolIndex: index chosen at init, the same pair can have several lps
+ */
+export async function getLpAccounts(
+  program: Program<SimpleLiquidityPool>,
+  base: PublicKey,
+  quote: PublicKey,
+  quoteTokenProgram = anchor.utils.token.TOKEN_PROGRAM_ID,
+  poolIndex = 0,
+) {
+  const {
+    LP_SEED_PREFIX,
+    LP_LIQUIDITY_PREFIX,
+    LP_FEE_SEED_PREFIX,
+    LP_SHARE_MINT_PREFIX,
+  } = getThisProgramConstants(program);
+
+  const [mintA, mintB] = Buffer.compare(base.toBuffer(), quote.toBuffer()) <= 0 ? [base, quote] : [quote, base];
+  const poolIndexSeed = new anchor.BN(poolIndex).toArrayLike(Buffer, "le", 2);
+  const findPda = (prefix: Buffer) => PublicKey.findProgramAddressSync(
+    [prefix, mintA.toBuffer(), mintB.toBuffer(), poolIndexSeed],
+    program.programId
+  )[0];
+
+  const lpPubKey = findPda(LP_SEED_PREFIX);
+  const lpLiquidityPubKey = findPda(LP_LIQUIDITY_PREFIX);
+  const lpFeePubKey = findPda(LP_FEE_SEED_PREFIX);
+  const lpShareMintPubKey = findPda(LP_SHARE_MINT_PREFIX);
+
+  return {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta: await anchor.utils.token.associatedAddress({mint: base, owner: lpLiquidityPubKey}),
+    lpLiquidityQuoteAta: getAssociatedTokenAddressSync(quote, lpLiquidityPubKey, true, quoteTokenProgram),
+    lpFeePubKey,
+    lpFeeBaseAta: await anchor.utils.token.associatedAddress({mint: base, owner: lpFeePubKey}),
+    lpFeeQuoteAta: getAssociatedTokenAddressSync(quote, lpFeePubKey, true, quoteTokenProgram),
+    lpShareMintPubKey,
+  };
+}
+
+/**
+ * Temporary wSOL account of user, created and closed by the program when wrapSol is set
+ */
+export function getWsolTempAccount(program: Program<SimpleLiquidityPool>, user: PublicKey) {
+  const {LP_WSOL_TEMP_SEED_PREFIX} = getThisProgramConstants(program);
+  return PublicKey.findProgramAddressSync([LP_WSOL_TEMP_SEED_PREFIX, user.toBuffer()], program.programId)[0];
+}
+
+/**
+ * Anchor events emitted by a confirmed tx
+ */
+export async function getTxEvents(program: Program<SimpleLiquidityPool>, tx: string) {
+  // rpc() only waits for the provider commitment, the tx might not be "confirmed" yet
+  let txInfo = null;
+  for (let i = 0; i < 10 && !txInfo; i++) {
+    txInfo = await program.provider.connection.getTransaction(tx, {commitment: "confirmed"});
+    !txInfo && await sleep(500);
+  }
+  const eventParser = new anchor.EventParser(program.programId, program.coder);
+  return Array.from(eventParser.parseLogs(txInfo?.meta?.logMessages ?? []));
+}
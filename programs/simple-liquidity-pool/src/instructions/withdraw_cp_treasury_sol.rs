@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+  errors::LpBaseError,
+  ProgramConfig,
+  CONFIG_SEED_PREFIX, TREASURY_SEED_PREFIX,
+};
+
+///
+/// Admin withdraw the SOL protocol fee ConstantProductLP pays as lamports to the treasury itself
+///
+/// Treasury must stay rent-exempt after withdrawal
+/// Token protocol fee, wrapped SOL included, is withdrawn by withdraw_treasury
+///
+pub fn withdraw_cp_treasury_sol(ctx: Context<TreasurySolWithdraw>, amount: u64) -> Result<()> {
+  require!(amount > 0, LpBaseError::InvalidAmount);
+
+  let treasury = ctx.accounts.treasury.to_account_info();
+  let rent_exempt_lamports = Rent::get()?.minimum_balance(ProgramConfig::SOL_HOLDER_SIZE);
+  let withdrawable = treasury.lamports().saturating_sub(rent_exempt_lamports);
+  require!(amount <= withdrawable, LpBaseError::InsufficientTreasuryAmount);
+
+  msg!("[withdraw_cp_treasury_sol] Transferring {} lamports ...", amount);
+
+  **treasury.try_borrow_mut_lamports()? -= amount;
+  **ctx.accounts.admin
+    .to_account_info()
+    .try_borrow_mut_lamports()? += amount;
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct TreasurySolWithdraw<'info> {
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    has_one = admin @ LpBaseError::InvalidAdmin,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  /// CHECK: Just to store SOL
+  #[account(
+    mut,
+    seeds = [TREASURY_SEED_PREFIX],
+    bump = config.treasury_bump,
+  )]
+  pub treasury: UncheckedAccount<'info>,
+
+  #[account(mut)]
+  pub admin: Signer<'info>,
+}
@@ -0,0 +1,260 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  token,
+  token_interface,
+  associated_token,
+};
+use crate::instructions::share_token::burn_share;
+use crate::state::{
+  errors::LpBaseError,
+  events::LiquidityWithdrawn,
+  FixedRateLP, LiquidityProvider,
+  LP_SEED_PREFIX, LP_LIQUIDITY_PREFIX, LP_PROVIDER_SEED_PREFIX, LP_SHARE_MINT_PREFIX,
+};
+
+pub fn withdraw_liquidity(
+  ctx: Context<LpWithdrawLiquidity>,
+  share: u64,
+) -> Result<()> {
+  // Only burn the share this user got from add_liquidity
+  // fee earned so far stays claimable in liquidity_provider
+  ctx.accounts.liquidity_provider.withdraw(&mut *ctx.accounts.lp, share)?;
+
+  let share_supply: u64 = ctx.accounts.lp_share_mint.supply;
+
+  let lp = &mut ctx.accounts.lp;
+  let (base_amount, quote_amount) = lp.withdraw_liquidity(
+    share,
+    share_supply,
+  )?;
+
+  burn_share_of_user(&ctx, share)?;
+
+  if base_amount > 0 {
+    transfer_token_out_of_pool(&ctx, ctx.accounts.token_base.key(), base_amount)?;
+  }
+  if quote_amount > 0 {
+    transfer_token_out_of_pool(&ctx, ctx.accounts.token_quote.key(), quote_amount)?;
+  }
+
+  let lp = &ctx.accounts.lp;
+  emit!(LiquidityWithdrawn {
+    lp: lp.key(),
+    user: ctx.accounts.user.key(),
+    token_base: lp.token_base,
+    token_quote: lp.token_quote,
+    base_amount,
+    quote_amount,
+    share,
+    amount_base: lp.amount_base,
+    amount_quote: lp.amount_quote,
+  });
+
+  Ok(())
+}
+
+
+#[derive(Accounts)]
+pub struct LpWithdrawLiquidity<'info> {
+  // lp state data
+  #[account(
+    mut,
+    seeds = [
+      LP_SEED_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.bump,
+    has_one = token_base @ LpBaseError::InvalidTokenPair,
+    has_one = token_quote @ LpBaseError::InvalidTokenPair,
+  )]
+  pub lp: Account<'info, FixedRateLP>,
+
+  #[account(mint::token_program = token_base_program)]
+  pub token_base: Box<InterfaceAccount<'info, token_interface::Mint>>,
+  #[account(mint::token_program = token_quote_program)]
+  pub token_quote: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+
+  // lp liquidity: owner of the liquidity token accounts
+  /// CHECK: data-less PDA, only signs for its token accounts
+  #[account(
+    mut,
+    seeds = [
+      LP_LIQUIDITY_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.liquidity_bump,
+  )]
+  pub lp_liquidity: UncheckedAccount<'info>,
+
+  // lp liquidity: store token liquidity, wrapped SOL included
+  #[account(
+    mut,
+    associated_token::mint = token_base,
+    associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_base_program,
+  )]
+  pub lp_liquidity_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_quote,
+    associated_token::authority = lp_liquidity,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub lp_liquidity_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_base,
+    associated_token::authority = user,
+    associated_token::token_program = token_base_program,
+  )]
+  pub user_base_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = user,
+    associated_token::mint = token_quote,
+    associated_token::authority = user,
+    associated_token::token_program = token_quote_program,
+  )]
+  pub user_quote_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  // lp share: burned on withdrawal
+  #[account(
+    mut,
+    seeds = [
+      LP_SHARE_MINT_PREFIX,
+      lp.seed_mints().0.as_ref(),
+      lp.seed_mints().1.as_ref(),
+      lp.pool_index.to_le_bytes().as_ref(),
+    ],
+    bump = lp.share_mint_bump,
+  )]
+  pub lp_share_mint: Box<Account<'info, token::Mint>>,
+
+  #[account(
+    mut,
+    associated_token::mint = lp_share_mint,
+    associated_token::authority = user,
+  )]
+  pub user_share_ata: Box<Account<'info, token::TokenAccount>>,
+
+  // how much share this user got from this lp
+  // seeds contain user key, so a user can only withdraw from his own record
+  #[account(
+    mut,
+    seeds = [
+      LP_PROVIDER_SEED_PREFIX,
+      lp.key().as_ref(),
+      user.key().as_ref()
+    ],
+    bump = liquidity_provider.bump,
+  )]
+  pub liquidity_provider: Account<'info, LiquidityProvider>,
+
+
+  #[account(mut)]
+  pub user: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_program: Program<'info, token::Token>,
+  pub token_base_program: Interface<'info, token_interface::TokenInterface>,
+  pub token_quote_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
+
+
+///
+/// Burn lp share from user wallet, see share_token.rs
+///
+fn burn_share_of_user<'info>(
+  ctx: &Context<LpWithdrawLiquidity<'info>>,
+  share: u64,
+) -> Result<()> {
+  msg!("[burn_share_of_user] Burning {} share ...", share);
+
+  let (mint_a, mint_b) = ctx.accounts.lp.seed_mints();
+  let pool_index = ctx.accounts.lp.pool_index.to_le_bytes();
+  let lp_seeds: &[&[u8]] = &[
+    LP_SEED_PREFIX,
+    mint_a.as_ref(),
+    mint_b.as_ref(),
+    pool_index.as_ref(),
+    &[ctx.accounts.lp.bump],
+  ];
+
+  burn_share(
+    &ctx.accounts.lp_share_mint.to_account_info(),
+    &ctx.accounts.user_share_ata,
+    &ctx.accounts.user.to_account_info(),
+    &ctx.accounts.lp.to_account_info(),
+    lp_seeds,
+    &ctx.accounts.token_program.to_account_info(),
+    share,
+  )
+}
+
+///
+/// Transfer token from pool back to user wallet
+///
+fn transfer_token_out_of_pool<'info>(
+  ctx: &Context<LpWithdrawLiquidity<'info>>,
+  for_token: Pubkey,
+  amount: u64,
+) -> Result<()> {
+  msg!("[transfer_token_out_of_pool] Transferring {} {} tokens ...", amount, for_token.key().to_string());
+
+  // wrapped SOL is a token like any other, user can close his wSOL ata to unwrap it
+  let is_base = for_token == ctx.accounts.lp.token_base;
+  let (mint, from, to, token_program) = if is_base {
+    (
+      &ctx.accounts.token_base,
+      &ctx.accounts.lp_liquidity_base_ata,
+      &ctx.accounts.user_base_ata,
+      ctx.accounts.token_base_program.to_account_info(),
+    )
+  } else {
+    (
+      &ctx.accounts.token_quote,
+      &ctx.accounts.lp_liquidity_quote_ata,
+      &ctx.accounts.user_quote_ata,
+      ctx.accounts.token_quote_program.to_account_info(),
+    )
+  };
+  let insufficient = if is_base { LpBaseError::InsufficientBaseAmount } else { LpBaseError::InsufficientQuoteAmount };
+  if amount > from.amount {
+    return Err(error!(insufficient));
+  }
+
+  let (mint_a, mint_b) = ctx.accounts.lp.seed_mints();
+  let pool_index = ctx.accounts.lp.pool_index.to_le_bytes();
+  let signer_seeds: &[&[&[u8]]] = &[&[
+    LP_LIQUIDITY_PREFIX,
+    mint_a.as_ref(),
+    mint_b.as_ref(),
+    pool_index.as_ref(),
+    &[ctx.accounts.lp.liquidity_bump],
+  ]];
+
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      token_program,
+      token_interface::TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: ctx.accounts.lp_liquidity.to_account_info(),
+      },
+      signer_seeds,
+    ),
+    amount,
+    mint.decimals,
+  )
+}
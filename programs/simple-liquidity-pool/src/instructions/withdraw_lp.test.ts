@@ -0,0 +1,195 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getCurrentProvider, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair} from "@solana/web3.js";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getLpAccounts, getThisProgramConstants} from "./utils.test";
+
+
+export default function test__withdraw_liquidity(program: Program<SimpleLiquidityPool>) {
+  it("Liquidity provider can burn his share to withdraw liquidity", async () => test__withdraw_provided_liquidity(program));
+  it("Cannot withdraw more than provided", async () => test__withdraw_over_provided(program));
+  it("Wallet without provided liquidity cannot withdraw", async () => test__withdraw_by_other_wallet(program));
+}
+
+async function test__withdraw_provided_liquidity(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {lpLiquidityBaseAta, lpLiquidityQuoteAta, liquidityProviderPubKey, userShareAta} = await getWithdrawAccounts(program, wallet.payer);
+  const provider = getCurrentProvider();
+
+  const providerAccount = await program.account.liquidityProvider.fetch(liquidityProviderPubKey);
+  // withdraw half of his share
+  const share = providerAccount.share.divn(2);
+
+  const lpBalances = {
+    before: {quote: 0, base: 0},
+    after: {quote: 0, base: 0},
+  }
+  lpBalances.before.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
+  lpBalances.before.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
+  const shareBefore = new anchor.BN((await provider.connection.getTokenAccountBalance(userShareAta)).value.amount);
+
+  const tx = await withdraw_liquidity(program, {
+    share,
+    payer: wallet.payer,
+    showException: true,
+  });
+  assert(!!tx, "Tx should not be empty");
+
+  lpBalances.after.base = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityBaseAta)).value.amount).toNumber();
+  lpBalances.after.quote = new anchor.BN((await provider.connection.getTokenAccountBalance(lpLiquidityQuoteAta)).value.amount).toNumber();
+  const shareAfter = new anchor.BN((await provider.connection.getTokenAccountBalance(userShareAta)).value.amount);
+
+  // lpBalances must decrease, share must be burned
+  VERBOSE && console.log('{test__withdraw_provided_liquidity} lpBalances after: ', lpBalances);
+  assert(lpBalances.after.base <= lpBalances.before.base, "LP base balance must decrease");
+  assert(lpBalances.after.quote <= lpBalances.before.quote, "LP quote balance must decrease");
+  expect(shareAfter.toString()).to.be.eq(shareBefore.sub(share).toString());
+
+  const providerAccountAfter = await program.account.liquidityProvider.fetch(liquidityProviderPubKey);
+  expect(providerAccountAfter.share.toString()).to.be.eq(providerAccount.share.sub(share).toString());
+}
+
+async function test__withdraw_over_provided(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const {liquidityProviderPubKey} = await getWithdrawAccounts(program, wallet.payer);
+  const providerAccount = await program.account.liquidityProvider.fetch(liquidityProviderPubKey);
+
+  let tx = "";
+  try {
+    tx = await withdraw_liquidity(program, {
+      share: providerAccount.share.addn(1),
+      payer: wallet.payer,
+      showException: false,
+    });
+  } catch (e) {
+    assert(e.message.indexOf("ExceedProvidedLiquidity") > -1, "Should throw error when withdraw over provided");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function test__withdraw_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await withdraw_liquidity(program, {
+      share: new anchor.BN(1),
+      payer: walletKeyPair,
+      showException: false,
+    });
+  } catch (e) {
+    // liquidity_provider account of this wallet does not exist
+    assert(!!e.message, "Should throw error when withdraw by other wallet");
+  }
+  expect(tx).to.be.empty;
+}
+
+async function getWithdrawAccounts(program: Program<SimpleLiquidityPool>, payer: Keypair) {
+  const {LP_PROVIDER_SEED_PREFIX} = getThisProgramConstants(program);
+
+  const tokenBasePubKey = NATIVE_MINT;  // Sol
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  const tokenQuotePubKey = new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+
+  const {
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    lpShareMintPubKey,
+  } = await getLpAccounts(program, tokenBasePubKey, tokenQuotePubKey);
+  const [liquidityProviderPubKey] = (anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      LP_PROVIDER_SEED_PREFIX,
+      lpPubKey.toBuffer(),
+      payer.publicKey.toBuffer(),
+    ],
+    program.programId
+  ))
+
+  const userBaseAta = await anchor.utils.token.associatedAddress({
+    mint: tokenBasePubKey,
+    owner: payer.publicKey
+  });
+  const userQuoteAta = await anchor.utils.token.associatedAddress({
+    mint: tokenQuotePubKey,
+    owner: payer.publicKey
+  });
+  const userShareAta = await anchor.utils.token.associatedAddress({
+    mint: lpShareMintPubKey,
+    owner: payer.publicKey
+  });
+
+  return {
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    liquidityProviderPubKey,
+    lpShareMintPubKey,
+    userBaseAta,
+    userQuoteAta,
+    userShareAta,
+  }
+}
+
+export async function withdraw_liquidity(program: Program<SimpleLiquidityPool>, option: {
+  share: anchor.BN,
+  payer: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{withdraw_liquidity} : ', Date.now());
+  const {share, payer, showException} = option;
+
+  const {
+    tokenBasePubKey,
+    tokenQuotePubKey,
+    lpPubKey,
+    lpLiquidityPubKey,
+    lpLiquidityBaseAta,
+    lpLiquidityQuoteAta,
+    liquidityProviderPubKey,
+    lpShareMintPubKey,
+    userBaseAta,
+    userQuoteAta,
+    userShareAta,
+  } = await getWithdrawAccounts(program, payer);
+
+  const tx = await program.methods.withdrawLiquidity(share)
+    .accounts({
+      lp: lpPubKey,
+      tokenBase: tokenBasePubKey,
+      tokenQuote: tokenQuotePubKey,
+      lpLiquidity: lpLiquidityPubKey,
+      lpLiquidityBaseAta: lpLiquidityBaseAta,
+      lpLiquidityQuoteAta: lpLiquidityQuoteAta,
+      userBaseAta: userBaseAta,
+      userQuoteAta: userQuoteAta,
+      lpShareMint: lpShareMintPubKey,
+      userShareAta: userShareAta,
+      liquidityProvider: liquidityProviderPubKey,
+      user: payer.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenBaseProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      tokenQuoteProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([payer])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{withdraw_liquidity} tx: ', tx);
+
+  return tx;
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+  token_interface,
+  associated_token,
+};
+use crate::state::{
+  errors::LpBaseError,
+  ProgramConfig,
+  CONFIG_SEED_PREFIX, TREASURY_SEED_PREFIX,
+};
+
+///
+/// Admin withdraw the protocol fee collected in the treasury ATA of one token
+///
+/// Any mint, wrapped SOL included: FixedRateLP pays protocol fee to the treasury ATAs
+/// SOL paid as lamports by ConstantProductLP is withdrawn by withdraw_cp_treasury_sol
+///
+pub fn withdraw_treasury(ctx: Context<TreasuryWithdraw>, amount: u64) -> Result<()> {
+  require!(amount > 0, LpBaseError::InvalidAmount);
+  require!(amount <= ctx.accounts.treasury_ata.amount, LpBaseError::InsufficientTreasuryAmount);
+
+  msg!("[withdraw_treasury] Transferring {} {} tokens ...", amount, ctx.accounts.token_mint.key().to_string());
+
+  let signer_seeds: &[&[&[u8]]] = &[&[
+    TREASURY_SEED_PREFIX,
+    &[ctx.accounts.config.treasury_bump],
+  ]];
+
+  token_interface::transfer_checked(
+    CpiContext::new_with_signer(
+      ctx.accounts.token_program.to_account_info(),
+      token_interface::TransferChecked {
+        from: ctx.accounts.treasury_ata.to_account_info(),
+        mint: ctx.accounts.token_mint.to_account_info(),
+        to: ctx.accounts.admin_ata.to_account_info(),
+        authority: ctx.accounts.treasury.to_account_info(),
+      },
+      signer_seeds,
+    ),
+    amount,
+    ctx.accounts.token_mint.decimals,
+  )
+}
+
+
+#[derive(Accounts)]
+pub struct TreasuryWithdraw<'info> {
+  #[account(
+    seeds = [CONFIG_SEED_PREFIX],
+    bump = config.bump,
+    has_one = admin @ LpBaseError::InvalidAdmin,
+  )]
+  pub config: Account<'info, ProgramConfig>,
+
+  #[account(mint::token_program = token_program)]
+  pub token_mint: Box<InterfaceAccount<'info, token_interface::Mint>>,
+
+  /// CHECK: data-less PDA, only signs for its token accounts
+  #[account(
+    seeds = [TREASURY_SEED_PREFIX],
+    bump = config.treasury_bump,
+  )]
+  pub treasury: UncheckedAccount<'info>,
+
+  #[account(
+    mut,
+    associated_token::mint = token_mint,
+    associated_token::authority = treasury,
+    associated_token::token_program = token_program,
+  )]
+  pub treasury_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(
+    init_if_needed,
+    payer = admin,
+    associated_token::mint = token_mint,
+    associated_token::authority = admin,
+    associated_token::token_program = token_program,
+  )]
+  pub admin_ata: Box<InterfaceAccount<'info, token_interface::TokenAccount>>,
+
+  #[account(mut)]
+  pub admin: Signer<'info>,
+
+  pub system_program: Program<'info, System>,
+  pub token_program: Interface<'info, token_interface::TokenInterface>,
+  pub associated_token_program: Program<'info, associated_token::AssociatedToken>,
+}
@@ -0,0 +1,93 @@
+import * as anchor from "@project-serum/anchor";
+import {Program} from "@project-serum/anchor";
+import {SimpleLiquidityPool} from "../../../../target/types/simple_liquidity_pool";
+import {getCurrentProvider, getProviderWallet, VERBOSE} from "../../../../tests/helpers/test-env";
+import {assert, expect} from "chai";
+import {getPrevMintTokenInfoFromTmpData} from "../../../move-token/src/instructions/create_token.test";
+import {Keypair, PublicKey} from "@solana/web3.js";
+import {NATIVE_MINT} from "@solana/spl-token";
+import {airDropSolIfBalanceLowerThan} from "../../../../tests/helpers/token";
+import {getConfigAccounts} from "./utils.test";
+
+
+export default function test__withdraw_treasury(program: Program<SimpleLiquidityPool>) {
+  // Must run after swap, so the treasury has some protocol fee
+  it("Admin can withdraw protocol fee from treasury", async () => test__withdraw_treasury_by_admin(program));
+  it("Other wallet cannot withdraw from treasury", async () => test__withdraw_treasury_by_other_wallet(program));
+}
+
+async function test__withdraw_treasury_by_admin(program: Program<SimpleLiquidityPool>) {
+  const wallet = getProviderWallet();
+  const provider = getCurrentProvider();
+
+  // swap test swapped both ways: wrapped SOL protocol fee sits in the treasury native mint ATA, withdrawn like any other token
+  for (const tokenMint of [NATIVE_MINT, getQuotePubKey()]) {
+    const {treasuryQuoteAta: treasuryAta} = await getConfigAccounts(program, tokenMint);
+
+    const amount = new anchor.BN((await provider.connection.getTokenAccountBalance(treasuryAta)).value.amount).toNumber();
+    assert(amount > 0, "Treasury must receive protocol fee from swap");
+
+    const tx = await withdraw_treasury(program, {tokenMint, amount, admin: wallet.payer, showException: true});
+    assert(!!tx, "Tx should not be empty");
+
+    const amountAfter = new anchor.BN((await provider.connection.getTokenAccountBalance(treasuryAta)).value.amount).toNumber();
+    expect(amountAfter).to.be.eq(0);
+  }
+}
+
+async function test__withdraw_treasury_by_other_wallet(program: Program<SimpleLiquidityPool>) {
+  const walletKeyPair = anchor.web3.Keypair.generate();
+  await airDropSolIfBalanceLowerThan(0.1, walletKeyPair.publicKey);
+
+  let tx = "";
+  try {
+    tx = await withdraw_treasury(program, {
+      tokenMint: getQuotePubKey(),
+      amount: 1,
+      admin: walletKeyPair,
+      showException: false,
+    });
+  } catch (e) {
+    assert(e.message.indexOf("InvalidAdmin") > -1, "Should throw InvalidAdmin");
+  }
+  expect(tx).to.be.empty;
+}
+
+function getQuotePubKey() {
+  const prevMintToken = getPrevMintTokenInfoFromTmpData(); // This test must run after mint test; Test run async but mochajs test case will run once by one
+  return new anchor.web3.PublicKey(prevMintToken.mintKeypair.publicKey)
+}
+
+export async function withdraw_treasury(program: Program<SimpleLiquidityPool>, option: {
+  tokenMint: PublicKey,
+  amount: number,
+  admin: Keypair,
+  showException?: boolean,
+}) {
+  console.log('{withdraw_treasury} : ', Date.now());
+  const {tokenMint, amount, admin, showException} = option;
+  const {configPubKey, treasuryPubKey, treasuryQuoteAta: treasuryAta} = await getConfigAccounts(program, tokenMint);
+  const adminAta = await anchor.utils.token.associatedAddress({mint: tokenMint, owner: admin.publicKey});
+
+  const tx = await program.methods.withdrawTreasury(new anchor.BN(amount))
+    .accounts({
+      config: configPubKey,
+      tokenMint: tokenMint,
+      treasury: treasuryPubKey,
+      treasuryAta: treasuryAta,
+      adminAta: adminAta,
+      admin: admin.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+    })
+    .signers([admin])
+    .rpc()
+    .catch(e => {
+      VERBOSE && showException && console.log('Error: ', e); // show on-chain logs
+      throw e;
+    });
+  VERBOSE && console.log('{withdraw_treasury} tx: ', tx);
+
+  return tx;
+}
@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::{
+  token,
+  token::spl_token,
+};
+use crate::state::{
+  errors::LpBaseError,
+  LP_WSOL_TEMP_SEED_PREFIX,
+};
+
+///
+/// Temporary wSOL account of a user, so he can pay and receive native SOL
+/// while the lp holds wrapped SOL in its token accounts
+///
+/// It's a PDA of [LP_WSOL_TEMP_SEED_PREFIX, user] owned by the token program, authority is the user:
+///   open_wsol_temp_account: create it, wrap `amount` SOL from user wallet into it
+///   close_wsol_temp_account: unwrap everything left back to user wallet, rent included
+/// Both must be called in the same instruction, so the account never outlives it
+///
+pub(crate) fn open_wsol_temp_account<'info>(
+  wsol_temp: &AccountInfo<'info>,
+  wsol_temp_bump: u8,
+  native_mint: &AccountInfo<'info>,
+  user: &AccountInfo<'info>,
+  system_program: &AccountInfo<'info>,
+  token_program: &AccountInfo<'info>,
+  amount: u64,
+) -> Result<()> {
+  msg!("[open_wsol_temp_account] Wrapping {} SOL ...", amount);
+  require_keys_eq!(native_mint.key(), spl_token::native_mint::id(), LpBaseError::InvalidWrapSol);
+
+  let user_key = user.key();
+  let signer_seeds: &[&[&[u8]]] = &[&[
+    LP_WSOL_TEMP_SEED_PREFIX,
+    user_key.as_ref(),
+    &[wsol_temp_bump],
+  ]];
+
+  // Anyone can send lamports to the PDA address, so create_account cannot be used if it already has some
+  let space = token::TokenAccount::LEN;
+  let rent_exempt_lamports = Rent::get()?.minimum_balance(space);
+  let current_lamports = wsol_temp.lamports();
+  if current_lamports == 0 {
+    system_program::create_account(
+      CpiContext::new_with_signer(
+        system_program.clone(),
+        system_program::CreateAccount {
+          from: user.clone(),
+          to: wsol_temp.clone(),
+        },
+        signer_seeds,
+      ),
+      rent_exempt_lamports,
+      space as u64,
+      token_program.key,
+    )?;
+  } else {
+    let missing_lamports = rent_exempt_lamports.saturating_sub(current_lamports);
+    if missing_lamports > 0 {
+      transfer_sol(user, wsol_temp, system_program, missing_lamports)?;
+    }
+    system_program::allocate(
+      CpiContext::new_with_signer(
+        system_program.clone(),
+        system_program::Allocate { account_to_allocate: wsol_temp.clone() },
+        signer_seeds,
+      ),
+      space as u64,
+    )?;
+    system_program::assign(
+      CpiContext::new_with_signer(
+        system_program.clone(),
+        system_program::Assign { account_to_assign: wsol_temp.clone() },
+        signer_seeds,
+      ),
+      token_program.key,
+    )?;
+  }
+
+  token::initialize_account3(
+    CpiContext::new(
+      token_program.clone(),
+      token::InitializeAccount3 {
+        account: wsol_temp.clone(),
+        mint: native_mint.clone(),
+        authority: user.clone(),
+      },
+    ),
+  )?;
+
+  if amount > 0 {
+    transfer_sol(user, wsol_temp, system_program, amount)?;
+    token::sync_native(
+      CpiContext::new(
+        token_program.clone(),
+        token::SyncNative { account: wsol_temp.clone() },
+      ),
+    )?;
+  }
+
+  Ok(())
+}
+
+pub(crate) fn close_wsol_temp_account<'info>(
+  wsol_temp: &AccountInfo<'info>,
+  user: &AccountInfo<'info>,
+  token_program: &AccountInfo<'info>,
+) -> Result<()> {
+  msg!("[close_wsol_temp_account] Unwrapping {} lamports ...", wsol_temp.lamports());
+
+  token::close_account(
+    CpiContext::new(
+      token_program.clone(),
+      token::CloseAccount {
+        account: wsol_temp.clone(),
+        destination: user.clone(),
+        authority: user.clone(),
+      },
+    ),
+  )
+}
+
+fn transfer_sol<'info>(
+  from: &AccountInfo<'info>,
+  to: &AccountInfo<'info>,
+  system_program: &AccountInfo<'info>,
+  amount: u64,
+) -> Result<()> {
+  system_program::transfer(
+    CpiContext::new(
+      system_program.clone(),
+      system_program::Transfer {
+        from: from.clone(),
+        to: to.clone(),
+      },
+    ),
+    amount,
+  )
+}
@@ -1,9 +1,13 @@
+// anchor_lang::Result carries a large error type, every instruction returns it
+#![allow(clippy::result_large_err)]
+
 mod instructions;
-mod state;
+pub mod state;
 mod errors;
 
 use anchor_lang::prelude::*;
 use instructions::*; // Must import as * to avoid error
+use state::LpStatus;
 
 declare_id!("GMDA6SqHUFzctniBczeBSsoLEfd3HaW161wwyAms2buL");
 
@@ -11,29 +15,136 @@ declare_id!("GMDA6SqHUFzctniBczeBSsoLEfd3HaW161wwyAms2buL");
 pub mod simple_liquidity_pool {
   use super::*;
 
-  pub fn initialize(ctx: Context<LpInit>, fixed_rate: u32) -> Result<()> {
-    init::init(ctx, fixed_rate)
+  /*
+   * Global program config: protocol fee and treasury
+   */
+  pub fn init_config(ctx: Context<ConfigInit>, protocol_fee_share_bps: u16, default_fee_bps: u16) -> Result<()> {
+    init_config::init_config(ctx, protocol_fee_share_bps, default_fee_bps)
+  }
+
+  pub fn update_config(ctx: Context<ConfigUpdate>, protocol_fee_share_bps: u16, default_fee_bps: u16) -> Result<()> {
+    update_config::update_config(ctx, protocol_fee_share_bps, default_fee_bps)
+  }
+
+  pub fn set_paused(ctx: Context<ConfigSetPaused>, paused: bool) -> Result<()> {
+    set_paused::set_paused(ctx, paused)
+  }
+
+  /// pool_creator: Pubkey::default() to approve nobody
+  pub fn set_pool_creation(ctx: Context<ConfigSetPoolCreation>, permissionless_init: bool, pool_creator: Pubkey) -> Result<()> {
+    set_pool_creation::set_pool_creation(ctx, permissionless_init, pool_creator)
+  }
+
+  pub fn propose_admin(ctx: Context<ConfigProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    propose_admin::propose_admin(ctx, new_admin)
+  }
+
+  pub fn accept_admin(ctx: Context<ConfigAcceptAdmin>) -> Result<()> {
+    accept_admin::accept_admin(ctx)
+  }
+
+  pub fn withdraw_treasury(ctx: Context<TreasuryWithdraw>, amount: u64) -> Result<()> {
+    withdraw_treasury::withdraw_treasury(ctx, amount)
   }
 
-  pub fn add_liquidity(ctx: Context<LpAddLiquidity>, base_amount: u64, quote_amount: u64) -> Result<()> {
-    add_lp::add_liquidity(ctx, base_amount, quote_amount)
+  pub fn withdraw_cp_treasury_sol(ctx: Context<TreasurySolWithdraw>, amount: u64) -> Result<()> {
+    withdraw_cp_treasury_sol::withdraw_cp_treasury_sol(ctx, amount)
   }
 
+  /*
+   * Fixed rate lp
+   */
+  /// fee_bps: None to use the default fee of program config
+  /// pool_index: any free index of this pair, so several lps can compete on rate and fee
+  pub fn initialize(ctx: Context<LpInit>, fixed_rate: u32, fee_bps: Option<u16>, pool_index: u16) -> Result<()> {
+    init::init(ctx, fixed_rate, fee_bps, pool_index)
+  }
+
+  /// wrap_sol: pay native SOL instead of wSOL, the lp must have native SOL as base or quote
+  pub fn add_liquidity(ctx: Context<LpAddLiquidity>, base_amount: u64, quote_amount: u64, wrap_sol: bool) -> Result<()> {
+    add_lp::add_liquidity(ctx, base_amount, quote_amount, wrap_sol)
+  }
+
+  /// wrap_sol: pay and receive native SOL instead of wSOL, the lp must have native SOL as base or quote
   pub fn swap(
     ctx: Context<LpSwap>,
     from: Pubkey,
     to: Pubkey,
     from_amount: u64,
+    min_amount_out: u64,
+    wrap_sol: bool,
+  ) -> Result<()> {
+    swap::swap(ctx, from, to, from_amount, min_amount_out, wrap_sol)
+  }
+
+  pub fn swap_exact_out(
+    ctx: Context<LpSwap>,
+    from: Pubkey,
+    to: Pubkey,
+    to_amount: u64,
+    max_amount_in: u64,
+    wrap_sol: bool,
+  ) -> Result<()> {
+    swap_exact_out::swap_exact_out(ctx, from, to, to_amount, max_amount_in, wrap_sol)
+  }
+
+  /// Read-only, simulate it to get the quote from return data
+  pub fn quote_swap(ctx: Context<LpQuoteSwap>, from: Pubkey, to: Pubkey, from_amount: u64) -> Result<SwapQuote> {
+    quote_swap::quote_swap(ctx, from, to, from_amount)
+  }
+
+  pub fn withdraw_liquidity(ctx: Context<LpWithdrawLiquidity>, share: u64) -> Result<()> {
+    withdraw_lp::withdraw_liquidity(ctx, share)
+  }
+
+  pub fn claim_fee(ctx: Context<LpClaimFee>) -> Result<()> {
+    claim_fee::claim_fee(ctx)
+  }
+
+  pub fn update_rate(ctx: Context<LpUpdateRate>, fixed_rate: u32) -> Result<()> {
+    update_rate::update_rate(ctx, fixed_rate)
+  }
+
+  pub fn update_fee(ctx: Context<LpUpdateFee>, fee_bps: u16) -> Result<()> {
+    update_fee::update_fee(ctx, fee_bps)
+  }
+
+  pub fn set_status(ctx: Context<LpSetStatus>, status: LpStatus) -> Result<()> {
+    set_status::set_status(ctx, status)
+  }
+
+  pub fn close_pool(ctx: Context<LpClosePool>) -> Result<()> {
+    close_pool::close_pool(ctx)
+  }
+
+  /*
+   * Constant product AMM lp: base_reserve * quote_reserve = k
+   */
+  /// fee_bps: None to use the default fee of program config
+  pub fn cp_initialize(ctx: Context<CpLpInit>, fee_bps: Option<u16>) -> Result<()> {
+    cp_init::cp_init(ctx, fee_bps)
+  }
+
+  pub fn cp_add_liquidity(ctx: Context<CpLpAddLiquidity>, max_base_amount: u64, max_quote_amount: u64) -> Result<()> {
+    cp_add_lp::cp_add_liquidity(ctx, max_base_amount, max_quote_amount)
+  }
+
+  pub fn cp_swap(
+    ctx: Context<CpLpSwap>,
+    from: Pubkey,
+    to: Pubkey,
+    from_amount: u64,
+    min_amount_out: u64,
   ) -> Result<()> {
-    swap::swap(ctx, from, to, from_amount)
+    cp_swap::cp_swap(ctx, from, to, from_amount, min_amount_out)
   }
 
-  // pub fn withdraw_liquidity(ctx: Context<LpAddLiquidity>, base_amount: u64, quote_amount: u64) -> Result<()> {
-  //   todo!()
-  // }
-  //
-  // pub fn destruct(ctx: Context<Tmp>, from: Token, to: Token, fromAmount: int) -> Result<()> {
-  //   todo!()
-  // }
+  pub fn cp_withdraw_liquidity(ctx: Context<CpLpWithdrawLiquidity>, share: u64) -> Result<()> {
+    cp_withdraw_lp::cp_withdraw_liquidity(ctx, share)
+  }
+
+  pub fn cp_claim_fee(ctx: Context<CpLpClaimFee>) -> Result<()> {
+    cp_claim_fee::cp_claim_fee(ctx)
+  }
 
 }
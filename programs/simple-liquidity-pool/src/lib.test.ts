@@ -1,14 +1,36 @@
 import {Program} from "@project-serum/anchor";
 import {SimpleLiquidityPool} from "../../../target/types/simple_liquidity_pool";
 import testProgram from "../../../tests/helpers/testProgram";
+import test__init_config from "./instructions/init_config.test";
 import test__init from "./instructions/init.test";
 import test__add_liquidity from "./instructions/add_lp.test";
 import test__swap from "./instructions/swap.test";
+import test__swap_exact_out from "./instructions/swap_exact_out.test";
+import test__withdraw_liquidity from "./instructions/withdraw_lp.test";
+import test__claim_fee from "./instructions/claim_fee.test";
+import test__withdraw_treasury from "./instructions/withdraw_treasury.test";
+import test__update_rate from "./instructions/update_rate.test";
+import test__update_fee from "./instructions/update_fee.test";
+import test__set_status from "./instructions/set_status.test";
+import test__close_pool from "./instructions/close_pool.test";
+import test__token_2022 from "./instructions/token_2022.test";
+import test__cp_lp from "./instructions/cp_lp.test";
 
 const tests = [
+  test__init_config,
   test__init,
   test__add_liquidity,
   test__swap,
+  test__swap_exact_out,
+  test__claim_fee,
+  test__withdraw_treasury,
+  test__withdraw_liquidity,
+  test__update_rate,
+  test__update_fee,
+  test__set_status,
+  test__close_pool,
+  test__token_2022,
+  test__cp_lp,
   // test__fullFlow,
 ];
 testProgram<SimpleLiquidityPool>("SimpleLiquidityPool", tests)
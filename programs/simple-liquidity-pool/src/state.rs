@@ -1,6 +1,13 @@
 pub mod lp;
 pub mod fixed_rate_lp;
+pub mod constant_product_lp;
+pub mod liquidity_provider;
+pub mod program_config;
 pub mod errors;
+pub mod events;
 
 pub use lp::*;
 pub use fixed_rate_lp::*;
+pub use constant_product_lp::*;
+pub use liquidity_provider::*;
+pub use program_config::*;
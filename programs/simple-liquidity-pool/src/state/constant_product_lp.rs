@@ -0,0 +1,264 @@
+use anchor_lang::prelude::*;
+use pool_math::ceil_div;
+use crate::state::{errors::*, SwapDir, FeeSharingLP, LP_FEE_BPS_DENOMINATOR};
+
+///
+/// AMM lp for <Sol, SplToken>: base_reserve * quote_reserve = k
+///
+/// Same as FixedRateLP, base token is hard coded to SOL
+/// but the price follows the reserves instead of a fixed rate:
+///   1 base = quote_reserve / base_reserve quote
+///
+/// Reserves are cached here instead of reading lamports,
+/// so rent-exempt lamports and tokens sent directly to the pool are never counted as liquidity
+///
+#[account]
+#[derive(Default)]
+pub struct ConstantProductLP {
+  /// Who init this lp
+  pub authority: Pubkey,        // 32
+
+  // NOTE: base token is hardcoded to be native SOL
+  pub token_base: Pubkey,       // 32
+  pub token_quote: Pubkey,      // 32
+
+  pub reserve_base: u64,        // 8
+  pub reserve_quote: u64,       // 8
+
+  /// Swap fee deducted on to_amount, in LP_FEE_BPS_DENOMINATOR like FixedRateLP
+  /// max = LP_MAX_FEE_BPS
+  pub fee_bps: u16,             // 2
+
+  // profit tracking for all liquidity provider: see FeeSharingLP
+  pub fee_base_per_share: u128,   // 16
+  pub fee_quote_per_share: u128,  // 16
+  pub fee_base_unclaimed: u64,    // 8: settled in LiquidityProvider, not claimed yet
+  pub fee_quote_unclaimed: u64,   // 8
+
+  // misc
+  pub bump: u8,                 // 1
+  pub liquidity_bump: u8,       // 1
+  pub fee_bump: u8,             // 1
+  pub share_mint_bump: u8,      // 1
+}
+
+
+#[constant]
+pub const CP_LP_SEED_PREFIX: &[u8] = b"ConstantProductLP_";
+#[constant]
+pub const CP_LP_LIQUIDITY_PREFIX: &[u8] = b"ConstantProductLP_liquid_";
+#[constant]
+pub const CP_LP_FEE_SEED_PREFIX: &[u8] = b"ConstantProductLP_fee_";
+#[constant]
+pub const CP_LP_SHARE_MINT_PREFIX: &[u8] = b"ConstantProductLP_share_";
+#[constant]
+pub const CP_LP_PROVIDER_SEED_PREFIX: &[u8] = b"ConstantProductLP_provider_";
+
+impl ConstantProductLP {
+  pub const MAXIMUM_SIZE: usize = 32 + 32 + 32 + 8 + 8 + 2 + 16 + 16 + 8 + 8 + 1 + 1 + 1 + 1;
+  /// lp_liquidity and lp_fee are data-less PDAs, only used to hold SOL
+  pub const SOL_HOLDER_SIZE: usize = 8 + 8;
+
+  pub fn get_swap_dir(&self, from_token: Pubkey, to_token: Pubkey) -> Option<SwapDir> {
+    if from_token == self.token_base && to_token == self.token_quote {
+      Some(SwapDir::BaseToQuote)
+    } else if from_token == self.token_quote && to_token == self.token_base {
+      Some(SwapDir::QuoteToBase)
+    } else {
+      None
+    }
+  }
+}
+
+impl FeeSharingLP for ConstantProductLP {
+  fn fee_per_share(&self) -> (u128, u128) {
+    (self.fee_base_per_share, self.fee_quote_per_share)
+  }
+
+  fn fee_per_share_mut(&mut self) -> (&mut u128, &mut u128) {
+    (&mut self.fee_base_per_share, &mut self.fee_quote_per_share)
+  }
+
+  fn fee_unclaimed(&self) -> (u64, u64) {
+    (self.fee_base_unclaimed, self.fee_quote_unclaimed)
+  }
+
+  fn fee_unclaimed_mut(&mut self) -> (&mut u64, &mut u64) {
+    (&mut self.fee_base_unclaimed, &mut self.fee_quote_unclaimed)
+  }
+}
+
+/// What ConstantProductLP::init needs: who creates the lp, its pair, its fee and the PDA bumps
+pub struct CpLpInitParams {
+  pub authority: Pubkey,
+  pub token_base: Pubkey,
+  pub token_quote: Pubkey,
+  pub fee_bps: u16,
+  pub bump: u8,
+  pub liquidity_bump: u8,
+  pub fee_bump: u8,
+  pub share_mint_bump: u8,
+}
+
+impl ConstantProductLP {
+  pub fn init(&mut self, params: CpLpInitParams) -> Result<()> {
+    pool_math::validate_fee(params.fee_bps).map_err(LpBaseError::from)?;
+
+    self.authority = params.authority;
+    self.token_base = params.token_base;
+    self.token_quote = params.token_quote;
+    self.reserve_base = 0;
+    self.reserve_quote = 0;
+    self.fee_bps = params.fee_bps;
+    self.bump = params.bump;
+    self.liquidity_bump = params.liquidity_bump;
+    self.fee_bump = params.fee_bump;
+    self.share_mint_bump = params.share_mint_bump;
+
+    Ok(())
+  }
+
+  ///
+  /// Return (base_amount, quote_amount, share)
+  ///   base_amount, quote_amount: what will be taken from user, at the current reserve ratio
+  ///   share: the amount of share to mint
+  ///
+  /// max_base_amount, max_quote_amount are the most user want to add,
+  /// first deposit will take both of them and set the initial price
+  ///
+  pub fn add_liquidity(
+    &mut self,
+    max_base_amount: u64,
+    max_quote_amount: u64,
+    share_supply: u64,
+  ) -> Result<(u64, u64, u64)> {
+    require_gt!(max_base_amount, 0, LpBaseError::InvalidAmount);
+    require_gt!(max_quote_amount, 0, LpBaseError::InvalidAmount);
+
+    let (base_amount, quote_amount, share) = if share_supply == 0 || self.reserve_base == 0 || self.reserve_quote == 0 {
+      // first deposit: share = sqrt(base * quote)
+      let share = integer_sqrt(max_base_amount as u128 * max_quote_amount as u128);
+      (max_base_amount, max_quote_amount, share)
+    } else {
+      let reserve_base = self.reserve_base as u128;
+      let reserve_quote = self.reserve_quote as u128;
+      let supply = share_supply as u128;
+
+      // Take only what match the current price, round up in favour of the pool
+      let quote_needed = ceil_div(max_base_amount as u128 * reserve_quote, reserve_base);
+      let (base_amount, quote_amount) = if quote_needed <= max_quote_amount as u128 {
+        (max_base_amount as u128, quote_needed)
+      } else {
+        let base_needed = ceil_div(max_quote_amount as u128 * reserve_base, reserve_quote);
+        (base_needed, max_quote_amount as u128)
+      };
+
+      // Round down in favour of the pool
+      let share = std::cmp::min(
+        base_amount * supply / reserve_base,
+        quote_amount * supply / reserve_quote,
+      );
+      (base_amount as u64, quote_amount as u64, share)
+    };
+
+    let share = u64::try_from(share).map_err(|_| LpBaseError::InvalidAmount)?;
+    require_gt!(share, 0, LpBaseError::InvalidAmount);
+
+    self.reserve_base = self.reserve_base.checked_add(base_amount).ok_or(LpBaseError::InvalidAmount)?;
+    self.reserve_quote = self.reserve_quote.checked_add(quote_amount).ok_or(LpBaseError::InvalidAmount)?;
+
+    Ok((base_amount, quote_amount, share))
+  }
+
+  ///
+  /// Return (base_amount, quote_amount) to send back for the burned share
+  ///
+  pub fn withdraw_liquidity(&mut self, share: u64, share_supply: u64) -> Result<(u64, u64)> {
+    require_gt!(share, 0, LpBaseError::InvalidAmount);
+    require!(share <= share_supply, LpBaseError::ExceedProvidedLiquidity);
+
+    // Round down in favour of the pool, both are <= reserve so they always fit in u64
+    let base_amount = (self.reserve_base as u128 * share as u128 / share_supply as u128) as u64;
+    let quote_amount = (self.reserve_quote as u128 * share as u128 / share_supply as u128) as u64;
+
+    self.reserve_base -= base_amount;
+    self.reserve_quote -= quote_amount;
+
+    Ok((base_amount, quote_amount))
+  }
+
+  ///
+  /// Return (
+  ///   swap_dir,
+  ///   from_amount: from token change amount,
+  ///   to_amount_without_fee: to token change amount,
+  ///   fee: the fee deducted on to_amount,
+  /// )
+  ///
+  /// to_amount = to_reserve * from_amount / (from_reserve + from_amount), rounded down
+  /// so k never decreases
+  ///
+  pub fn preview_swap(
+    &self,
+    from_token: Pubkey,
+    to_token: Pubkey,
+    from_amount: u64,
+  ) -> Result<(SwapDir, u64, u64, u64)> {
+    require_gt!(from_amount, 0, LpBaseError::InvalidSwapAmount);
+
+    let swap_direction = self.get_swap_dir(from_token, to_token);
+    require!(swap_direction.is_some(), LpBaseError::InvalidSwapToken);
+    let swap_dir = swap_direction.unwrap();
+
+    let (from_reserve, to_reserve) = match swap_dir {
+      SwapDir::BaseToQuote => (self.reserve_base, self.reserve_quote),
+      SwapDir::QuoteToBase => (self.reserve_quote, self.reserve_base),
+    };
+
+    let to_amount = to_reserve as u128 * from_amount as u128 / (from_reserve as u128 + from_amount as u128);
+    // to_amount < to_reserve so it always fit in u64
+    let to_amount = to_amount as u64;
+    match swap_dir {
+      SwapDir::BaseToQuote => require_gt!(to_amount, 0, LpBaseError::InsufficientQuoteAmount),
+      SwapDir::QuoteToBase => require_gt!(to_amount, 0, LpBaseError::InsufficientBaseAmount),
+    };
+
+    let fee = self.get_swap_fee(to_amount);
+
+    Ok((swap_dir, from_amount, to_amount, fee))
+  }
+
+  /// Apply a swap previewed by preview_swap to the reserves
+  pub fn swap(&mut self, swap_dir: &SwapDir, from_amount: u64, to_amount_without_fee: u64) -> Result<()> {
+    let (from_reserve, to_reserve) = match swap_dir {
+      SwapDir::BaseToQuote => (&mut self.reserve_base, &mut self.reserve_quote),
+      SwapDir::QuoteToBase => (&mut self.reserve_quote, &mut self.reserve_base),
+    };
+
+    // fee leaves the reserve too, it goes to lp fee account
+    *from_reserve = from_reserve.checked_add(from_amount).ok_or(LpBaseError::LargeSwapAmount)?;
+    *to_reserve = to_reserve.checked_sub(to_amount_without_fee).ok_or(LpBaseError::LargeSwapAmount)?;
+
+    Ok(())
+  }
+
+  /// Rounded up like FixedRateLP, fee is never more than to_amount so it always fit in u64
+  fn get_swap_fee(&self, to_amount: u64) -> u64 {
+    ceil_div(to_amount as u128 * self.fee_bps as u128, LP_FEE_BPS_DENOMINATOR as u128) as u64
+  }
+}
+
+/// Babylonian method, rounded down
+fn integer_sqrt(value: u128) -> u128 {
+  if value < 2 {
+    return value;
+  }
+
+  let mut x = value;
+  let mut y = ceil_div(x, 2);
+  while y < x {
+    x = y;
+    y = (x + value / x) / 2;
+  }
+  x
+}
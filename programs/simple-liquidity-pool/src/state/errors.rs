@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use pool_math::MathError;
 
 #[error_code]
 pub enum LpBaseError {
@@ -6,7 +7,7 @@ pub enum LpBaseError {
   InvalidRate,
   #[msg("Amount must be >= 0")]
   InvalidAmount,
-  #[msg("Amount must be > 0")]
+  #[msg("Swap amount must be > 0 and give a non-zero output")]
   InvalidSwapAmount,
   #[msg("Swap amount too large, plz try with smaller amount")]
   LargeSwapAmount,
@@ -16,4 +17,58 @@ pub enum LpBaseError {
   InsufficientQuoteAmount,
   #[msg("Base amount is insufficient, please ask LP provider for adding more liquidity")]
   InsufficientBaseAmount,
+  #[msg("Withdraw amount is greater than the liquidity you provided")]
+  ExceedProvidedLiquidity,
+  #[msg("Only lp authority can do this")]
+  InvalidAuthority,
+  #[msg("Swap output is less than min_amount_out, price might have changed")]
+  SlippageExceeded,
+  #[msg("Swap input is greater than max_amount_in, price might have changed")]
+  MaxAmountInExceeded,
+  #[msg("Swap fee must be <= LP_MAX_FEE_BPS")]
+  InvalidFee,
+  #[msg("Protocol fee share must be <= MAX_PROTOCOL_FEE_SHARE_BPS")]
+  InvalidProtocolFeeShare,
+  #[msg("Only program config admin can do this")]
+  InvalidAdmin,
+  #[msg("Program is paused by admin")]
+  ProgramPaused,
+  #[msg("Only the proposed admin can accept the admin role")]
+  InvalidPendingAdmin,
+  #[msg("Swaps are paused on this lp")]
+  SwapsPaused,
+  #[msg("This lp is withdraw only, swap and add liquidity are not allowed")]
+  WithdrawOnly,
+  #[msg("Lp still has share, liquidity or unclaimed fee, cannot be closed")]
+  PoolNotEmpty,
+  #[msg("Token accounts do not match the lp token pair, base and quote must be different mints")]
+  InvalidTokenPair,
+  #[msg("SOL can only be wrapped for an lp with native SOL as base or quote token")]
+  InvalidWrapSol,
+  #[msg("Exact out swap cannot pay with or receive a token that has a transfer fee, use swap instead")]
+  TransferFeeNotSupported,
+  #[msg("Only admin, the approved pool creator or the quote mint authority can init a lp")]
+  PoolCreatorNotAllowed,
+  #[msg("Only the program upgrade authority can init the program config")]
+  InvalidUpgradeAuthority,
+  #[msg("Treasury does not hold that much protocol fee")]
+  InsufficientTreasuryAmount,
+  #[msg("User token account of this side of the pair is required, it can only be left out for the SOL side when wrap_sol is set")]
+  MissingUserTokenAccount,
+}
+
+/// pool_math is free of Anchor, map its errors to the ones clients already know
+impl From<MathError> for LpBaseError {
+  fn from(e: MathError) -> Self {
+    match e {
+      MathError::InvalidRate => LpBaseError::InvalidRate,
+      MathError::InvalidFee => LpBaseError::InvalidFee,
+      MathError::InvalidAmount => LpBaseError::InvalidAmount,
+      MathError::InvalidSwapAmount => LpBaseError::InvalidSwapAmount,
+      MathError::InvalidSwapToken => LpBaseError::InvalidSwapToken,
+      MathError::InsufficientBaseAmount => LpBaseError::InsufficientBaseAmount,
+      MathError::InsufficientQuoteAmount => LpBaseError::InsufficientQuoteAmount,
+      MathError::LargeSwapAmount => LpBaseError::LargeSwapAmount,
+    }
+  }
 }
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use crate::state::LpStatus;
+
+///
+/// Events of FixedRateLP and ConstantProductLP, so indexers don't have to parse msg! logs
+///
+/// Amounts are what actually moved, reserves (amount_base, amount_quote) are the lp state after the action
+///
+
+#[event]
+pub struct PoolInitialized {
+  pub lp: Pubkey,
+  pub authority: Pubkey,
+  pub token_base: Pubkey,
+  pub token_quote: Pubkey,
+  pub pool_index: u16,
+  pub rate: u32,
+  pub fee_bps: u16,
+}
+
+/// Lp authority changed the status, rate or fee, all of them are the lp state after the change
+#[event]
+pub struct PoolUpdated {
+  pub lp: Pubkey,
+  pub authority: Pubkey,
+  pub status: LpStatus,
+  pub rate: u32,
+  pub fee_bps: u16,
+}
+
+#[event]
+pub struct PoolClosed {
+  pub lp: Pubkey,
+  pub authority: Pubkey,
+}
+
+#[event]
+pub struct LiquidityAdded {
+  pub lp: Pubkey,
+  pub user: Pubkey,
+  pub token_base: Pubkey,
+  pub token_quote: Pubkey,
+  /// received by the lp, Token-2022 transfer fee excluded
+  pub base_amount: u64,
+  pub quote_amount: u64,
+  pub share: u64,
+  pub amount_base: u64,
+  pub amount_quote: u64,
+}
+
+#[event]
+pub struct LiquidityWithdrawn {
+  pub lp: Pubkey,
+  pub user: Pubkey,
+  pub token_base: Pubkey,
+  pub token_quote: Pubkey,
+  pub base_amount: u64,
+  pub quote_amount: u64,
+  pub share: u64,
+  pub amount_base: u64,
+  pub amount_quote: u64,
+}
+
+#[event]
+pub struct Swapped {
+  pub lp: Pubkey,
+  pub user: Pubkey,
+  pub from_token: Pubkey,
+  pub to_token: Pubkey,
+  pub from_amount: u64,
+  /// sent to user, fee excluded
+  pub to_amount: u64,
+  /// total swap fee of to_token, see FeeCollected for the split
+  pub fee: u64,
+  pub amount_base: u64,
+  pub amount_quote: u64,
+}
+
+/// Swap fee split between liquidity providers and the treasury
+#[event]
+pub struct FeeCollected {
+  pub lp: Pubkey,
+  pub token: Pubkey,
+  pub lp_fee: u64,
+  pub protocol_fee: u64,
+}
+
+#[event]
+pub struct FeeClaimed {
+  pub lp: Pubkey,
+  pub user: Pubkey,
+  pub base_fee: u64,
+  pub quote_fee: u64,
+}
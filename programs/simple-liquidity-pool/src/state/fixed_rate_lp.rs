@@ -1,21 +1,20 @@
 use anchor_lang::prelude::*;
-use crate::state::{errors::*, SwapDir};
+use pool_math::FixedRatePool;
+use crate::state::{errors::*, SwapDir, FeeSharingLP};
 
 ///
-/// this LP is for <Sol, SplToken>
-/// base token is hard coded to SOL
+/// this LP is for any <SplToken, SplToken> pair, such as <Sol, SplToken> or <USDC, SplToken>
 ///
-/// If we need to swap <Sol/SplToken, Sol/SplToken>,
-/// We need to use WRAPPED SOL,
-///
-/// which is not a requirement of this test
-/// So I skip it for now
-///
-/// Base: SOL
-/// Quote: SPL token
+/// Base: any mint, native SOL is held as wrapped SOL like any other token
+/// Quote: any other mint
+/// Each side can be an SPL token or a Token-2022 mint, amount_* only count what the lp actually received,
+/// so transfer fee withheld by Token-2022 never inflates the reserves
 ///
 /// Base,Quote is a term in trading that represents the BASE/QUOTE trading pair
 ///
+/// PDA seeds contain both mints in canonical order (see sorted_mints) and the pool_index,
+/// so <A, B> and <B, A> are the same lp, while another pool_index is another lp of the same pair
+///
 #[account]
 #[derive(Default)]
 pub struct FixedRateLP {
@@ -24,32 +23,63 @@ pub struct FixedRateLP {
   /// max rate = 2^(32-RATE_DECIMAL)
   pub rate: u32,            // 4
 
-  // NOTE: base token is hardcoded to be native SOL
+  /// Swap fee in basis point, deducted on to_amount: 30 = 0.3%
+  /// max = LP_MAX_FEE_BPS
+  pub fee_bps: u16,         // 2
+
+  /// Who can manage this lp, such as update the rate
+  pub authority: Pubkey,    // 32
+
+  /// Set by authority, to wind down the lp safely
+  pub status: LpStatus,     // 1
+
   pub token_base: Pubkey,   // 32
   pub token_quote: Pubkey,  // 32
+  /// Chosen by the creator, part of every lp PDA seeds,
+  /// so lps with different rate or fee can coexist for the same pair
+  pub pool_index: u16,      // 2
 
   // pub liquidity: Pubkey,    // 32
   // pub fee: Pubkey,          // 32
   // pub liquidity_quote_ata: Pubkey, // 32
   // pub fee_quote_ata: Pubkey, // 32
 
-  // NOTE: At this time: we read balance inside amount_base_ATA as the single source of truth
-  // For better performance, need another complex implementation, such as cache the balance here
-  // pub amount_base: u128,    // 16: current base token amount in this pool
-  // pub amount_quote: u128,   // 16
+  // Usable reserves, cached here instead of reading lp_liquidity balances:
+  // rent-exempt lamports of lp_liquidity and tokens sent directly to the pool are never counted
+  pub amount_base: u64,     // 8: current base token amount in this pool
+  pub amount_quote: u64,    // 8
 
-  // profit tracking for all liquidity provider: Ignore this feature
+  // profit tracking for all liquidity provider: see FeeSharingLP
+  pub fee_base_per_share: u128,   // 16
+  pub fee_quote_per_share: u128,  // 16
+  pub fee_base_unclaimed: u64,    // 8: settled in LiquidityProvider, not claimed yet
+  pub fee_quote_unclaimed: u64,   // 8
 
   // misc
   pub bump: u8,                 // 1
   pub liquidity_bump: u8,       // 1
   pub fee_bump: u8,             // 1
+  pub share_mint_bump: u8,      // 1
 }
 
 
+///
+/// What an lp allows, withdraw_liquidity and claim_fee are always allowed
+///
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LpStatus {
+  /// swap, add_liquidity
+  #[default]
+  Active,
+  /// add_liquidity only
+  SwapsPaused,
+  /// neither swap nor add_liquidity
+  WithdrawOnly,
+}
+
 // pub enum LpType {
 //   FixedRate, // 1A always = nB, n is fixed
-//   ConstantProduct, // AMM
+//   ConstantProduct, // AMM: see ConstantProductLP
 // }
 
 
@@ -64,65 +94,223 @@ pub const LP_LIQUIDITY_PREFIX: &[u8] = b"FixedRateLP_liquid_";
 #[constant]
 pub const LP_FEE_SEED_PREFIX: &[u8] = b"FixedRateLP_fee_";
 #[constant]
+pub const LP_SHARE_MINT_PREFIX: &[u8] = b"FixedRateLP_share_";
+/// Temporary wSOL account of a user, see instructions/wrap_sol.rs
+#[constant]
+pub const LP_WSOL_TEMP_SEED_PREFIX: &[u8] = b"FixedRateLP_wsol_";
+#[constant]
 pub const LP_RATE_DECIMAL: u8 = 3;
+/// Decimal of ConstantProductLP share
+/// FixedRateLP share is valued in base token, so it has the same decimal as token_base instead
+#[constant]
+pub const LP_SHARE_DECIMAL: u8 = 9;
 
 /// Swap fee will be deducted directly on to_amount, not from_amount
+/// fee = to_amount * fee_bps / LP_FEE_BPS_DENOMINATOR
+#[constant]
+pub const LP_FEE_BPS_DENOMINATOR: u16 = 10_000;
 #[constant]
-pub const LP_SWAP_FEE_PERMIL: u8 = 50; // 50/1000 = 5.0%
+pub const LP_MAX_FEE_BPS: u16 = 1_000; // 1000/10000 = 10%
+
+// #[constant] needs literals for the Idl, the math itself lives in pool_math
+const _: () = assert!(LP_RATE_DECIMAL == pool_math::RATE_DECIMAL);
+const _: () = assert!(LP_FEE_BPS_DENOMINATOR == pool_math::FEE_BPS_DENOMINATOR);
+const _: () = assert!(LP_MAX_FEE_BPS == pool_math::MAX_FEE_BPS);
 
 impl FixedRateLP {
   // pub const SEED_PREFIX: &'static [u8] = b"FixedRateLP_";
-  pub const MAXIMUM_SIZE: usize = 4 + 32 + 32 + 1 + 1 + 1;
+  pub const MAXIMUM_SIZE: usize = 4 + 2 + 32 + 1 + 32 + 32 + 2 + 8 + 8 + 16 + 16 + 8 + 8 + 1 + 1 + 1 + 1;
+  /// lp_liquidity and lp_fee are data-less PDAs, only used to own the lp token accounts
+  pub const SOL_HOLDER_SIZE: usize = 8 + 8;
 
 
+  ///
+  /// Mints of a pair sorted by key, used in the seeds of every lp PDA
+  /// so <A, B> and <B, A> resolve to the same lp
+  ///
+  pub fn sorted_mints(token_a: Pubkey, token_b: Pubkey) -> (Pubkey, Pubkey) {
+    if token_a <= token_b {
+      (token_a, token_b)
+    } else {
+      (token_b, token_a)
+    }
+  }
+
+  /// Mints of this lp in seeds order
+  pub fn seed_mints(&self) -> (Pubkey, Pubkey) {
+    FixedRateLP::sorted_mints(self.token_base, self.token_quote)
+  }
+
   pub fn get_swap_dir(&self, from_token: Pubkey, to_token: Pubkey) -> Option<SwapDir> {
-    let mut swap_dir: Option<SwapDir> = None;
-    // if from_token is base token and to_token is quote token then swap_dir=BaseToQuote
-    if from_token == self.token_base && to_token == self.token_quote {
-      swap_dir = Option::from(SwapDir::BaseToQuote)
-    } else if from_token == self.token_quote && to_token == self.token_base {
-      swap_dir = Option::from(SwapDir::QuoteToBase)
+    pool_math::get_swap_dir(&self.token_base, &self.token_quote, &from_token, &to_token).map(SwapDir::from)
+  }
+
+  /// Rate, fee and reserves of this lp, as pool_math sees them
+  pub fn math(&self) -> FixedRatePool {
+    FixedRatePool {
+      rate: self.rate,
+      fee_bps: self.fee_bps,
+      amount_base: self.amount_base,
+      amount_quote: self.amount_quote,
     }
+  }
+}
 
-    swap_dir
+impl FeeSharingLP for FixedRateLP {
+  fn fee_per_share(&self) -> (u128, u128) {
+    (self.fee_base_per_share, self.fee_quote_per_share)
   }
+
+  fn fee_per_share_mut(&mut self) -> (&mut u128, &mut u128) {
+    (&mut self.fee_base_per_share, &mut self.fee_quote_per_share)
+  }
+
+  fn fee_unclaimed(&self) -> (u64, u64) {
+    (self.fee_base_unclaimed, self.fee_quote_unclaimed)
+  }
+
+  fn fee_unclaimed_mut(&mut self) -> (&mut u64, &mut u64) {
+    (&mut self.fee_base_unclaimed, &mut self.fee_quote_unclaimed)
+  }
+}
+
+/// What FixedRateLP::init needs: who creates the lp, its pair, pricing and the PDA bumps
+pub struct LpInitParams {
+  pub authority: Pubkey,
+  pub token_base: Pubkey,
+  pub token_quote: Pubkey,
+  pub pool_index: u16,
+  pub fixed_rate: u32,
+  pub fee_bps: u16,
+  pub bump: u8,
+  pub liquidity_bump: u8,
+  pub fee_bump: u8,
+  pub share_mint_bump: u8,
 }
 
 // impl LP for FixedRateLP {
 impl FixedRateLP {
-  pub fn init(
-    &mut self,
-    token_base: Pubkey, token_quote: Pubkey,
-    fixed_rate: u32,
-    bump: u8, liquidity_bump: u8, fee_bump: u8,
-  ) -> Result<()> {
-    require_gt!(fixed_rate, 0, LpBaseError::InvalidRate);
-    require!(fixed_rate <= 2_u32.pow(32 - LP_RATE_DECIMAL as u32), LpBaseError::InvalidRate);
+  pub fn init(&mut self, params: LpInitParams) -> Result<()> {
+    FixedRateLP::validate_rate(params.fixed_rate)?;
+    FixedRateLP::validate_fee(params.fee_bps)?;
+
+    self.rate = params.fixed_rate;
+    self.fee_bps = params.fee_bps;
+    self.authority = params.authority;
+    self.status = LpStatus::Active;
+    self.token_base = params.token_base;
+    self.token_quote = params.token_quote;
+    self.pool_index = params.pool_index;
+    self.amount_base = 0;
+    self.amount_quote = 0;
+    self.bump = params.bump;
+    self.liquidity_bump = params.liquidity_bump;
+    self.fee_bump = params.fee_bump;
+    self.share_mint_bump = params.share_mint_bump;
+
+    Ok(())
+  }
+
+  /// Only authority can call this, validated by the instruction accounts
+  pub fn update_rate(&mut self, fixed_rate: u32) -> Result<()> {
+    FixedRateLP::validate_rate(fixed_rate)?;
 
     self.rate = fixed_rate;
-    self.token_base = token_base;
-    self.token_quote = token_quote;
-    self.bump = bump;
-    self.liquidity_bump = liquidity_bump;
-    self.fee_bump = fee_bump;
 
     Ok(())
   }
 
-  /// No logic yet because this lp is simple, but let it here to validate.
-  /// Or just remove this fn is oke
-  pub fn add_liquidity(&mut self, token_base_amount: u64, token_quote_amount: u64) -> Result<()> {
-    require_gte!(token_base_amount, 0, LpBaseError::InvalidAmount);
-    require_gte!(token_quote_amount, 0, LpBaseError::InvalidAmount);
+  /// Only authority can call this, validated by the instruction accounts
+  pub fn update_fee(&mut self, fee_bps: u16) -> Result<()> {
+    FixedRateLP::validate_fee(fee_bps)?;
+
+    self.fee_bps = fee_bps;
+
+    Ok(())
+  }
+
+  /// Only authority can call this, validated by the instruction accounts
+  pub fn set_status(&mut self, status: LpStatus) {
+    self.status = status;
+  }
+
+  /// swap runs it in FixedRateLP::swap, quote_swap calls it directly
+  pub fn require_can_swap(&self) -> Result<()> {
+    match self.status {
+      LpStatus::Active => Ok(()),
+      LpStatus::SwapsPaused => err!(LpBaseError::SwapsPaused),
+      LpStatus::WithdrawOnly => err!(LpBaseError::WithdrawOnly),
+    }
+  }
+
+  fn require_can_add_liquidity(&self) -> Result<()> {
+    match self.status {
+      LpStatus::Active | LpStatus::SwapsPaused => Ok(()),
+      LpStatus::WithdrawOnly => err!(LpBaseError::WithdrawOnly),
+    }
+  }
+
+  fn validate_fee(fee_bps: u16) -> Result<()> {
+    FixedRatePool::validate_fee(fee_bps).map_err(LpBaseError::from)?;
 
-    // TODO: Validate current amount over upper range of u64
+    Ok(())
+  }
 
-    // self.amount_base += token_base_amount;
-    // self.amount_quote += token_quote_amount;
+  fn validate_rate(fixed_rate: u32) -> Result<()> {
+    FixedRatePool::validate_rate(fixed_rate).map_err(LpBaseError::from)?;
 
     Ok(())
   }
 
+  ///
+  /// Return the amount of share to mint for this deposit
+  ///
+  /// Share is proportional to the value added, valued in base token at the pool `rate`:
+  ///   first deposit: share = value
+  ///   next deposits: share = value * share_supply / pool_value
+  /// Rounded down, so the pool never gives more share than deserved, see FixedRatePool::deposit_share
+  ///
+  pub fn add_liquidity(
+    &mut self,
+    token_base_amount: u64,
+    token_quote_amount: u64,
+    share_supply: u64,
+  ) -> Result<u64> {
+    self.require_can_add_liquidity()?;
+
+    let share = self.math()
+      .deposit_share(token_base_amount, token_quote_amount, share_supply)
+      .map_err(LpBaseError::from)?;
+
+    self.amount_base = self.amount_base.checked_add(token_base_amount).ok_or(LpBaseError::InvalidAmount)?;
+    self.amount_quote = self.amount_quote.checked_add(token_quote_amount).ok_or(LpBaseError::InvalidAmount)?;
+
+    Ok(share)
+  }
+
+  ///
+  /// Return (base_amount, quote_amount) to send back for the burned share
+  ///
+  /// User get his slice of the current liquidity, rounded down
+  ///
+  pub fn withdraw_liquidity(
+    &mut self,
+    share: u64,
+    share_supply: u64,
+  ) -> Result<(u64, u64)> {
+    require_gt!(share, 0, LpBaseError::InvalidAmount);
+    require!(share <= share_supply, LpBaseError::ExceedProvidedLiquidity);
+
+    // both are <= current reserves so they always fit in u64
+    let base_amount = (self.amount_base as u128 * share as u128 / share_supply as u128) as u64;
+    let quote_amount = (self.amount_quote as u128 * share as u128 / share_supply as u128) as u64;
+
+    self.amount_base -= base_amount;
+    self.amount_quote -= quote_amount;
+
+    Ok((base_amount, quote_amount))
+  }
+
   ///
   /// Return (
   ///   from_amount: base token change amount,
@@ -130,49 +318,71 @@ impl FixedRateLP {
   ///   fee: the fee deducted on to_amount,
   /// )
   ///
+  /// Integer math only, rate has LP_RATE_DECIMAL decimals:
+  ///   BaseToQuote: to_amount = from_amount * rate / 10^LP_RATE_DECIMAL
+  ///   QuoteToBase: to_amount = from_amount * 10^LP_RATE_DECIMAL / rate
+  /// to_amount is rounded down and fee is rounded up, so rounding always favours the pool
+  ///
   pub fn preview_swap(
-    &mut self,
+    &self,
     from_token: Pubkey,
     to_token: Pubkey,
     from_amount: u64,
-    current_base_liquidity: u64,
-    current_quote_liquidity: u64,
   ) -> Result<(SwapDir, u64, u64, u64)> {
-    require_gt!(from_amount, 0, LpBaseError::InvalidSwapAmount);
-
     let swap_direction = self.get_swap_dir(from_token, to_token);
     require!(swap_direction.is_some(), LpBaseError::InvalidSwapToken);
 
     let swap_dir = swap_direction.unwrap();
-    // let (current_base_liquidity, current_quote_liquidity) = FixedRateLP::get_current_liquidity();
 
     let verbose = false;
-    if verbose { msg!("[preview_swap] current base, quote liquidity: {}, {}", current_base_liquidity, current_quote_liquidity); }
-
-    let rate: f64 = self.rate as f64 / 1000_f64;
-    if verbose { msg!("[preview_swap] rate: {}", rate); }
-
-    let to_amount = match swap_dir {
-      SwapDir::BaseToQuote => {
-        let to_amount = (from_amount as f64 * rate) as u64;
-        require!(to_amount <= current_quote_liquidity, LpBaseError::InsufficientQuoteAmount);
-        to_amount
-      },
-      SwapDir::QuoteToBase => {
-        let to_amount = (from_amount as f64 / rate) as u64;
-        require!(to_amount <= current_base_liquidity, LpBaseError::InsufficientBaseAmount);
-        to_amount
-      }
-    };
-    if verbose { msg!("[preview_swap] to_amount: {}", to_amount); }
+    if verbose { msg!("[preview_swap] current base, quote liquidity: {}, {}", self.amount_base, self.amount_quote); }
+
+    let preview = self.math().preview_swap(swap_dir.into(), from_amount).map_err(LpBaseError::from)?;
+    if verbose { msg!("[preview_swap] to_amount: {}", preview.to_amount_without_fee); }
+
+    Ok((swap_dir, preview.from_amount, preview.to_amount_without_fee, preview.fee))
+  }
+
+  ///
+  /// Exact output version of preview_swap: user receive exactly `to_amount` after fee
+  ///
+  /// Return (
+  ///   from_amount: from token the user must pay,
+  ///   to_amount_without_fee: to token leaving the liquidity,
+  ///   fee: the fee deducted on to_amount_without_fee,
+  /// )
+  ///
+  /// Both the gross output and the input are rounded up, in favour of the pool
+  ///
+  pub fn preview_swap_exact_out(
+    &self,
+    from_token: Pubkey,
+    to_token: Pubkey,
+    to_amount: u64,
+  ) -> Result<(SwapDir, u64, u64, u64)> {
+    let swap_direction = self.get_swap_dir(from_token, to_token);
+    require!(swap_direction.is_some(), LpBaseError::InvalidSwapToken);
+    let swap_dir = swap_direction.unwrap();
 
-    let fee = FixedRateLP::get_swap_fee(to_amount);
+    let preview = self.math().preview_swap_exact_out(swap_dir.into(), to_amount).map_err(LpBaseError::from)?;
 
-    Ok((swap_dir, from_amount, to_amount, fee))
+    Ok((swap_dir, preview.from_amount, preview.to_amount_without_fee, preview.fee))
   }
 
-  fn get_swap_fee(to_amount: u64) -> u64 {
-    return to_amount * (LP_SWAP_FEE_PERMIL as u64) / 1000;
+  /// Apply a swap previewed by preview_swap or preview_swap_exact_out to the reserves
+  pub fn swap(&mut self, swap_dir: &SwapDir, from_amount: u64, to_amount_without_fee: u64) -> Result<()> {
+    self.require_can_swap()?;
+
+    let (from_reserve, to_reserve) = match swap_dir {
+      SwapDir::BaseToQuote => (&mut self.amount_base, &mut self.amount_quote),
+      SwapDir::QuoteToBase => (&mut self.amount_quote, &mut self.amount_base),
+    };
+
+    // fee leaves the reserve too, it goes to lp fee account
+    *from_reserve = from_reserve.checked_add(from_amount).ok_or(LpBaseError::LargeSwapAmount)?;
+    *to_reserve = to_reserve.checked_sub(to_amount_without_fee).ok_or(LpBaseError::LargeSwapAmount)?;
+
+    Ok(())
   }
 
   // /// @return (current_base_amount_available, current_quote_amount_available)
@@ -182,11 +392,152 @@ impl FixedRateLP {
   // }
 }
 
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const RATE: u32 = 10_000; // 1 base = 10 quote
+  const FEE_BPS: u16 = 30;
+
+  fn init_params(token_base: Pubkey, token_quote: Pubkey) -> LpInitParams {
+    LpInitParams {
+      authority: Pubkey::new_unique(),
+      token_base,
+      token_quote,
+      pool_index: 0,
+      fixed_rate: RATE,
+      fee_bps: FEE_BPS,
+      bump: 255,
+      liquidity_bump: 254,
+      fee_bump: 253,
+      share_mint_bump: 252,
+    }
+  }
+
+  fn new_lp() -> (FixedRateLP, Pubkey, Pubkey) {
+    let (token_base, token_quote) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut lp = FixedRateLP::default();
+    lp.init(init_params(token_base, token_quote)).unwrap();
 
-// #[cfg(test)]
-// mod tests {
-//   #[test]
-//   fn exploration() {
-//     assert_eq!(2 + 2, 4);
-//   }
-// }
+    (lp, token_base, token_quote)
+  }
+
+  fn lp_error(e: LpBaseError) -> Error {
+    e.into()
+  }
+
+  #[test]
+  fn init_validates_rate_and_fee() {
+    let mut lp = FixedRateLP::default();
+    let params = init_params(Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert_eq!(lp.init(LpInitParams { fixed_rate: 0, ..params }).unwrap_err(), lp_error(LpBaseError::InvalidRate));
+    assert_eq!(lp.init(LpInitParams { fee_bps: LP_MAX_FEE_BPS + 1, ..params }).unwrap_err(), lp_error(LpBaseError::InvalidFee));
+
+    lp.init(LpInitParams { pool_index: 7, bump: 1, liquidity_bump: 2, fee_bump: 3, share_mint_bump: 4, ..params }).unwrap();
+    assert_eq!((lp.rate, lp.fee_bps, lp.pool_index), (RATE, FEE_BPS, 7));
+    assert_eq!(lp.status, LpStatus::Active);
+    assert_eq!((lp.bump, lp.liquidity_bump, lp.fee_bump, lp.share_mint_bump), (1, 2, 3, 4));
+  }
+
+  #[test]
+  fn sorted_mints_is_canonical() {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    assert_eq!(FixedRateLP::sorted_mints(a, b), FixedRateLP::sorted_mints(b, a));
+  }
+
+  #[test]
+  fn add_liquidity_share_is_valued_in_base() {
+    let (mut lp, _, _) = new_lp();
+
+    // first deposit: share = base + quote / 10
+    assert_eq!(lp.add_liquidity(1_000, 10_000, 0).unwrap(), 2_000);
+    assert_eq!((lp.amount_base, lp.amount_quote), (1_000, 10_000));
+
+    // next deposit: proportional to the pool value
+    assert_eq!(lp.add_liquidity(0, 5_000, 2_000).unwrap(), 500);
+    assert_eq!((lp.amount_base, lp.amount_quote), (1_000, 15_000));
+
+    assert_eq!(lp.add_liquidity(0, 0, 2_500).unwrap_err(), lp_error(LpBaseError::InvalidAmount));
+  }
+
+  #[test]
+  fn withdraw_liquidity_is_proportional() {
+    let (mut lp, _, _) = new_lp();
+    lp.add_liquidity(1_000, 10_000, 0).unwrap();
+
+    assert_eq!(lp.withdraw_liquidity(500, 2_000).unwrap(), (250, 2_500));
+    assert_eq!((lp.amount_base, lp.amount_quote), (750, 7_500));
+
+    assert_eq!(lp.withdraw_liquidity(0, 1_500).unwrap_err(), lp_error(LpBaseError::InvalidAmount));
+    assert_eq!(lp.withdraw_liquidity(1_501, 1_500).unwrap_err(), lp_error(LpBaseError::ExceedProvidedLiquidity));
+  }
+
+  #[test]
+  fn swap_moves_reserves_both_directions() {
+    let (mut lp, base, quote) = new_lp();
+    lp.add_liquidity(1_000_000, 10_000_000, 0).unwrap();
+
+    let (swap_dir, from_amount, to_amount, fee) = lp.preview_swap(base, quote, 1_000).unwrap();
+    assert_eq!((swap_dir, from_amount, to_amount, fee), (SwapDir::BaseToQuote, 1_000, 10_000, 30));
+    lp.swap(&swap_dir, from_amount, to_amount).unwrap();
+    assert_eq!((lp.amount_base, lp.amount_quote), (1_001_000, 9_990_000));
+
+    let (swap_dir, from_amount, to_amount, fee) = lp.preview_swap(quote, base, 10_000).unwrap();
+    assert_eq!((swap_dir, from_amount, to_amount, fee), (SwapDir::QuoteToBase, 10_000, 1_000, 3));
+    lp.swap(&swap_dir, from_amount, to_amount).unwrap();
+    assert_eq!((lp.amount_base, lp.amount_quote), (1_000_000, 10_000_000));
+  }
+
+  #[test]
+  fn preview_swap_errors() {
+    let (mut lp, base, quote) = new_lp();
+    lp.add_liquidity(1_000, 10_000, 0).unwrap();
+
+    assert_eq!(lp.preview_swap(base, base, 1).unwrap_err(), lp_error(LpBaseError::InvalidSwapToken));
+    assert_eq!(lp.preview_swap(Pubkey::new_unique(), quote, 1).unwrap_err(), lp_error(LpBaseError::InvalidSwapToken));
+    assert_eq!(lp.preview_swap(base, quote, 0).unwrap_err(), lp_error(LpBaseError::InvalidSwapAmount));
+    assert_eq!(lp.preview_swap(base, quote, 1_001).unwrap_err(), lp_error(LpBaseError::InsufficientQuoteAmount));
+    assert_eq!(lp.preview_swap(quote, base, 10_010).unwrap_err(), lp_error(LpBaseError::InsufficientBaseAmount));
+    assert_eq!(lp.preview_swap_exact_out(base, quote, 10_000).unwrap_err(), lp_error(LpBaseError::InsufficientQuoteAmount));
+  }
+
+  #[test]
+  fn preview_swap_exact_out_pays_exact_output() {
+    let (mut lp, base, quote) = new_lp();
+    lp.add_liquidity(1_000_000, 10_000_000, 0).unwrap();
+
+    let (swap_dir, from_amount, to_amount_without_fee, fee) = lp.preview_swap_exact_out(base, quote, 9_970).unwrap();
+    assert_eq!(swap_dir, SwapDir::BaseToQuote);
+    assert_eq!(to_amount_without_fee - fee, 9_970);
+    assert_eq!((from_amount, to_amount_without_fee), (1_000, 10_000));
+  }
+
+  #[test]
+  fn status_gates_swap_and_add_liquidity() {
+    let (mut lp, _, _) = new_lp();
+    lp.add_liquidity(1_000, 10_000, 0).unwrap();
+
+    lp.set_status(LpStatus::SwapsPaused);
+    assert_eq!(lp.swap(&SwapDir::BaseToQuote, 1, 10).unwrap_err(), lp_error(LpBaseError::SwapsPaused));
+    assert!(lp.add_liquidity(1, 0, 2_000).is_ok());
+
+    lp.set_status(LpStatus::WithdrawOnly);
+    assert_eq!(lp.swap(&SwapDir::BaseToQuote, 1, 10).unwrap_err(), lp_error(LpBaseError::WithdrawOnly));
+    assert_eq!(lp.add_liquidity(1, 0, 2_001).unwrap_err(), lp_error(LpBaseError::WithdrawOnly));
+    assert!(lp.withdraw_liquidity(1, 2_001).is_ok());
+  }
+
+  #[test]
+  fn update_rate_and_fee_are_validated() {
+    let (mut lp, _, _) = new_lp();
+
+    assert_eq!(lp.update_rate(0).unwrap_err(), lp_error(LpBaseError::InvalidRate));
+    assert_eq!(lp.update_fee(LP_MAX_FEE_BPS + 1).unwrap_err(), lp_error(LpBaseError::InvalidFee));
+    assert_eq!((lp.rate, lp.fee_bps), (RATE, FEE_BPS));
+
+    lp.update_rate(20_000).unwrap();
+    lp.update_fee(0).unwrap();
+    assert_eq!((lp.rate, lp.fee_bps), (20_000, 0));
+  }
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use crate::state::{errors::*, FeeSharingLP, LP_FEE_PER_SHARE_PRECISION};
+
+///
+/// Share of one LP owned by one wallet
+/// Used by both FixedRateLP and ConstantProductLP
+///
+/// The share itself is minted as SPL token (LP_SHARE_MINT_PREFIX mint),
+/// this account keeps track of what this wallet got from add_liquidity,
+/// so withdraw_liquidity can only burn the share that was minted to him
+///
+/// Share token accounts are kept frozen by the lp, see instructions/share_token.rs,
+/// so this share always matches the wallet's token balance and the mint supply is the sum of all of them
+///
+/// Fee tracking:
+///   fee earned = (share * lp.fee_per_share() - fee_*_debt) / LP_FEE_PER_SHARE_PRECISION
+/// debt is reset every time the share changes, so claiming is O(1)
+/// no matter how many swaps happened
+///
+/// debt is kept scaled by LP_FEE_PER_SHARE_PRECISION and only the fee earned is rounded down,
+/// so all providers together can never be owed more than the fee paid into lp_fee
+///
+#[account]
+#[derive(Default)]
+pub struct LiquidityProvider {
+  pub lp: Pubkey,               // 32
+  pub owner: Pubkey,            // 32
+
+  pub share: u64,               // 8
+
+  // fee tracking
+  pub fee_base_debt: u128,      // 16: scaled by LP_FEE_PER_SHARE_PRECISION
+  pub fee_quote_debt: u128,     // 16
+  pub fee_base_pending: u64,    // 8: earned but not claimed yet
+  pub fee_quote_pending: u64,   // 8
+
+  // misc
+  pub bump: u8,                 // 1
+}
+
+#[constant]
+pub const LP_PROVIDER_SEED_PREFIX: &[u8] = b"FixedRateLP_provider_";
+
+impl LiquidityProvider {
+  pub const MAXIMUM_SIZE: usize = 32 + 32 + 8 + 16 + 16 + 8 + 8 + 1;
+
+  pub fn deposit(&mut self, lp: &mut impl FeeSharingLP, lp_key: Pubkey, owner: Pubkey, bump: u8, share: u64) -> Result<()> {
+    // init_if_needed: first deposit will fill the identity fields
+    self.lp = lp_key;
+    self.owner = owner;
+    self.bump = bump;
+
+    // fee earned with the old share must be settled before the share changes
+    self.settle_fee(lp)?;
+    self.share = self.share.checked_add(share).ok_or(LpBaseError::InvalidAmount)?;
+    self.reset_fee_debt(&*lp)
+  }
+
+  pub fn withdraw(&mut self, lp: &mut impl FeeSharingLP, share: u64) -> Result<()> {
+    require!(share <= self.share, LpBaseError::ExceedProvidedLiquidity);
+
+    self.settle_fee(lp)?;
+    self.share -= share;
+    self.reset_fee_debt(&*lp)
+  }
+
+  ///
+  /// Return (base_fee, quote_fee) this provider can claim now, and reset them to 0
+  ///
+  pub fn claim_fee(&mut self, lp: &mut impl FeeSharingLP) -> Result<(u64, u64)> {
+    self.settle_fee(lp)?;
+
+    let claimed = (self.fee_base_pending, self.fee_quote_pending);
+    self.fee_base_pending = 0;
+    self.fee_quote_pending = 0;
+
+    let (fee_base_unclaimed, fee_quote_unclaimed) = lp.fee_unclaimed_mut();
+    *fee_base_unclaimed = fee_base_unclaimed.saturating_sub(claimed.0);
+    *fee_quote_unclaimed = fee_quote_unclaimed.saturating_sub(claimed.1);
+
+    Ok(claimed)
+  }
+
+  /// Move fee earned since last settlement into pending, lp keeps the total in fee_unclaimed
+  fn settle_fee(&mut self, lp: &mut impl FeeSharingLP) -> Result<()> {
+    let (fee_base_per_share, fee_quote_per_share) = lp.fee_per_share();
+    let base_accrued = Self::accrued_fee(self.share, fee_base_per_share)?;
+    let quote_accrued = Self::accrued_fee(self.share, fee_quote_per_share)?;
+
+    let base_earned = Self::earned_fee(base_accrued, self.fee_base_debt)?;
+    let quote_earned = Self::earned_fee(quote_accrued, self.fee_quote_debt)?;
+
+    self.fee_base_pending = self.fee_base_pending.checked_add(base_earned).ok_or(LpBaseError::InvalidAmount)?;
+    self.fee_quote_pending = self.fee_quote_pending.checked_add(quote_earned).ok_or(LpBaseError::InvalidAmount)?;
+    // what was rounded away stays in the debt, it is earned with the next swaps
+    self.fee_base_debt += base_earned as u128 * LP_FEE_PER_SHARE_PRECISION;
+    self.fee_quote_debt += quote_earned as u128 * LP_FEE_PER_SHARE_PRECISION;
+
+    let (fee_base_unclaimed, fee_quote_unclaimed) = lp.fee_unclaimed_mut();
+    *fee_base_unclaimed = fee_base_unclaimed.checked_add(base_earned).ok_or(LpBaseError::InvalidAmount)?;
+    *fee_quote_unclaimed = fee_quote_unclaimed.checked_add(quote_earned).ok_or(LpBaseError::InvalidAmount)?;
+
+    Ok(())
+  }
+
+  fn reset_fee_debt(&mut self, lp: &impl FeeSharingLP) -> Result<()> {
+    let (fee_base_per_share, fee_quote_per_share) = lp.fee_per_share();
+    self.fee_base_debt = Self::accrued_fee(self.share, fee_base_per_share)?;
+    self.fee_quote_debt = Self::accrued_fee(self.share, fee_quote_per_share)?;
+
+    Ok(())
+  }
+
+  /// Scaled by LP_FEE_PER_SHARE_PRECISION, not rounded
+  fn accrued_fee(share: u64, fee_per_share: u128) -> Result<u128> {
+    let accrued = (share as u128)
+      .checked_mul(fee_per_share)
+      .ok_or(LpBaseError::InvalidAmount)?;
+
+    Ok(accrued)
+  }
+
+  /// Rounded down, in favour of the lp
+  fn earned_fee(accrued: u128, debt: u128) -> Result<u64> {
+    let earned = accrued.saturating_sub(debt) / LP_FEE_PER_SHARE_PRECISION;
+
+    u64::try_from(earned).map_err(|_| error!(LpBaseError::InvalidAmount))
+  }
+}
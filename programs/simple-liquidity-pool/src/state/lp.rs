@@ -1,4 +1,5 @@
-// use anchor_lang::prelude::*;
+use anchor_lang::prelude::*;
+use crate::state::errors::*;
 
 // pub trait LP {
 //   fn init(&mut self, token_base: Pubkey, token_quote: Pubkey, fixed_rate: u32) -> Result<()>;
@@ -11,7 +12,72 @@
 //   fn get_current_liquidity() -> (u128, u128);
 // }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum SwapDir {
   BaseToQuote,
   QuoteToBase,
 }
+
+impl From<pool_math::SwapDir> for SwapDir {
+  fn from(swap_dir: pool_math::SwapDir) -> Self {
+    match swap_dir {
+      pool_math::SwapDir::BaseToQuote => SwapDir::BaseToQuote,
+      pool_math::SwapDir::QuoteToBase => SwapDir::QuoteToBase,
+    }
+  }
+}
+
+impl From<SwapDir> for pool_math::SwapDir {
+  fn from(swap_dir: SwapDir) -> Self {
+    match swap_dir {
+      SwapDir::BaseToQuote => pool_math::SwapDir::BaseToQuote,
+      SwapDir::QuoteToBase => pool_math::SwapDir::QuoteToBase,
+    }
+  }
+}
+
+
+/// Scale of fee_base_per_share, fee_quote_per_share
+#[constant]
+pub const LP_FEE_PER_SHARE_PRECISION: u128 = 1_000_000_000_000;
+
+///
+/// Lp that shares its swap fee to share holders
+/// Each share holder keeps his own debt in LiquidityProvider
+///
+pub trait FeeSharingLP {
+  /// accumulated fee per 1 share, scaled by LP_FEE_PER_SHARE_PRECISION
+  /// @return (fee_base_per_share, fee_quote_per_share)
+  fn fee_per_share(&self) -> (u128, u128);
+  fn fee_per_share_mut(&mut self) -> (&mut u128, &mut u128);
+
+  /// fee settled to liquidity providers but not claimed yet
+  /// @return (fee_base_unclaimed, fee_quote_unclaimed)
+  fn fee_unclaimed(&self) -> (u64, u64);
+  fn fee_unclaimed_mut(&mut self) -> (&mut u64, &mut u64);
+
+  ///
+  /// Share the swap fee to all current share holders
+  /// If nobody hold the share, fee just stays in lp fee account
+  ///
+  fn accrue_fee(&mut self, swap_dir: &SwapDir, fee: u64, share_supply: u64) -> Result<()> {
+    if fee == 0 || share_supply == 0 {
+      return Ok(());
+    }
+
+    let fee_per_share = (fee as u128)
+      .checked_mul(LP_FEE_PER_SHARE_PRECISION)
+      .ok_or(LpBaseError::LargeSwapAmount)?
+      / share_supply as u128;
+
+    // fee is deducted on to_token
+    let (fee_base_per_share, fee_quote_per_share) = self.fee_per_share_mut();
+    let acc = match swap_dir {
+      SwapDir::BaseToQuote => fee_quote_per_share,
+      SwapDir::QuoteToBase => fee_base_per_share,
+    };
+    *acc = acc.checked_add(fee_per_share).ok_or(LpBaseError::LargeSwapAmount)?;
+
+    Ok(())
+  }
+}
@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use crate::state::{errors::*, LP_FEE_BPS_DENOMINATOR, LP_MAX_FEE_BPS};
+
+///
+/// Global config of this program, only one per program
+///
+/// Protocol takes a cut of every swap fee, the cut goes to the treasury:
+///   protocol_fee = swap_fee * protocol_fee_share_bps / LP_FEE_BPS_DENOMINATOR
+///   the rest is shared to liquidity providers
+///
+/// Treasury is a data-less PDA holding SOL, and the owner of the treasury quote ATAs
+/// Only admin can withdraw from it
+///
+/// Admin can pause the program in an incident:
+/// initialize, add_liquidity and swap are rejected, withdraw and claim still work so users can exit
+///
+/// Pool creation is permissioned by default, so nobody can squat a pair at a bad rate:
+/// only admin, the approved pool_creator or the quote mint authority can initialize a lp
+/// Admin can open it to everyone with permissionless_init
+///
+#[account]
+#[derive(Default)]
+pub struct ProgramConfig {
+  pub admin: Pubkey,                  // 32
+  /// Proposed by admin, must accept to become the admin. Pubkey::default() = none
+  pub pending_admin: Pubkey,          // 32
+  pub treasury: Pubkey,               // 32
+
+  pub paused: bool,                   // 1
+
+  /// 1667 = 1/6 of the swap fee goes to treasury
+  pub protocol_fee_share_bps: u16,    // 2
+  /// fee_bps of new lp if initialize does not specify one
+  pub default_fee_bps: u16,           // 2
+
+  /// Anyone can initialize a lp, see require_can_create_pool
+  pub permissionless_init: bool,      // 1
+  /// Approved by admin to initialize any lp. Pubkey::default() = none
+  pub pool_creator: Pubkey,           // 32
+
+  // misc
+  pub bump: u8,                       // 1
+  pub treasury_bump: u8,              // 1
+}
+
+#[constant]
+pub const CONFIG_SEED_PREFIX: &[u8] = b"ProgramConfig_";
+#[constant]
+pub const TREASURY_SEED_PREFIX: &[u8] = b"ProgramConfig_treasury_";
+#[constant]
+pub const MAX_PROTOCOL_FEE_SHARE_BPS: u16 = 5_000; // at most half of the swap fee
+
+impl ProgramConfig {
+  pub const MAXIMUM_SIZE: usize = 32 + 32 + 32 + 1 + 2 + 2 + 1 + 32 + 1 + 1;
+  /// treasury is a data-less PDA, only used to hold SOL
+  pub const SOL_HOLDER_SIZE: usize = 8 + 8;
+
+  pub fn init(
+    &mut self,
+    admin: Pubkey,
+    treasury: Pubkey,
+    protocol_fee_share_bps: u16,
+    default_fee_bps: u16,
+    bump: u8, treasury_bump: u8,
+  ) -> Result<()> {
+    self.admin = admin;
+    self.pending_admin = Pubkey::default();
+    self.treasury = treasury;
+    self.paused = false;
+    self.permissionless_init = false;
+    self.pool_creator = Pubkey::default();
+    self.bump = bump;
+    self.treasury_bump = treasury_bump;
+
+    self.update_fees(protocol_fee_share_bps, default_fee_bps)
+  }
+
+  /// Only admin can call this, validated by the instruction accounts
+  pub fn update_fees(&mut self, protocol_fee_share_bps: u16, default_fee_bps: u16) -> Result<()> {
+    require!(protocol_fee_share_bps <= MAX_PROTOCOL_FEE_SHARE_BPS, LpBaseError::InvalidProtocolFeeShare);
+    require!(default_fee_bps <= LP_MAX_FEE_BPS, LpBaseError::InvalidFee);
+
+    self.protocol_fee_share_bps = protocol_fee_share_bps;
+    self.default_fee_bps = default_fee_bps;
+
+    Ok(())
+  }
+
+  /// Only admin can call this, validated by the instruction accounts
+  pub fn set_paused(&mut self, paused: bool) {
+    self.paused = paused;
+  }
+
+  /// Only admin can call this, validated by the instruction accounts
+  /// pool_creator: Pubkey::default() to approve nobody
+  pub fn set_pool_creation(&mut self, permissionless_init: bool, pool_creator: Pubkey) {
+    self.permissionless_init = permissionless_init;
+    self.pool_creator = pool_creator;
+  }
+
+  ///
+  /// Who can initialize a lp:
+  ///   anyone in permissionless mode
+  ///   otherwise admin, the approved pool_creator, or the mint authority of the quote token
+  ///
+  pub fn require_can_create_pool(&self, creator: Pubkey, quote_mint_authority: Option<Pubkey>) -> Result<()> {
+    let allowed = self.permissionless_init
+      || creator == self.admin
+      || (self.pool_creator != Pubkey::default() && creator == self.pool_creator)
+      || quote_mint_authority == Some(creator);
+    require!(allowed, LpBaseError::PoolCreatorNotAllowed);
+
+    Ok(())
+  }
+
+  /// Only admin can call this, validated by the instruction accounts
+  /// Pubkey::default() cancels the current proposal
+  pub fn propose_admin(&mut self, new_admin: Pubkey) {
+    self.pending_admin = new_admin;
+  }
+
+  pub fn accept_admin(&mut self, signer: Pubkey) -> Result<()> {
+    require!(
+      self.pending_admin != Pubkey::default() && self.pending_admin == signer,
+      LpBaseError::InvalidPendingAdmin
+    );
+
+    self.admin = signer;
+    self.pending_admin = Pubkey::default();
+
+    Ok(())
+  }
+
+  ///
+  /// Return (lp_fee, protocol_fee) of a swap fee
+  /// protocol_fee is rounded down, so liquidity providers never lose to rounding
+  ///
+  pub fn split_swap_fee(&self, fee: u64) -> (u64, u64) {
+    let protocol_fee = (fee as u128 * self.protocol_fee_share_bps as u128 / LP_FEE_BPS_DENOMINATOR as u128) as u64;
+
+    (fee - protocol_fee, protocol_fee)
+  }
+}
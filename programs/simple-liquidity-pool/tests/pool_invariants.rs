@@ -0,0 +1,492 @@
+//!
+//! Property tests: random sequences of add / swap / withdraw / claim against the lp and provider state,
+//! with the token and lamport moves of the instructions replayed on a model of the accounts
+//!
+//! After every operation:
+//!   - reserves equal the vault balances and never go negative
+//!   - tokens and lamports are conserved: vaults + fee accounts + treasury + users == what was there at init
+//!   - value per share never decreases, rounding always favours the pool
+//!   - swap fee never exceeds the output, protocol fee never exceeds the swap fee
+//!   - lp fee accounts always hold what the providers can claim
+//!   - lamport PDAs stay rent-exempt
+//!
+//! A rejected operation reverts, like the transaction would
+//!
+#![allow(clippy::result_large_err)]
+
+use anchor_lang::{prelude::Pubkey, solana_program::rent::Rent};
+use anchor_spl::token::spl_token;
+use pool_math::FixedRatePool;
+use proptest::prelude::*;
+use simple_liquidity_pool::state::{
+  ConstantProductLP, CpLpInitParams, FeeSharingLP, FixedRateLP, LiquidityProvider, LpInitParams, ProgramConfig, SwapDir,
+  LP_MAX_FEE_BPS, MAX_PROTOCOL_FEE_SHARE_BPS,
+};
+
+/// Big enough for rounding and reserve exhaustion, small enough for the value per share check to fit in u128
+const MAX_AMOUNT: u64 = 1_000_000_000_000;
+/// 1 base = 1000 quote at most
+const MAX_RATE: u32 = 1_000_000;
+/// Wallets sharing one lp, each with its own LiquidityProvider
+const USERS: usize = 3;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+  AddLiquidity { user: usize, base: u64, quote: u64 },
+  Swap { base_to_quote: bool, amount: u64 },
+  SwapExactOut { base_to_quote: bool, amount: u64 },
+  WithdrawLiquidity { user: usize, share_bps: u16 },
+  ClaimFee { user: usize },
+}
+
+/// Balances of one mint across the accounts an lp touches
+#[derive(Clone, Copy, Debug, Default)]
+struct Balances {
+  liquidity: u64,
+  lp_fee: u64,
+  treasury: u64,
+  /// received - paid, all users together
+  user: i128,
+}
+
+impl Balances {
+  fn total(&self) -> i128 {
+    self.liquidity as i128 + self.lp_fee as i128 + self.treasury as i128 + self.user
+  }
+
+  fn pay_in(&mut self, amount: u64) {
+    self.liquidity = self.liquidity.checked_add(amount).expect("liquidity overflow");
+    self.user -= amount as i128;
+  }
+
+  /// What leaves the liquidity for a swap: to_amount_without_fee = user + lp fee + protocol fee
+  fn pay_out(&mut self, to_user: u64, lp_fee: u64, protocol_fee: u64) {
+    let total = to_user + lp_fee + protocol_fee;
+    self.liquidity = self.liquidity.checked_sub(total).expect("liquidity went negative");
+    self.lp_fee += lp_fee;
+    self.treasury += protocol_fee;
+    self.user += to_user as i128;
+  }
+
+  fn withdraw(&mut self, amount: u64) {
+    self.liquidity = self.liquidity.checked_sub(amount).expect("liquidity went negative");
+    self.user += amount as i128;
+  }
+
+  fn claim(&mut self, amount: u64) {
+    self.lp_fee = self.lp_fee.checked_sub(amount).expect("lp fee account went negative");
+    self.user += amount as i128;
+  }
+}
+
+fn config(protocol_fee_share_bps: u16) -> ProgramConfig {
+  ProgramConfig { protocol_fee_share_bps, ..ProgramConfig::default() }
+}
+
+fn providers() -> Vec<LiquidityProvider> {
+  (0..USERS)
+    .map(|_| LiquidityProvider { owner: Pubkey::new_unique(), ..LiquidityProvider::default() })
+    .collect()
+}
+
+///
+/// What all providers could claim right now, claimed on copies of the state
+/// @return (base_fee, quote_fee)
+///
+fn fee_owed<L: FeeSharingLP + Clone>(lp: &L, providers: &[LiquidityProvider]) -> (u64, u64) {
+  let mut lp = lp.clone();
+  providers.iter().fold((0, 0), |(base, quote), provider| {
+    let (base_fee, quote_fee) = provider.clone().claim_fee(&mut lp).expect("fee owed overflow");
+    (base + base_fee, quote + quote_fee)
+  })
+}
+
+fn share_of(provider: &LiquidityProvider, share_bps: u16) -> u64 {
+  (provider.share as u128 * share_bps as u128 / 10_000) as u64
+}
+
+
+#[derive(Clone)]
+struct FixedRateModel {
+  lp: FixedRateLP,
+  lp_key: Pubkey,
+  config: ProgramConfig,
+  providers: Vec<LiquidityProvider>,
+  share_supply: u64,
+  base: Balances,
+  quote: Balances,
+}
+
+impl FixedRateModel {
+  fn new(rate: u32, fee_bps: u16, protocol_fee_share_bps: u16) -> Self {
+    let mut lp = FixedRateLP::default();
+    lp.init(LpInitParams {
+      authority: Pubkey::new_unique(),
+      token_base: Pubkey::new_unique(),
+      token_quote: Pubkey::new_unique(),
+      pool_index: 0,
+      fixed_rate: rate,
+      fee_bps,
+      bump: 0,
+      liquidity_bump: 0,
+      fee_bump: 0,
+      share_mint_bump: 0,
+    }).unwrap();
+
+    Self {
+      lp,
+      lp_key: Pubkey::new_unique(),
+      config: config(protocol_fee_share_bps),
+      providers: providers(),
+      share_supply: 0,
+      base: Balances::default(),
+      quote: Balances::default(),
+    }
+  }
+
+  fn pair(&self, base_to_quote: bool) -> (Pubkey, Pubkey) {
+    if base_to_quote {
+      (self.lp.token_base, self.lp.token_quote)
+    } else {
+      (self.lp.token_quote, self.lp.token_base)
+    }
+  }
+
+  fn apply(&mut self, op: Op) {
+    let before = self.clone();
+    if self.try_apply(op).is_err() {
+      // the transaction reverts
+      *self = before;
+    }
+  }
+
+  /// Same calls, in the same order, as the instructions
+  fn try_apply(&mut self, op: Op) -> anchor_lang::Result<()> {
+    match op {
+      Op::AddLiquidity { user, base, quote } => {
+        let share = self.lp.add_liquidity(base, quote, self.share_supply)?;
+        let provider = &mut self.providers[user];
+        let owner = provider.owner;
+        provider.deposit(&mut self.lp, self.lp_key, owner, 0, share)?;
+
+        self.base.pay_in(base);
+        self.quote.pay_in(quote);
+        self.share_supply += share;
+      }
+      Op::Swap { base_to_quote, amount } => {
+        let (from, to) = self.pair(base_to_quote);
+        let (swap_dir, from_amount, to_amount_without_fee, fee) = self.lp.preview_swap(from, to, amount)?;
+        assert_eq!(from_amount, amount);
+        self.settle_swap(swap_dir, from_amount, to_amount_without_fee, fee)?;
+      }
+      Op::SwapExactOut { base_to_quote, amount } => {
+        let (from, to) = self.pair(base_to_quote);
+        let (swap_dir, from_amount, to_amount_without_fee, fee) = self.lp.preview_swap_exact_out(from, to, amount)?;
+        assert!(fee <= to_amount_without_fee, "fee {} > output {}", fee, to_amount_without_fee);
+        assert_eq!(to_amount_without_fee - fee, amount, "exact out must pay exactly the requested amount");
+        self.settle_swap(swap_dir, from_amount, to_amount_without_fee, fee)?;
+      }
+      Op::WithdrawLiquidity { user, share_bps } => {
+        let share = share_of(&self.providers[user], share_bps);
+        self.providers[user].withdraw(&mut self.lp, share)?;
+        let (base_amount, quote_amount) = self.lp.withdraw_liquidity(share, self.share_supply)?;
+
+        self.base.withdraw(base_amount);
+        self.quote.withdraw(quote_amount);
+        self.share_supply -= share;
+      }
+      Op::ClaimFee { user } => {
+        let (base_fee, quote_fee) = self.providers[user].claim_fee(&mut self.lp)?;
+        self.base.claim(base_fee);
+        self.quote.claim(quote_fee);
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Same moves as instructions/swap.rs
+  fn settle_swap(&mut self, swap_dir: SwapDir, from_amount: u64, to_amount_without_fee: u64, fee: u64) -> anchor_lang::Result<()> {
+    assert!(fee <= to_amount_without_fee, "fee {} > output {}", fee, to_amount_without_fee);
+
+    self.lp.swap(&swap_dir, from_amount, to_amount_without_fee)?;
+    let to_amount = to_amount_without_fee - fee;
+
+    let (lp_fee, protocol_fee) = self.config.split_swap_fee(fee);
+    assert!(protocol_fee <= fee);
+    assert_eq!(lp_fee + protocol_fee, fee);
+
+    let (from, to) = match swap_dir {
+      SwapDir::BaseToQuote => (&mut self.base, &mut self.quote),
+      SwapDir::QuoteToBase => (&mut self.quote, &mut self.base),
+    };
+    from.pay_in(from_amount);
+    to.pay_out(to_amount, lp_fee, protocol_fee);
+
+    self.lp.accrue_fee(&swap_dir, lp_fee, self.share_supply)
+  }
+
+  fn pool_value(&self) -> u128 {
+    self.lp.math().scaled_value(self.lp.amount_base, self.lp.amount_quote)
+  }
+
+  fn check(&self, prev_value: u128, prev_supply: u64) {
+    assert_eq!(self.lp.amount_base, self.base.liquidity, "base reserve drifted from the vault");
+    assert_eq!(self.lp.amount_quote, self.quote.liquidity, "quote reserve drifted from the vault");
+    assert_eq!(self.base.total(), 0, "base token created or destroyed");
+    assert_eq!(self.quote.total(), 0, "quote token created or destroyed");
+
+    let provided: u64 = self.providers.iter().map(|provider| provider.share).sum();
+    assert_eq!(provided, self.share_supply, "share supply drifted from the provider records");
+
+    let (base_owed, quote_owed) = fee_owed(&self.lp, &self.providers);
+    assert!(base_owed <= self.base.lp_fee, "providers are owed {} base fee, lp_fee holds {}", base_owed, self.base.lp_fee);
+    assert!(quote_owed <= self.quote.lp_fee, "providers are owed {} quote fee, lp_fee holds {}", quote_owed, self.quote.lp_fee);
+
+    // value / share never decreases: value * prev_supply >= prev_value * supply
+    let value = self.pool_value();
+    if prev_supply > 0 && self.share_supply > 0 {
+      assert!(
+        value * prev_supply as u128 >= prev_value * self.share_supply as u128,
+        "value per share decreased: {}/{} -> {}/{}", prev_value, prev_supply, value, self.share_supply,
+      );
+    }
+    if self.share_supply == 0 {
+      assert_eq!(value, 0, "liquidity left without any share");
+    }
+  }
+}
+
+
+#[derive(Clone, Copy, Debug)]
+enum CpOp {
+  AddLiquidity { user: usize, max_base: u64, max_quote: u64 },
+  Swap { base_to_quote: bool, amount: u64 },
+  WithdrawLiquidity { user: usize, share_bps: u16 },
+  ClaimFee { user: usize },
+}
+
+///
+/// ConstantProductLP holds SOL as lamports of lp_liquidity, lp_fee and treasury,
+/// so base balances are lamports, rent-exempt minimum included
+///
+#[derive(Clone)]
+struct CpModel {
+  lp: ConstantProductLP,
+  lp_key: Pubkey,
+  config: ProgramConfig,
+  providers: Vec<LiquidityProvider>,
+  share_supply: u64,
+  rent_exempt: u64,
+  treasury_rent_exempt: u64,
+  base: Balances,
+  quote: Balances,
+}
+
+impl CpModel {
+  fn new(fee_bps: u16, protocol_fee_share_bps: u16) -> Self {
+    let mut lp = ConstantProductLP::default();
+    lp.init(CpLpInitParams {
+      authority: Pubkey::new_unique(),
+      token_base: spl_token::native_mint::id(),
+      token_quote: Pubkey::new_unique(),
+      fee_bps,
+      bump: 0,
+      liquidity_bump: 0,
+      fee_bump: 0,
+      share_mint_bump: 0,
+    }).unwrap();
+    let rent = Rent::default();
+    let rent_exempt = rent.minimum_balance(ConstantProductLP::SOL_HOLDER_SIZE);
+    let treasury_rent_exempt = rent.minimum_balance(ProgramConfig::SOL_HOLDER_SIZE);
+
+    Self {
+      lp,
+      lp_key: Pubkey::new_unique(),
+      config: config(protocol_fee_share_bps),
+      providers: providers(),
+      share_supply: 0,
+      rent_exempt,
+      treasury_rent_exempt,
+      base: Balances { liquidity: rent_exempt, lp_fee: rent_exempt, treasury: treasury_rent_exempt, user: 0 },
+      quote: Balances::default(),
+    }
+  }
+
+  fn apply(&mut self, op: CpOp) {
+    let before = self.clone();
+    if self.try_apply(op).is_err() {
+      *self = before;
+    }
+  }
+
+  /// Same calls, in the same order, as the cp_* instructions
+  fn try_apply(&mut self, op: CpOp) -> anchor_lang::Result<()> {
+    match op {
+      CpOp::AddLiquidity { user, max_base, max_quote } => {
+        let (base_amount, quote_amount, share) = self.lp.add_liquidity(max_base, max_quote, self.share_supply)?;
+        assert!(base_amount <= max_base && quote_amount <= max_quote, "took more than the user allowed");
+        let provider = &mut self.providers[user];
+        let owner = provider.owner;
+        provider.deposit(&mut self.lp, self.lp_key, owner, 0, share)?;
+
+        self.base.pay_in(base_amount);
+        self.quote.pay_in(quote_amount);
+        self.share_supply += share;
+      }
+      CpOp::Swap { base_to_quote, amount } => {
+        let (from, to) = if base_to_quote {
+          (self.lp.token_base, self.lp.token_quote)
+        } else {
+          (self.lp.token_quote, self.lp.token_base)
+        };
+        let k_before = self.lp.reserve_base as u128 * self.lp.reserve_quote as u128;
+
+        let (swap_dir, from_amount, to_amount_without_fee, fee) = self.lp.preview_swap(from, to, amount)?;
+        assert!(fee <= to_amount_without_fee, "fee {} > output {}", fee, to_amount_without_fee);
+        self.lp.swap(&swap_dir, from_amount, to_amount_without_fee)?;
+        let to_amount = to_amount_without_fee - fee;
+
+        let (lp_fee, protocol_fee) = self.config.split_swap_fee(fee);
+        self.lp.accrue_fee(&swap_dir, lp_fee, self.share_supply)?;
+
+        let k_after = self.lp.reserve_base as u128 * self.lp.reserve_quote as u128;
+        assert!(k_after >= k_before, "k decreased: {} -> {}", k_before, k_after);
+
+        // SOL out is paid straight from lp_liquidity lamports to the user, lp_fee and treasury
+        let (from, to) = match swap_dir {
+          SwapDir::BaseToQuote => (&mut self.base, &mut self.quote),
+          SwapDir::QuoteToBase => (&mut self.quote, &mut self.base),
+        };
+        from.pay_in(from_amount);
+        to.pay_out(to_amount, lp_fee, protocol_fee);
+      }
+      CpOp::WithdrawLiquidity { user, share_bps } => {
+        let share = share_of(&self.providers[user], share_bps);
+        self.providers[user].withdraw(&mut self.lp, share)?;
+        let (base_amount, quote_amount) = self.lp.withdraw_liquidity(share, self.share_supply)?;
+
+        self.base.withdraw(base_amount);
+        self.quote.withdraw(quote_amount);
+        self.share_supply -= share;
+      }
+      CpOp::ClaimFee { user } => {
+        let (base_fee, quote_fee) = self.providers[user].claim_fee(&mut self.lp)?;
+        self.base.claim(base_fee);
+        self.quote.claim(quote_fee);
+      }
+    }
+
+    Ok(())
+  }
+
+  fn check(&self) {
+    let initial_lamports = 2 * self.rent_exempt as i128 + self.treasury_rent_exempt as i128;
+    assert_eq!(self.base.total(), initial_lamports, "lamports created or destroyed");
+    assert_eq!(self.quote.total(), 0, "quote token created or destroyed");
+
+    assert_eq!(self.base.liquidity, self.rent_exempt + self.lp.reserve_base, "rent-exempt lamports counted as liquidity");
+    assert_eq!(self.lp.reserve_quote, self.quote.liquidity, "quote reserve drifted from the vault");
+    assert!(self.base.lp_fee >= self.rent_exempt, "lp_fee is no longer rent-exempt");
+    assert!(self.base.treasury >= self.treasury_rent_exempt, "treasury is no longer rent-exempt");
+
+    let provided: u64 = self.providers.iter().map(|provider| provider.share).sum();
+    assert_eq!(provided, self.share_supply, "share supply drifted from the provider records");
+
+    let (base_owed, quote_owed) = fee_owed(&self.lp, &self.providers);
+    assert!(
+      base_owed <= self.base.lp_fee - self.rent_exempt,
+      "providers are owed {} lamports, lp_fee holds {} above rent", base_owed, self.base.lp_fee - self.rent_exempt,
+    );
+    assert!(quote_owed <= self.quote.lp_fee, "providers are owed {} quote fee, lp_fee holds {}", quote_owed, self.quote.lp_fee);
+  }
+}
+
+
+/// Small amounts hit the rounding edge cases, large ones the reserve exhaustion
+fn amount() -> impl Strategy<Value = u64> {
+  prop_oneof![0..100_u64, 0..=MAX_AMOUNT]
+}
+
+fn user() -> impl Strategy<Value = usize> {
+  0..USERS
+}
+
+fn op() -> impl Strategy<Value = Op> {
+  prop_oneof![
+    (user(), amount(), amount()).prop_map(|(user, base, quote)| Op::AddLiquidity { user, base, quote }),
+    (any::<bool>(), amount()).prop_map(|(base_to_quote, amount)| Op::Swap { base_to_quote, amount }),
+    (any::<bool>(), amount()).prop_map(|(base_to_quote, amount)| Op::SwapExactOut { base_to_quote, amount }),
+    (user(), 1..=10_000_u16).prop_map(|(user, share_bps)| Op::WithdrawLiquidity { user, share_bps }),
+    user().prop_map(|user| Op::ClaimFee { user }),
+  ]
+}
+
+fn cp_op() -> impl Strategy<Value = CpOp> {
+  prop_oneof![
+    (user(), amount(), amount()).prop_map(|(user, max_base, max_quote)| CpOp::AddLiquidity { user, max_base, max_quote }),
+    (any::<bool>(), amount()).prop_map(|(base_to_quote, amount)| CpOp::Swap { base_to_quote, amount }),
+    (user(), 1..=10_000_u16).prop_map(|(user, share_bps)| CpOp::WithdrawLiquidity { user, share_bps }),
+    user().prop_map(|user| CpOp::ClaimFee { user }),
+  ]
+}
+
+proptest! {
+  #![proptest_config(ProptestConfig::with_cases(512))]
+
+  #[test]
+  fn fixed_rate_lp_sequence_keeps_invariants(
+    rate in 1..=MAX_RATE,
+    fee_bps in 0..=LP_MAX_FEE_BPS,
+    protocol_fee_share_bps in 0..=MAX_PROTOCOL_FEE_SHARE_BPS,
+    ops in prop::collection::vec(op(), 1..64),
+  ) {
+    let mut model = FixedRateModel::new(rate, fee_bps, protocol_fee_share_bps);
+    for op in ops {
+      let (prev_value, prev_supply) = (model.pool_value(), model.share_supply);
+      model.apply(op);
+      model.check(prev_value, prev_supply);
+    }
+  }
+
+  #[test]
+  fn constant_product_lp_sequence_keeps_invariants(
+    fee_bps in 0..=LP_MAX_FEE_BPS,
+    protocol_fee_share_bps in 0..=MAX_PROTOCOL_FEE_SHARE_BPS,
+    ops in prop::collection::vec(cp_op(), 1..64),
+  ) {
+    let mut model = CpModel::new(fee_bps, protocol_fee_share_bps);
+    for op in ops {
+      model.apply(op);
+      model.check();
+    }
+  }
+
+  /// Full u64 range: pool_math must return an error, never panic or wrap
+  #[test]
+  fn preview_never_overflows(
+    rate in 1..=2_u32.pow(29),
+    fee_bps in 0..=LP_MAX_FEE_BPS,
+    amount_base: u64,
+    amount_quote: u64,
+    amount: u64,
+    share_supply: u64,
+    base_to_quote: bool,
+  ) {
+    let pool = FixedRatePool { rate, fee_bps, amount_base, amount_quote };
+    let swap_dir = if base_to_quote { pool_math::SwapDir::BaseToQuote } else { pool_math::SwapDir::QuoteToBase };
+    let to_reserve = if base_to_quote { amount_quote } else { amount_base };
+
+    if let Ok(preview) = pool.preview_swap(swap_dir, amount) {
+      prop_assert!(preview.fee <= preview.to_amount_without_fee);
+      prop_assert!(preview.to_amount_without_fee <= to_reserve);
+    }
+    if let Ok(preview) = pool.preview_swap_exact_out(swap_dir, amount) {
+      prop_assert!(preview.fee <= preview.to_amount_without_fee);
+      prop_assert!(preview.to_amount_without_fee <= to_reserve);
+      prop_assert_eq!(preview.to_amount(), amount);
+    }
+    prop_assert!(pool.swap_fee(amount) <= amount);
+    let _ = pool.deposit_share(amount, amount_quote, share_supply);
+  }
+}